@@ -45,6 +45,34 @@ static SERVER_COUNTER: AtomicUsize = AtomicUsize::new(0);
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
     env_logger::init_from_env(env_logger::Env::default().default_filter_or("debug"));
+
+    // консольные команды выгрузки / загрузки каталога, очистки корзины,
+    // пересчета цен элементов и заполнения slug тегов
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if crate::utils::transfer_command(&args)
+        || crate::utils::trash_command(&args)
+        || crate::utils::reprice_command(&args)
+        || crate::utils::tag_slugs_command(&args) {
+        return Ok(());
+    }
+
+    // раз в сутки окончательно удаляем то, что пролежало в корзине дольше срока
+    actix_web::rt::spawn(async {
+        let mut interval = actix_web::rt::time::interval(std::time::Duration::from_secs(60 * 60 * 24));
+        loop {
+            interval.tick().await;
+            let report = web::block(|| crate::utils::purge_trash(crate::utils::get_trash_retention_days())).await;
+            match report {
+                Ok(Ok(report)) => info!("purge trash: {:?}", report),
+                Ok(Err(e)) => error!("purge trash: {}", e),
+                Err(_) => (),
+            }
+            // у правил скидок есть сроки действия - скидки пересчитываем раз в сутки
+            if let Ok(changed) = web::block(crate::utils::refresh_price_acc).await {
+                info!("refresh price_acc: {} rows", changed);
+            }
+        }
+    });
     //let server = websocket::Server::new().start();
     let secret_key = Key::generate();
 
@@ -0,0 +1,279 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+};
+use serde::Serialize;
+use crate::schema::cart_items;
+use crate::models::{Item, ItemVariant};
+use crate::utils::{
+    establish_connection,
+    get_price_acc_values,
+};
+
+
+///////////
+// owner_types:
+// 1. пользователь куки
+// 2. зарегистрированный пользователь
+
+// больше этого в одну позицию корзины не положить
+pub const MAX_CART_COUNT: i16 = 99;
+
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+pub struct CartItem {
+    pub id:          i32,
+    pub owner_id:    i32,
+    pub owner_types: i16,
+    pub item_id:     i32,
+    pub variant_id:  Option<i32>,
+    pub count:       i16,
+    pub created:     chrono::NaiveDateTime,
+}
+
+// позиция корзины для шаблонов и ответов
+#[derive(Debug, Serialize)]
+pub struct CartLine {
+    pub id:           i32,
+    pub item_id:      i32,
+    pub variant_id:   Option<i32>,
+    pub title:        String,
+    pub sku:          Option<String>,
+    pub image:        String,
+    pub link:         String,
+    pub count:        i16,
+    pub price:        i32,  // за единицу
+    pub total:        i32,
+    pub is_available: bool, // товар активен и остатка хватает
+}
+
+#[derive(Debug, Serialize)]
+pub struct CartSummary {
+    pub count:     i32,         // всего единиц
+    pub price:     i32,         // сумма без скидки
+    pub price_acc: Option<i32>, // скидка
+    pub total:     i32,         // к оплате
+}
+
+impl CartItem {
+    pub fn get_owner_items(owner_id: i32, owner_types: i16) -> Vec<CartItem> {
+        let _connection = establish_connection();
+        return schema::cart_items::table
+            .filter(schema::cart_items::owner_id.eq(owner_id))
+            .filter(schema::cart_items::owner_types.eq(owner_types))
+            .order(schema::cart_items::created.asc())
+            .load::<CartItem>(&_connection)
+            .expect("E");
+    }
+
+    // товар (types 3), активный и не в корзине удаленных.
+    // вариант, если указан, должен относиться к товару.
+    // количество в позиции - не больше MAX_CART_COUNT и остатка варианта.
+    pub fn add (
+        owner_id:    i32,
+        owner_types: i16,
+        item_id:     i32,
+        variant_id:  Option<i32>,
+        count:       i16,
+    ) -> Result<(), String> {
+        use chrono::Duration;
+
+        let _connection = establish_connection();
+        let _item = schema::items::table
+            .filter(schema::items::id.eq(item_id))
+            .filter(schema::items::types.eq(3))
+            .filter(schema::items::is_active.eq(true))
+            .filter(schema::items::deleted_at.is_null())
+            .first::<Item>(&_connection);
+        if _item.is_err() || count < 1 {
+            return Err("Товар недоступен".to_string());
+        }
+        let mut _variant = None;
+        if variant_id.is_some() {
+            let _item_variant = schema::item_variants::table
+                .filter(schema::item_variants::id.eq(variant_id.unwrap()))
+                .filter(schema::item_variants::item_id.eq(item_id))
+                .first::<ItemVariant>(&_connection);
+            if _item_variant.is_err() {
+                return Err("Товар недоступен".to_string());
+            }
+            _variant = _item_variant.ok();
+        }
+
+        let _exists = schema::cart_items::table
+            .filter(schema::cart_items::owner_id.eq(owner_id))
+            .filter(schema::cart_items::owner_types.eq(owner_types))
+            .filter(schema::cart_items::item_id.eq(item_id))
+            .load::<CartItem>(&_connection)
+            .expect("E")
+            .into_iter()
+            .find(|c| c.variant_id == variant_id);
+        let current = _exists.as_ref().map(|c| c.count).unwrap_or(0);
+        let total = match current.checked_add(count) {
+            Some(total) => total,
+            None => return Err(CartItem::get_count_error(MAX_CART_COUNT)),
+        };
+        CartItem::check_count(total, _variant.as_ref())?;
+
+        if let Some(_cart_item) = _exists {
+            _cart_item.change_count(total);
+        }
+        else {
+            let _new = NewCartItem {
+                owner_id:    owner_id,
+                owner_types: owner_types,
+                item_id:     item_id,
+                variant_id:  variant_id,
+                count:       count,
+                created:     chrono::Local::now().naive_utc() + Duration::hours(3),
+            };
+            diesel::insert_into(schema::cart_items::table)
+                .values(&_new)
+                .execute(&_connection)
+                .expect("E.");
+        }
+        return Ok(());
+    }
+    fn get_count_error(max: i16) -> String {
+        "Можно заказать не больше ".to_string() + &max.to_string() + &" шт.".to_string()
+    }
+    fn check_count(count: i16, variant: Option<&ItemVariant>) -> Result<(), String> {
+        if count > MAX_CART_COUNT {
+            return Err(CartItem::get_count_error(MAX_CART_COUNT));
+        }
+        if let Some(_variant) = variant {
+            if count as i32 > _variant.stock {
+                return Err("На складе только ".to_string() + &_variant.stock.to_string() + &" шт.".to_string());
+            }
+        }
+        Ok(())
+    }
+    // новое количество из корзины: те же ограничения, что и при добавлении
+    pub fn set_count(&self, count: i16) -> Result<(), String> {
+        if count > 0 {
+            let _variant = match self.variant_id {
+                Some(variant_id) => ItemVariant::get_with_id(variant_id),
+                None => None,
+            };
+            CartItem::check_count(count, _variant.as_ref())?;
+        }
+        self.change_count(count);
+        Ok(())
+    }
+    // 0 и меньше - позиция удаляется
+    pub fn change_count(&self, count: i16) -> () {
+        let _connection = establish_connection();
+        if count < 1 {
+            diesel::delete(self)
+                .execute(&_connection)
+                .expect("E");
+        }
+        else {
+            diesel::update(self)
+                .set(schema::cart_items::count.eq(count))
+                .execute(&_connection)
+                .expect("E");
+        }
+    }
+    pub fn clear(owner_id: i32, owner_types: i16) -> () {
+        let _connection = establish_connection();
+        diesel::delete (
+            schema::cart_items::table
+                .filter(schema::cart_items::owner_id.eq(owner_id))
+                .filter(schema::cart_items::owner_types.eq(owner_types))
+            )
+            .execute(&_connection)
+            .expect("E");
+    }
+    // при входе переносим корзину пользователя куки в корзину аккаунта.
+    // позиции сверх лимита или остатка не переносятся
+    pub fn merge_owner(from_id: i32, from_types: i16, to_id: i32, to_types: i16) -> () {
+        for _cart_item in CartItem::get_owner_items(from_id, from_types).into_iter() {
+            CartItem::add(to_id, to_types, _cart_item.item_id, _cart_item.variant_id, _cart_item.count).ok();
+        }
+        CartItem::clear(from_id, from_types);
+    }
+
+    pub fn get_line(&self) -> Option<CartLine> {
+        let _connection = establish_connection();
+        let _item = schema::items::table
+            .filter(schema::items::id.eq(self.item_id))
+            .first::<Item>(&_connection);
+        if _item.is_err() {
+            return None;
+        }
+        let _item = _item.unwrap();
+        let mut is_available = _item.is_active && _item.deleted_at.is_none();
+
+        let mut title = _item.title.clone();
+        let mut sku = None;
+        let mut price = _item.price;
+        if self.variant_id.is_some() {
+            let _variant = schema::item_variants::table
+                .filter(schema::item_variants::id.eq(self.variant_id.unwrap()))
+                .first::<ItemVariant>(&_connection);
+            match _variant {
+                Ok(_variant) => {
+                    title = title + &" - ".to_string() + &_variant.title;
+                    sku = Some(_variant.sku.clone());
+                    price = _variant.price;
+                    is_available = is_available && _variant.stock >= self.count as i32;
+                },
+                Err(_) => is_available = false,
+            }
+        }
+
+        return Some(CartLine {
+            id:           self.id,
+            item_id:      _item.id,
+            variant_id:   self.variant_id,
+            title:        title,
+            sku:          sku,
+            image:        _item.get_image(),
+            link:         _item.get_link(),
+            count:        self.count,
+            price:        price,
+            total:        price * self.count as i32,
+            is_available: is_available,
+        });
+    }
+
+    pub fn get_cart(owner_id: i32, owner_types: i16) -> (Vec<CartLine>, CartSummary) {
+        let lines: Vec<CartLine> = CartItem::get_owner_items(owner_id, owner_types)
+            .iter()
+            .filter_map(|c| c.get_line())
+            .collect();
+        let summary = CartItem::get_summary(&lines);
+        (lines, summary)
+    }
+    // скидка считается от суммы всей корзины, как и у заказов
+    pub fn get_summary(lines: &Vec<CartLine>) -> CartSummary {
+        let mut count = 0;
+        let mut price = 0;
+        for line in lines.iter() {
+            count += line.count as i32;
+            price += line.total;
+        }
+        let price_acc = get_price_acc_values(&price, 3);
+        CartSummary {
+            count:     count,
+            price:     price,
+            price_acc: price_acc,
+            total:     price - price_acc.unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="cart_items"]
+pub struct NewCartItem {
+    pub owner_id:    i32,
+    pub owner_types: i16,
+    pub item_id:     i32,
+    pub variant_id:  Option<i32>,
+    pub count:       i16,
+    pub created:     chrono::NaiveDateTime,
+}
@@ -0,0 +1,158 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+};
+use serde::Serialize;
+use crate::schema::currencies;
+use crate::utils::{
+    establish_connection,
+    CurrencyForm,
+};
+
+
+// разбиваем число на разряды: 1234567 -> 1 234 567
+fn group_digits(value: u64, separator: &str) -> String {
+    let digits = value.to_string();
+    let mut result = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            result.push_str(separator);
+        }
+        result.push(c);
+    }
+    result
+}
+
+// сумма с символом валюты, округляется до целых.
+// ru: "1 234 567 ₽", en: "$1,234,567"
+pub fn format_amount(amount: f64, symbol: &str, l: u8) -> String {
+    let rounded = amount.round() as i64;
+    let sign = if rounded < 0 { "-" } else { "" };
+    if l == 2 {
+        format!("{}{}{}", sign, symbol, group_digits(rounded.unsigned_abs(), ","))
+    }
+    else {
+        format!("{}{}\u{a0}{}", sign, group_digits(rounded.unsigned_abs(), "\u{a0}"), symbol)
+    }
+}
+
+#[derive(Debug, Serialize, Identifiable, Queryable)]
+#[table_name="currencies"]
+pub struct Currency {
+    pub id:        i32,
+    pub code:      String,
+    pub name:      String,
+    pub name_en:   String,
+    pub symbol:    String,
+    pub rate:      f64,
+    pub is_base:   bool,
+    pub is_active: bool,
+    pub position:  i16,
+    pub updated:   chrono::NaiveDateTime,
+}
+
+impl Currency {
+    pub fn get_all() -> Vec<Currency> {
+        let _connection = establish_connection();
+        return schema::currencies::table
+            .order(schema::currencies::position.asc())
+            .load::<Currency>(&_connection)
+            .expect("E.");
+    }
+    pub fn get_active() -> Vec<Currency> {
+        let _connection = establish_connection();
+        return schema::currencies::table
+            .filter(schema::currencies::is_active.eq(true))
+            .order(schema::currencies::position.asc())
+            .load::<Currency>(&_connection)
+            .expect("E.");
+    }
+    pub fn get_with_id(id: i32) -> Option<Currency> {
+        let _connection = establish_connection();
+        return schema::currencies::table
+            .filter(schema::currencies::id.eq(id))
+            .first::<Currency>(&_connection)
+            .ok();
+    }
+    pub fn get_with_code(code: &str) -> Option<Currency> {
+        let _connection = establish_connection();
+        return schema::currencies::table
+            .filter(schema::currencies::code.eq(code.trim().to_uppercase()))
+            .filter(schema::currencies::is_active.eq(true))
+            .first::<Currency>(&_connection)
+            .ok();
+    }
+    pub fn get_name(&self, l: u8) -> String {
+        if l == 2 {
+            return self.name_en.clone();
+        }
+        self.name.clone()
+    }
+    // цены хранятся в рублях; rate - рублей за единицу валюты
+    pub fn convert(&self, price: i32) -> f64 {
+        if self.is_base || self.rate <= 0.0 {
+            return price as f64;
+        }
+        price as f64 / self.rate
+    }
+    pub fn format(&self, price: i32, l: u8) -> String {
+        format_amount(self.convert(price), &self.symbol, l)
+    }
+    pub fn create(form: CurrencyForm) -> Option<Currency> {
+        let _connection = establish_connection();
+        let new_currency = NewCurrency {
+            code:      form.code,
+            name:      form.name,
+            name_en:   form.name_en,
+            symbol:    form.symbol,
+            rate:      form.rate,
+            is_base:   false,
+            is_active: form.is_active,
+            position:  form.position,
+            updated:   chrono::Local::now().naive_utc() + chrono::Duration::hours(3),
+        };
+        return diesel::insert_into(schema::currencies::table)
+            .values(&new_currency)
+            .get_result::<Currency>(&_connection)
+            .ok();
+    }
+    // у базовой валюты курс всегда 1, код не меняется и выключить ее нельзя
+    pub fn edit(&self, form: CurrencyForm) -> bool {
+        let _connection = establish_connection();
+        let (code, rate, is_active) = match self.is_base {
+            true => (self.code.clone(), 1.0, true),
+            false => (form.code, form.rate, form.is_active),
+        };
+        return diesel::update(self)
+            .set((
+                schema::currencies::code.eq(code),
+                schema::currencies::name.eq(form.name),
+                schema::currencies::name_en.eq(form.name_en),
+                schema::currencies::symbol.eq(form.symbol),
+                schema::currencies::rate.eq(rate),
+                schema::currencies::is_active.eq(is_active),
+                schema::currencies::position.eq(form.position),
+                schema::currencies::updated.eq(chrono::Local::now().naive_utc() + chrono::Duration::hours(3)),
+            ))
+            .execute(&_connection)
+            .is_ok();
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="currencies"]
+pub struct NewCurrency {
+    pub code:      String,
+    pub name:      String,
+    pub name_en:   String,
+    pub symbol:    String,
+    pub rate:      f64,
+    pub is_base:   bool,
+    pub is_active: bool,
+    pub position:  i16,
+    pub updated:   chrono::NaiveDateTime,
+}
@@ -0,0 +1,295 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+    BoolExpressionMethods,
+    PgConnection,
+};
+use serde::{Serialize, Deserialize};
+use crate::schema::{
+    discount_rules,
+    promo_codes,
+};
+use crate::utils::{
+    establish_connection,
+    DiscountRuleForm,
+    PromoCodeForm,
+};
+
+
+fn get_now() -> chrono::NaiveDateTime {
+    chrono::Local::now().naive_utc() + chrono::Duration::hours(3)
+}
+// скидка в рублях от суммы
+fn get_percent_of(price: i32, percent: i16) -> i32 {
+    (price as i64 * percent as i64 / 100) as i32
+}
+
+#[derive(Debug, Serialize, Identifiable, Queryable)]
+#[table_name="discount_rules"]
+pub struct DiscountRule {
+    pub id:        i32,
+    pub title:     String,
+    pub threshold: i32,
+    pub percent:   i16,
+    pub types:     i16,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at:   Option<chrono::NaiveDateTime>,
+    pub is_active: bool,
+    pub created:   chrono::NaiveDateTime,
+}
+
+impl DiscountRule {
+    pub fn get_all() -> Vec<DiscountRule> {
+        let _connection = establish_connection();
+        return schema::discount_rules::table
+            .order((schema::discount_rules::types.asc(), schema::discount_rules::threshold.asc()))
+            .load::<DiscountRule>(&_connection)
+            .expect("E.");
+    }
+    pub fn get_with_id(id: i32) -> Option<DiscountRule> {
+        let _connection = establish_connection();
+        return schema::discount_rules::table
+            .filter(schema::discount_rules::id.eq(id))
+            .first::<DiscountRule>(&_connection)
+            .ok();
+    }
+    // лучшее действующее правило для суммы: порог включительно,
+    // правило для этого типа элементов или для любых (types 0)
+    pub fn get_for_price(price: i32, types: i16) -> Option<DiscountRule> {
+        let _connection = establish_connection();
+        let now = get_now();
+        return schema::discount_rules::table
+            .filter(schema::discount_rules::is_active.eq(true))
+            .filter(schema::discount_rules::threshold.le(price))
+            .filter(schema::discount_rules::types.eq(0).or(schema::discount_rules::types.eq(types)))
+            .filter(schema::discount_rules::starts_at.is_null().or(schema::discount_rules::starts_at.le(now)))
+            .filter(schema::discount_rules::ends_at.is_null().or(schema::discount_rules::ends_at.gt(now)))
+            .order((schema::discount_rules::percent.desc(), schema::discount_rules::threshold.desc()))
+            .first::<DiscountRule>(&_connection)
+            .ok();
+    }
+    pub fn get_amount(&self, price: i32) -> i32 {
+        get_percent_of(price, self.percent)
+    }
+    pub fn create(form: DiscountRuleForm) -> DiscountRule {
+        let _connection = establish_connection();
+        let new_rule = NewDiscountRule {
+            title:     form.title,
+            threshold: form.threshold,
+            percent:   form.percent,
+            types:     form.types,
+            starts_at: form.starts_at,
+            ends_at:   form.ends_at,
+            is_active: form.is_active,
+            created:   get_now(),
+        };
+        return diesel::insert_into(schema::discount_rules::table)
+            .values(&new_rule)
+            .get_result::<DiscountRule>(&_connection)
+            .expect("E.");
+    }
+    pub fn edit(&self, form: DiscountRuleForm) -> () {
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set((
+                schema::discount_rules::title.eq(form.title),
+                schema::discount_rules::threshold.eq(form.threshold),
+                schema::discount_rules::percent.eq(form.percent),
+                schema::discount_rules::types.eq(form.types),
+                schema::discount_rules::starts_at.eq(form.starts_at),
+                schema::discount_rules::ends_at.eq(form.ends_at),
+                schema::discount_rules::is_active.eq(form.is_active),
+            ))
+            .execute(&_connection)
+            .expect("E");
+    }
+    // в заказах остается id правила - поэтому не удаляем, а выключаем
+    pub fn disable(&self) -> () {
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::discount_rules::is_active.eq(false))
+            .execute(&_connection)
+            .expect("E");
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="discount_rules"]
+pub struct NewDiscountRule {
+    pub title:     String,
+    pub threshold: i32,
+    pub percent:   i16,
+    pub types:     i16,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at:   Option<chrono::NaiveDateTime>,
+    pub is_active: bool,
+    pub created:   chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Identifiable, Queryable)]
+#[table_name="promo_codes"]
+pub struct PromoCode {
+    pub id:        i32,
+    pub code:      String,
+    pub percent:   i16,
+    pub types:     i16,
+    pub min_price: i32,
+    pub max_uses:  i32,
+    pub uses:      i32,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at:   Option<chrono::NaiveDateTime>,
+    pub is_active: bool,
+    pub created:   chrono::NaiveDateTime,
+}
+
+impl PromoCode {
+    pub fn get_all() -> Vec<PromoCode> {
+        let _connection = establish_connection();
+        return schema::promo_codes::table
+            .order(schema::promo_codes::created.desc())
+            .load::<PromoCode>(&_connection)
+            .expect("E.");
+    }
+    pub fn get_with_id(id: i32) -> Option<PromoCode> {
+        let _connection = establish_connection();
+        return schema::promo_codes::table
+            .filter(schema::promo_codes::id.eq(id))
+            .first::<PromoCode>(&_connection)
+            .ok();
+    }
+    // коды храним в верхнем регистре, вводить можно как угодно
+    pub fn get_valid(code: &str, price: i32, types: i16) -> Option<PromoCode> {
+        let _connection = establish_connection();
+        let _code = schema::promo_codes::table
+            .filter(schema::promo_codes::code.eq(code.trim().to_uppercase()))
+            .first::<PromoCode>(&_connection)
+            .ok()?;
+        if _code.is_valid(price, types) {
+            return Some(_code);
+        }
+        return None;
+    }
+    pub fn is_valid(&self, price: i32, types: i16) -> bool {
+        let now = get_now();
+        return self.is_active
+            && price >= self.min_price
+            && (self.types == 0 || self.types == types)
+            && (self.max_uses == 0 || self.uses < self.max_uses)
+            && self.starts_at.map(|d| d <= now).unwrap_or(true)
+            && self.ends_at.map(|d| d > now).unwrap_or(true);
+    }
+    pub fn get_amount(&self, price: i32) -> i32 {
+        get_percent_of(price, self.percent)
+    }
+    // списываем использование при оформлении заказа; лимит проверяется
+    // в самом update, чтобы два заказа одновременно не превысили max_uses
+    pub fn use_code(_connection: &PgConnection, id: i32) -> Result<bool, diesel::result::Error> {
+        let updated = diesel::update (
+            schema::promo_codes::table
+                .filter(schema::promo_codes::id.eq(id))
+                .filter(schema::promo_codes::max_uses.eq(0).or(schema::promo_codes::uses.lt(schema::promo_codes::max_uses)))
+            )
+            .set(schema::promo_codes::uses.eq(schema::promo_codes::uses + 1))
+            .execute(_connection)?;
+        Ok(updated > 0)
+    }
+    pub fn create(form: PromoCodeForm) -> Option<PromoCode> {
+        let _connection = establish_connection();
+        let new_code = NewPromoCode {
+            code:      form.code.trim().to_uppercase(),
+            percent:   form.percent,
+            types:     form.types,
+            min_price: form.min_price,
+            max_uses:  form.max_uses,
+            uses:      0,
+            starts_at: form.starts_at,
+            ends_at:   form.ends_at,
+            is_active: form.is_active,
+            created:   get_now(),
+        };
+        return diesel::insert_into(schema::promo_codes::table)
+            .values(&new_code)
+            .get_result::<PromoCode>(&_connection)
+            .ok();
+    }
+    pub fn edit(&self, form: PromoCodeForm) -> bool {
+        let _connection = establish_connection();
+        return diesel::update(self)
+            .set((
+                schema::promo_codes::code.eq(form.code.trim().to_uppercase()),
+                schema::promo_codes::percent.eq(form.percent),
+                schema::promo_codes::types.eq(form.types),
+                schema::promo_codes::min_price.eq(form.min_price),
+                schema::promo_codes::max_uses.eq(form.max_uses),
+                schema::promo_codes::starts_at.eq(form.starts_at),
+                schema::promo_codes::ends_at.eq(form.ends_at),
+                schema::promo_codes::is_active.eq(form.is_active),
+            ))
+            .execute(&_connection)
+            .is_ok();
+    }
+    pub fn disable(&self) -> () {
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::promo_codes::is_active.eq(false))
+            .execute(&_connection)
+            .expect("E");
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="promo_codes"]
+pub struct NewPromoCode {
+    pub code:      String,
+    pub percent:   i16,
+    pub types:     i16,
+    pub min_price: i32,
+    pub max_uses:  i32,
+    pub uses:      i32,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at:   Option<chrono::NaiveDateTime>,
+    pub is_active: bool,
+    pub created:   chrono::NaiveDateTime,
+}
+
+// итоговая скидка: правило по сумме или промокод - что выгоднее.
+// не суммируются
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Discount {
+    pub rule_id:       Option<i32>,
+    pub promo_code_id: Option<i32>,
+    pub title:         Option<String>,
+    pub amount:        Option<i32>,
+}
+
+impl Discount {
+    pub fn get(price: i32, types: i16, promo: Option<&str>) -> Discount {
+        let mut discount = Discount {
+            rule_id:       None,
+            promo_code_id: None,
+            title:         None,
+            amount:        None,
+        };
+        if let Some(rule) = DiscountRule::get_for_price(price, types) {
+            discount.rule_id = Some(rule.id);
+            discount.amount = Some(rule.get_amount(price));
+            discount.title = Some(rule.title);
+        }
+        if let Some(_code) = promo.filter(|c| !c.trim().is_empty()).and_then(|c| PromoCode::get_valid(c, price, types)) {
+            if _code.get_amount(price) > discount.amount.unwrap_or(0) {
+                discount.rule_id = None;
+                discount.promo_code_id = Some(_code.id);
+                discount.amount = Some(_code.get_amount(price));
+                discount.title = Some("Промокод ".to_string() + &_code.code);
+            }
+        }
+        if discount.amount == Some(0) {
+            discount.amount = None;
+        }
+        discount
+    }
+}
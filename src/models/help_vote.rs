@@ -0,0 +1,170 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+};
+use serde::Serialize;
+use crate::schema::help_votes;
+use crate::models::Item;
+use crate::utils::{
+    establish_connection,
+    HelpVoteForm,
+};
+
+
+// оценка статьи помощи: "Было полезно?" да / нет + комментарий
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+pub struct HelpVote {
+    pub id:         i32,
+    pub item_id:    i32,
+    pub user_id:    i32,
+    pub is_helpful: bool,
+    pub comment:    Option<String>,
+    pub created:    chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HelpScore {
+    pub yes: i32,
+    pub no:  i32,
+}
+impl HelpScore {
+    // доля полезных оценок, %
+    pub fn get_percent(&self) -> i32 {
+        let all = self.yes + self.no;
+        if all == 0 {
+            return 0;
+        }
+        self.yes * 100 / all
+    }
+}
+
+// строка отчета по статьям помощи
+#[derive(Debug, Serialize)]
+pub struct HelpReport {
+    pub id:       i32,
+    pub title:    String,
+    pub link:     String,
+    pub score:    HelpScore,
+    pub view:     i32,
+    pub seconds:  i32,
+    pub comments: Vec<String>,
+}
+
+impl HelpVote {
+    // повторная оценка того же пользователя заменяет прежнюю
+    pub fn vote(item_id: i32, user_id: i32, form: HelpVoteForm) -> bool {
+        use chrono::Duration;
+
+        let _connection = establish_connection();
+        let _item = schema::items::table
+            .filter(schema::items::id.eq(item_id))
+            .filter(schema::items::types.eq(6))
+            .filter(schema::items::deleted_at.is_null())
+            .first::<Item>(&_connection);
+        if _item.is_err() {
+            return false;
+        }
+        let comment = form.comment
+            .map(|c| c.trim().chars().take(1000).collect::<String>());
+
+        let _exists = schema::help_votes::table
+            .filter(schema::help_votes::item_id.eq(item_id))
+            .filter(schema::help_votes::user_id.eq(user_id))
+            .first::<HelpVote>(&_connection);
+        if let Ok(_vote) = _exists {
+            diesel::update(&_vote)
+                .set((
+                    schema::help_votes::is_helpful.eq(form.is_helpful),
+                    schema::help_votes::comment.eq(comment),
+                ))
+                .execute(&_connection)
+                .expect("E");
+        }
+        else {
+            let _new = NewHelpVote {
+                item_id:    item_id,
+                user_id:    user_id,
+                is_helpful: form.is_helpful,
+                comment:    comment,
+                created:    chrono::Local::now().naive_utc() + Duration::hours(3),
+            };
+            diesel::insert_into(schema::help_votes::table)
+                .values(&_new)
+                .execute(&_connection)
+                .expect("E.");
+        }
+        return true;
+    }
+
+    pub fn get_score(item_id: i32) -> HelpScore {
+        let _connection = establish_connection();
+        let votes = schema::help_votes::table
+            .filter(schema::help_votes::item_id.eq(item_id))
+            .select(schema::help_votes::is_helpful)
+            .load::<bool>(&_connection)
+            .expect("E");
+        let yes = votes.iter().filter(|v| **v).count() as i32;
+        HelpScore {
+            yes: yes,
+            no:  votes.len() as i32 - yes,
+        }
+    }
+
+    // статьи с оценками, сначала наименее полезные
+    pub fn get_report() -> Vec<HelpReport> {
+        let _connection = establish_connection();
+        let _votes = schema::help_votes::table
+            .order(schema::help_votes::created.desc())
+            .load::<HelpVote>(&_connection)
+            .expect("E");
+        let mut ids = Vec::new();
+        for vote in _votes.iter() {
+            if !ids.contains(&vote.item_id) {
+                ids.push(vote.item_id);
+            }
+        }
+        let _items = schema::items::table
+            .filter(schema::items::id.eq_any(ids))
+            .filter(schema::items::types.eq(6))
+            .filter(schema::items::deleted_at.is_null())
+            .load::<Item>(&_connection)
+            .expect("E");
+
+        let mut report: Vec<HelpReport> = _items.iter().map(|_item| {
+            let item_votes: Vec<&HelpVote> = _votes.iter()
+                .filter(|v| v.item_id == _item.id)
+                .collect();
+            let yes = item_votes.iter().filter(|v| v.is_helpful).count() as i32;
+            HelpReport {
+                id:       _item.id,
+                title:    _item.title.clone(),
+                link:     _item.get_link(),
+                score:    HelpScore {
+                    yes: yes,
+                    no:  item_votes.len() as i32 - yes,
+                },
+                view:     _item.view,
+                seconds:  _item.seconds,
+                comments: item_votes.iter()
+                    .filter_map(|v| v.comment.clone())
+                    .collect(),
+            }
+        }).collect();
+        report.sort_by_key(|r| (r.score.get_percent(), -r.score.no));
+        report
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="help_votes"]
+pub struct NewHelpVote {
+    pub item_id:    i32,
+    pub user_id:    i32,
+    pub is_helpful: bool,
+    pub comment:    Option<String>,
+    pub created:    chrono::NaiveDateTime,
+}
@@ -7,10 +7,14 @@ use crate::diesel::{
     ExpressionMethods,
     NullableExpressionMethods,
     PgTextExpressionMethods,
+    BoolExpressionMethods,
+    BoxableExpression,
+    pg::Pg,
+    sql_types::Bool,
 };
 use serde::{Serialize,Deserialize};
 use crate::models::{
-    Tag, TechCategories, Serve,
+    Tag, TechCategories, Serve, ServeCategories,
     SmallTag, SmallFile, User,
 };
 
@@ -22,11 +26,14 @@ use crate::schema::{
 };
 use crate::utils::{
     establish_connection,
-    get_linguage_storage,
-    CategoriesForm
+    CategoriesForm,
+    ListFilter,
 };
 use crate::errors::Error;
 
+// условие видимости для выборок элементов - см. Item::get_visible_filter
+pub type ItemsFilter = Box<dyn BoxableExpression<items::table, Pg, SqlType = Bool>>;
+
 
 ///////////
 // types:
@@ -94,6 +101,8 @@ pub struct Blog {
     pub title:       String,
     pub created:     chrono::NaiveDateTime,
     pub description: Option<String>,
+    pub word_count:   i32,
+    pub reading_time: i16,
 }
 impl Blog {
     pub fn get_image(&self) -> String {
@@ -189,6 +198,8 @@ pub struct Wiki {
     pub title:       String,
     pub description: Option<String>,
     pub created:     chrono::NaiveDateTime,
+    pub word_count:   i32,
+    pub reading_time: i16,
 }
 impl Wiki {
     pub fn get_image(&self) -> String {
@@ -251,6 +262,9 @@ pub struct Help {
     pub content:   Option<String>,
 }
 impl Help {
+    pub fn get_score(&self) -> crate::models::HelpScore {
+        return crate::models::HelpVote::get_score(self.id);
+    }
     pub fn get_category(&self) -> SmallCat {
         use crate::schema::{
             category::dsl::category,
@@ -310,12 +324,12 @@ pub struct Categories {
     pub seconds:        i32,
     pub types:          i16,
     pub slug:           String,
+    pub deleted_at:     Option<chrono::NaiveDateTime>,
 }
 
 impl Categories {
     pub fn update_category_with_id(user: User, id: i32, form: CategoriesForm) -> i16 {
         let _connection = establish_connection();
-        let l = get_linguage_storage();
         let cat = schema::categories::table
             .filter(schema::categories::id.eq(id))
             .first::<Categories>(&_connection)
@@ -323,75 +337,41 @@ impl Categories {
         if user.perm < 60 {
             return 0;
         }
-        if l == 1 { 
-            diesel::update(&cat)
-                .set((
-                    schema::categories::name.eq(&form.name),
-                    schema::categories::description.eq(&form.description),
-                    schema::categories::position.eq(form.position),
-                    schema::categories::image.eq(&form.image),
-                    schema::categories::slug.eq(&form.slug),
-                ))
-                .execute(&_connection)
-                .expect("E");
-        }
-        else if l == 2 {
-            diesel::update(&cat)
-                .set((
-                    schema::categories::name_en.eq(&form.name),
-                    schema::categories::description_en.eq(&form.description),
-                    schema::categories::position.eq(form.position),
-                    schema::categories::image.eq(&form.image),
-                    schema::categories::slug.eq(&form.slug),
-                ))
-                .execute(&_connection)
-                .expect("E");
-        }
+        // форма присылает обе версии - русскую и английскую
+        diesel::update(&cat)
+            .set((
+                schema::categories::name.eq(&form.name),
+                schema::categories::name_en.eq(&form.name_en),
+                schema::categories::description.eq(&form.description),
+                schema::categories::description_en.eq(&form.description_en),
+                schema::categories::position.eq(form.position),
+                schema::categories::image.eq(&form.image),
+                schema::categories::slug.eq(&form.slug),
+            ))
+            .execute(&_connection)
+            .expect("E");
         return 1;
     }
     pub fn create(form: CategoriesForm) -> i16 {
         let _connection = establish_connection();
-        let l = get_linguage_storage();
-        if l == 1 {
-            let new_cat = NewCategories {
-                name:           form.name.clone(),
-                name_en:        "".to_string(),
-                description:    Some(form.description.clone()),
-                description_en: Some("".to_string()),
-                position:       form.position,
-                image:          Some(form.image.clone()),
-                count:          0,
-                view:           0,
-                height:         0.0,
-                seconds:        0,
-                types:          form.types,
-                slug:           form.slug,
-            };
-            diesel::insert_into(schema::categories::table)
-                .values(&new_cat)
-                .execute(&_connection)
-                .expect("E.");
-        }
-        else if l == 2 {
-            let new_cat = NewCategories {
-                name:           "".to_string(),
-                name_en:        form.name.clone(),
-                description:    Some("".to_string()),
-                description_en: Some(form.description.clone()),
-                position:       form.position,
-                image:          Some(form.image.clone()),
-                count:          0,
-                view:           0,
-                height:         0.0,
-                seconds:        0,
-                types:          form.types,
-                slug:           form.slug,
-            };
-            diesel::insert_into(schema::categories::table)
-                .values(&new_cat)
-                .execute(&_connection)
-                .expect("E.");
-        }
+        let new_cat = NewCategories {
+            name:           form.name.clone(),
+            name_en:        form.name_en.clone(),
+            description:    Some(form.description.clone()),
+            description_en: Some(form.description_en.clone()),
+            position:       form.position,
+            image:          Some(form.image.clone()),
+            count:          0,
+            view:           0,
+            height:         0.0,
+            seconds:        0,
+            types:          form.types,
+            slug:           form.slug,
+        };
+        diesel::insert_into(schema::categories::table)
+            .values(&new_cat)
+            .execute(&_connection)
+            .expect("E.");
         return 1;
     }
     pub fn get_tags(types: i16, l: u8) -> Vec<SmallTag> {
@@ -417,12 +397,22 @@ impl Categories {
         let mut prev: Option<FeaturedItem> = None;
         let mut next: Option<FeaturedItem> = None;
 
-        let _category_items = schema::category::table
+        // удаленные в корзину элементы связи с категорией сохраняют
+        let _deleted_items = items
+            .filter(schema::items::types.eq(item_types))
+            .filter(schema::items::deleted_at.is_not_null())
+            .select(schema::items::id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        let _category_items: Vec<i32> = schema::category::table
             .filter(schema::category::category_id.eq(self.id))
             .filter(schema::category::types.eq(item_types))
             .select(schema::category::item_id)
             .load::<i32>(&_connection)
-            .expect("E");
+            .expect("E")
+            .into_iter()
+            .filter(|i| !_deleted_items.contains(i))
+            .collect();
         let _category_items_len = _category_items.len();
         for (i, item) in _category_items.iter().enumerate().rev() {
             if item == &item_id {
@@ -432,7 +422,7 @@ impl Categories {
                         next = Some(items
                             .filter(schema::items::id.eq(_next.unwrap()))
                             .filter(schema::items::types.eq(item_types))
-                            .filter(schema::items::is_active.eq(true))
+                            .filter(Item::get_visible_filter(None))
                             .select((
                                 schema::items::slug,
                                 schema::items::title,
@@ -444,7 +434,7 @@ impl Categories {
                         next = Some(items
                             .filter(schema::items::id.eq(_next.unwrap()))
                             .filter(schema::items::types.eq(item_types))
-                            .filter(schema::items::is_active.eq(true))
+                            .filter(Item::get_visible_filter(None))
                             .select((
                                 schema::items::slug,
                                 schema::items::title_en,
@@ -459,7 +449,7 @@ impl Categories {
                         prev = Some(items
                             .filter(schema::items::id.eq(_prev.unwrap()))
                             .filter(schema::items::types.eq(item_types))
-                            .filter(schema::items::is_active.eq(true))
+                            .filter(Item::get_visible_filter(None))
                             .select((
                                 schema::items::slug,
                                 schema::items::title,
@@ -471,7 +461,7 @@ impl Categories {
                         prev = Some(items
                             .filter(schema::items::id.eq(_prev.unwrap()))
                             .filter(schema::items::types.eq(item_types))
-                            .filter(schema::items::is_active.eq(true))
+                            .filter(Item::get_visible_filter(None))
                             .select((
                                 schema::items::slug,
                                 schema::items::title_en,
@@ -503,33 +493,8 @@ impl Categories {
         limit:    i32,
         is_admin: bool,
         l:        u8,
+        filter:   &ListFilter,
     ) -> Result<(Vec<Blog>, i32), Error> {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Blog>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Categories::get_blogs(cat_id, limit.into(), step.into(), is_admin, l)?;
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Categories::get_blogs(cat_id, limit.into(), 0, is_admin, l)?;
-        }
-        if Categories::get_blogs(cat_id, 1, have_next.into(), is_admin, l)?.len() > 0 {
-            next_page_number = page + 1;
-        }
-        let _tuple = (object_list, next_page_number);
-        Ok(_tuple)
-    }
-    pub fn get_blogs (
-        cat_id:   i32,
-        limit:    i64,
-        offset:   i64,
-        is_admin: bool,
-        l:        u8,
-    ) -> Result<Vec<Blog>, Error> {
         let _connection = establish_connection();
         let ids = schema::category::table
             .filter(schema::category::category_id.eq(cat_id))
@@ -537,13 +502,22 @@ impl Categories {
             .select(schema::category::item_id)
             .load::<i32>(&_connection)
             .expect("E");
+        let (page_ids, next_page_number) = filter.get_page_ids(ids, is_admin, page, limit);
+        let object_list = Categories::get_blogs(&page_ids, filter.user.as_ref(), l)?;
+        return Ok((filter.order_list(object_list, &page_ids), next_page_number));
+    }
+    pub fn get_blogs (
+        ids:      &Vec<i32>,
+        user:     Option<&User>,
+        l:        u8,
+    ) -> Result<Vec<Blog>, Error> {
+        let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             if l == 1 {
                 return Ok(schema::items::table
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -552,6 +526,8 @@ impl Categories {
                         schema::items::title,
                         schema::items::created,
                         schema::items::description.nullable(),
+                        schema::items::word_count,
+                        schema::items::reading_time,
                     ))
                     .load::<Blog>(&_connection)
                     .expect("E."));
@@ -559,9 +535,7 @@ impl Categories {
             else if l == 2 {
                 return Ok(schema::items::table
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -570,6 +544,8 @@ impl Categories {
                         schema::items::title_en,
                         schema::items::created,
                         schema::items::description_en.nullable(),
+                        schema::items::word_count_en,
+                        schema::items::reading_time_en,
                     ))
                     .load::<Blog>(&_connection)
                     .expect("E."));
@@ -578,10 +554,8 @@ impl Categories {
             if l == 2 {
                 return Ok(schema::items::table
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -590,6 +564,8 @@ impl Categories {
                         schema::items::title,
                         schema::items::created,
                         schema::items::description.nullable(),
+                        schema::items::word_count,
+                        schema::items::reading_time,
                     ))
                     .load::<Blog>(&_connection)
                     .expect("E."));
@@ -597,10 +573,8 @@ impl Categories {
             else if l == 2 {
                 return Ok(schema::items::table
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -609,6 +583,8 @@ impl Categories {
                         schema::items::title_en,
                         schema::items::created,
                         schema::items::description_en.nullable(),
+                        schema::items::word_count_en,
+                        schema::items::reading_time_en,
                     ))
                     .load::<Blog>(&_connection)
                     .expect("E."));
@@ -622,52 +598,33 @@ impl Categories {
         limit:    i32,
         is_admin: bool,
         l:        u8,
+        filter:   &ListFilter,
     ) -> Result<(Vec<Service>, i32), Error> {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Service>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Categories::get_services(cat_id, limit.into(), step.into(), is_admin, l)?;
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Categories::get_services(cat_id, limit.into(), 0, is_admin, l)?;
-        }
-        if Categories::get_services(cat_id, 1, have_next.into(), is_admin, l)?.len() > 0 {
-            next_page_number = page + 1;
-        }
-
-        return Ok((object_list, next_page_number));
-    }
-    pub fn get_services (
-        cat_id:   i32,
-        limit:    i64,
-        offset:   i64,
-        is_admin: bool,
-        l:        u8,
-    ) -> Result<Vec<Service>, Error> {
-        use crate::schema::{
-            items::dsl::items,
-            category::dsl::category,
-        };
-
         let _connection = establish_connection();
-        let ids = category
+        let ids = schema::category::table
             .filter(schema::category::category_id.eq(cat_id))
             .filter(schema::category::types.eq(2))
             .select(schema::category::item_id)
             .load::<i32>(&_connection)
             .expect("E");
+        let (page_ids, next_page_number) = filter.get_page_ids(ids, is_admin, page, limit);
+        let object_list = Categories::get_services(&page_ids, filter.user.as_ref(), l)?;
+        return Ok((filter.order_list(object_list, &page_ids), next_page_number));
+    }
+    pub fn get_services (
+        ids:      &Vec<i32>,
+        user:     Option<&User>,
+        l:        u8,
+    ) -> Result<Vec<Service>, Error> {
+        use crate::schema::items::dsl::items;
+
+        let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             if l == 1 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -682,9 +639,7 @@ impl Categories {
             else if l == 2 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -700,10 +655,8 @@ impl Categories {
             if l == 1 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -718,10 +671,8 @@ impl Categories {
             else if l == 2 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -743,52 +694,33 @@ impl Categories {
         limit:    i32,
         is_admin: bool,
         l:        u8,
+        filter:   &ListFilter,
     ) -> Result<(Vec<Store>, i32), Error> {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Store>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Categories::get_stores(cat_id, limit.into(), step.into(), is_admin, l)?;
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Categories::get_stores(cat_id, limit.into(), 0, is_admin, l)?;
-        }
-        if Categories::get_stores(cat_id, 1, have_next.into(), is_admin, l)?.len() > 0 {
-            next_page_number = page + 1;
-        }
-
-        return Ok((object_list, next_page_number));
-    }
-    pub fn get_stores (
-        cat_id:   i32,
-        limit:    i64,
-        offset:   i64,
-        is_admin: bool,
-        l:        u8,
-    ) -> Result<Vec<Store>, Error> {
-        use crate::schema::{
-            items::dsl::items,
-            category::dsl::category,
-        };
-
         let _connection = establish_connection();
-        let ids = category
+        let ids = schema::category::table
             .filter(schema::category::category_id.eq(cat_id))
             .filter(schema::category::types.eq(3))
             .select(schema::category::item_id)
             .load::<i32>(&_connection)
             .expect("E");
+        let (page_ids, next_page_number) = filter.get_page_ids(ids, is_admin, page, limit);
+        let object_list = Categories::get_stores(&page_ids, filter.user.as_ref(), l)?;
+        return Ok((filter.order_list(object_list, &page_ids), next_page_number));
+    }
+    pub fn get_stores (
+        ids:      &Vec<i32>,
+        user:     Option<&User>,
+        l:        u8,
+    ) -> Result<Vec<Store>, Error> {
+        use crate::schema::items::dsl::items;
+
+        let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             if l == 1 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -805,9 +737,7 @@ impl Categories {
             else if l == 2 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -825,10 +755,8 @@ impl Categories {
             if l == 1 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -845,10 +773,8 @@ impl Categories {
             else if l == 2 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -872,52 +798,33 @@ impl Categories {
         limit:    i32,
         is_admin: bool,
         l:        u8,
+        filter:   &ListFilter,
     ) -> Result<(Vec<Wiki>, i32), Error> {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Wiki>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Categories::get_wikis(cat_id, limit.into(), step.into(), is_admin, l)?;
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Categories::get_wikis(cat_id, limit.into(), 0, is_admin, l)?;
-        }
-        if Categories::get_wikis(cat_id, 1, have_next.into(), is_admin, l)?.len() > 0 {
-            next_page_number = page + 1;
-        }
-
-        return Ok((object_list, next_page_number));
-    }
-    pub fn get_wikis (
-        cat_id:   i32,
-        limit:    i64,
-        offset:   i64,
-        is_admin: bool,
-        l:        u8,
-    ) -> Result<Vec<Wiki>, Error> {
-        use crate::schema::{
-            items::dsl::items,
-            category::dsl::category,
-        };
-
         let _connection = establish_connection();
-        let ids = category
+        let ids = schema::category::table
             .filter(schema::category::category_id.eq(cat_id))
             .filter(schema::category::types.eq(4))
             .select(schema::category::item_id)
             .load::<i32>(&_connection)
             .expect("E");
+        let (page_ids, next_page_number) = filter.get_page_ids(ids, is_admin, page, limit);
+        let object_list = Categories::get_wikis(&page_ids, filter.user.as_ref(), l)?;
+        return Ok((filter.order_list(object_list, &page_ids), next_page_number));
+    }
+    pub fn get_wikis (
+        ids:      &Vec<i32>,
+        user:     Option<&User>,
+        l:        u8,
+    ) -> Result<Vec<Wiki>, Error> {
+        use crate::schema::items::dsl::items;
+
+        let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             if l == 1 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -925,7 +832,9 @@ impl Categories {
                         schema::items::is_active,
                         schema::items::title,
                         schema::items::description.nullable(),
-                        schema::items::created
+                        schema::items::created,
+                        schema::items::word_count,
+                        schema::items::reading_time,
                     ))
                     .load::<Wiki>(&_connection)
                     .expect("E."));
@@ -933,9 +842,7 @@ impl Categories {
             else if l == 2 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -943,7 +850,9 @@ impl Categories {
                         schema::items::is_active,
                         schema::items::title_en,
                         schema::items::description_en.nullable(),
-                        schema::items::created
+                        schema::items::created,
+                        schema::items::word_count_en,
+                        schema::items::reading_time_en,
                     ))
                     .load::<Wiki>(&_connection)
                     .expect("E."));
@@ -952,10 +861,8 @@ impl Categories {
             if l == 1 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -963,7 +870,9 @@ impl Categories {
                         schema::items::is_active,
                         schema::items::title,
                         schema::items::description.nullable(),
-                        schema::items::created
+                        schema::items::created,
+                        schema::items::word_count,
+                        schema::items::reading_time,
                     ))
                     .load::<Wiki>(&_connection)
                     .expect("E."));
@@ -971,10 +880,8 @@ impl Categories {
             else if l == 2 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -982,7 +889,9 @@ impl Categories {
                         schema::items::is_active,
                         schema::items::title_en,
                         schema::items::description_en.nullable(),
-                        schema::items::created
+                        schema::items::created,
+                        schema::items::word_count_en,
+                        schema::items::reading_time_en,
                     ))
                     .load::<Wiki>(&_connection)
                     .expect("E."));
@@ -997,52 +906,33 @@ impl Categories {
         limit:    i32,
         is_admin: bool,
         l:        u8,
+        filter:   &ListFilter,
     ) -> Result<(Vec<Work>, i32), Error> {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Work>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Categories::get_works(cat_id, limit.into(), step.into(), is_admin, l)?;
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Categories::get_works(cat_id, limit.into(), 0, is_admin, l)?;
-        }
-        if Categories::get_works(cat_id, 1, have_next.into(), is_admin, l)?.len() > 0 {
-            next_page_number = page + 1;
-        }
-
-        return Ok((object_list, next_page_number));
-    }
-    pub fn get_works (
-        cat_id:   i32,
-        limit:    i64,
-        offset:   i64,
-        is_admin: bool,
-        l:        u8,
-    ) -> Result<Vec<Work>, Error> {
-        use crate::schema::{
-            items::dsl::items,
-            category::dsl::category,
-        };
-
         let _connection = establish_connection();
-        let ids = category
+        let ids = schema::category::table
             .filter(schema::category::category_id.eq(cat_id))
             .filter(schema::category::types.eq(5))
             .select(schema::category::item_id)
             .load::<i32>(&_connection)
             .expect("E");
+        let (page_ids, next_page_number) = filter.get_page_ids(ids, is_admin, page, limit);
+        let object_list = Categories::get_works(&page_ids, filter.user.as_ref(), l)?;
+        return Ok((filter.order_list(object_list, &page_ids), next_page_number));
+    }
+    pub fn get_works (
+        ids:      &Vec<i32>,
+        user:     Option<&User>,
+        l:        u8,
+    ) -> Result<Vec<Work>, Error> {
+        use crate::schema::items::dsl::items;
+
+        let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             if l == 1 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -1057,9 +947,7 @@ impl Categories {
             else if l == 2 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -1075,10 +963,8 @@ impl Categories {
             if l == 1 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -1093,10 +979,8 @@ impl Categories {
             else if l == 2 {
                 return Ok(items
                     .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::created.desc())
-                    .limit(limit)
-                    .offset(offset)
+                    .filter(schema::items::deleted_at.is_null())
+                    .filter(Item::get_visible_filter(user))
                     .select((
                         schema::items::id,
                         schema::items::slug,
@@ -1112,38 +996,35 @@ impl Categories {
         return Ok(Vec::new());
     }
 
+    // видимость проверяется по каждой статье (Item::is_visible), поэтому
+    // страница отрезается уже от отфильтрованного списка
     pub fn get_helps_list (
-        cat_id:   i32,
-        page:     i32,
-        limit:    i32,
-        is_admin: bool,
-        l:        u8,
+        cat_id: i32,
+        page:   i32,
+        limit:  i32,
+        user:   Option<User>,
+        key:    Option<String>,
+        l:      u8,
     ) -> Result<(Vec<Help>, i32), Error> {
         let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Help>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Categories::get_helps(cat_id, limit.into(), step.into(), is_admin, l)?;
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Categories::get_helps(cat_id, limit.into(), 0, is_admin, l)?;
-        }
-        if Categories::get_helps(cat_id, 1, have_next.into(), is_admin, l)?.len() > 0 {
+        let helps = Categories::get_helps(cat_id, user.as_ref(), key.as_deref(), l)?;
+        let offset = ((page.max(1) - 1) * limit) as usize;
+        if helps.len() > offset + limit as usize {
             next_page_number = page + 1;
         }
+        let object_list = helps
+            .into_iter()
+            .skip(offset)
+            .take(limit as usize)
+            .collect();
 
         return Ok((object_list, next_page_number));
     }
-    pub fn get_helps (  
-        cat_id:   i32,
-        limit:    i64,
-        offset:   i64,
-        is_admin: bool,
-        l:        u8,
+    pub fn get_helps (
+        cat_id: i32,
+        user:   Option<&User>,
+        key:    Option<&str>,
+        l:      u8,
     ) -> Result<Vec<Help>, Error> {
         use crate::schema::{
             items::dsl::items,
@@ -1151,79 +1032,28 @@ impl Categories {
         };
 
         let _connection = establish_connection();
-        let _items: Vec<Help>;
         let ids = category
             .filter(schema::category::category_id.eq(cat_id))
             .filter(schema::category::types.eq(6))
             .select(schema::category::item_id)
             .load::<i32>(&_connection)
             .expect("E");
-        if is_admin {
-            if l == 1 {
-                return Ok(items
-                    .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::position.asc())
-                    .limit(limit)
-                    .offset(offset)
-                    .select((
-                        schema::items::id,
-                        schema::items::is_active,
-                        schema::items::title,
-                        schema::items::content,
-                    ))
-                    .load::<Help>(&_connection)
-                    .expect("E."));
-            }
-            else if l == 2 {
-                return Ok(items
-                    .filter(schema::items::id.eq_any(ids))
-                    .order(schema::items::position.asc())
-                    .limit(limit)
-                    .offset(offset)
-                    .select((
-                        schema::items::id,
-                        schema::items::is_active,
-                        schema::items::title_en,
-                        schema::items::content_en,
-                    ))
-                    .load::<Help>(&_connection)
-                    .expect("E."));
-            }
-        } else {
-            if l == 1 {
-                return Ok(items
-                    .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::position.asc())
-                    .limit(limit)
-                    .offset(offset)
-                    .select((
-                        schema::items::id,
-                        schema::items::is_active,
-                        schema::items::title,
-                        schema::items::content,
-                    ))
-                    .load::<Help>(&_connection)
-                    .expect("E."));
-            }
-            else if l == 2 {
-                return Ok(items
-                    .filter(schema::items::id.eq_any(ids))
-                    .filter(schema::items::is_active.eq(true))
-                    .order(schema::items::position.asc())
-                    .limit(limit)
-                    .offset(offset)
-                    .select((
-                        schema::items::id,
-                        schema::items::is_active,
-                        schema::items::title_en,
-                        schema::items::content_en,
-                    ))
-                    .load::<Help>(&_connection)
-                    .expect("E."));
-            }
-        }
-        return Ok(Vec::new());
+        let _items = items
+            .filter(schema::items::id.eq_any(ids))
+            .filter(schema::items::deleted_at.is_null())
+            .order(schema::items::position.asc())
+            .load::<Item>(&_connection)
+            .expect("E.");
+        return Ok(_items
+            .into_iter()
+            .filter(|i| i.is_visible(user, key))
+            .map(|i| Help {
+                id:        i.id,
+                is_active: i.is_active,
+                title:     if l == 2 { i.title_en } else { i.title },
+                content:   if l == 2 { i.content_en } else { i.content },
+            })
+            .collect());
     }
 
     pub fn get_image(&self) -> String {
@@ -1239,6 +1069,7 @@ impl Categories {
         if l == 1 {
             return schema::categories::table
                 .filter(schema::categories::types.eq(types))
+                .filter(schema::categories::deleted_at.is_null())
                 .select((
                     schema::categories::name,
                     schema::categories::slug,
@@ -1252,6 +1083,7 @@ impl Categories {
         else if l == 2 {
             return schema::categories::table
                 .filter(schema::categories::types.eq(types))
+                .filter(schema::categories::deleted_at.is_null())
                 .select((
                     schema::categories::name_en,
                     schema::categories::slug,
@@ -1264,6 +1096,46 @@ impl Categories {
         }
         return Vec::new();
     }
+
+    // пересчитываем count по активным и не удаленным элементам
+    pub fn recount(&self) -> () {
+        let _connection = establish_connection();
+        let ids = schema::category::table
+            .filter(schema::category::category_id.eq(self.id))
+            .filter(schema::category::types.eq(self.types))
+            .select(schema::category::item_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        let count = schema::items::table
+            .filter(schema::items::id.eq_any(ids))
+            .filter(Item::get_visible_filter(None))
+            .filter(schema::items::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(&_connection)
+            .expect("E");
+        diesel::update(self)
+            .set(schema::categories::count.eq(count as i16))
+            .execute(&_connection)
+            .expect("E");
+    }
+    // в корзину. связи с элементами остаются до очистки корзины
+    pub fn soft_delete(&self) -> () {
+        use chrono::Duration;
+
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::categories::deleted_at.eq(chrono::Local::now().naive_utc() + Duration::hours(3)))
+            .execute(&_connection)
+            .expect("E");
+    }
+    pub fn restore(&self) -> () {
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::categories::deleted_at.eq(None::<chrono::NaiveDateTime>))
+            .execute(&_connection)
+            .expect("E");
+        self.recount();
+    }
 }
 
 #[derive(Insertable)]
@@ -1317,6 +1189,13 @@ pub struct Item {
     pub price_acc:      Option<i32>,
     pub types:          i16,
     pub slug:           String,
+    pub deleted_at:     Option<chrono::NaiveDateTime>,
+    pub word_count:      i32,
+    pub word_count_en:   i32,
+    pub reading_time:    i16,
+    pub reading_time_en: i16,
+    pub visibility:      i16,
+    pub share_key:       Option<String>,
 }
 
 impl Item {
@@ -1351,6 +1230,7 @@ impl Item {
         let photos = files
             .filter(schema::files::item_id.eq(self.id))
             .filter(schema::files::item_types.eq(self.types))
+            .filter(schema::files::deleted_at.is_null())
             .filter(schema::files::types.eq(1))
             .select((
                 schema::files::id, 
@@ -1362,6 +1242,7 @@ impl Item {
         let videos = files
             .filter(schema::files::item_id.eq(self.id))
             .filter(schema::files::item_types.eq(self.types))
+            .filter(schema::files::deleted_at.is_null())
             .filter(schema::files::types.eq(2))
             .select((
                 schema::files::id, 
@@ -1373,6 +1254,7 @@ impl Item {
         let audios = files
             .filter(schema::files::item_id.eq(self.id))
             .filter(schema::files::item_types.eq(self.types))
+            .filter(schema::files::deleted_at.is_null())
             .filter(schema::files::types.eq(3))
             .select((
                 schema::files::id, 
@@ -1384,6 +1266,7 @@ impl Item {
         let docs = files
             .filter(schema::files::item_id.eq(self.id))
             .filter(schema::files::item_types.eq(self.types))
+            .filter(schema::files::deleted_at.is_null())
             .filter(schema::files::types.eq(4))
             .select((
                 schema::files::id, 
@@ -1443,6 +1326,7 @@ impl Item {
 
         let _categories = categories
             .filter(schema::categories::id.eq_any(ids))
+            .filter(schema::categories::deleted_at.is_null())
             .select((
                 schema::categories::name,
                 schema::categories::slug,
@@ -1468,6 +1352,7 @@ impl Item {
 
         let _categories = categories
             .filter(schema::categories::id.eq_any(ids))
+            .filter(schema::categories::deleted_at.is_null())
             .load::<Categories>(&_connection)
             .expect("E");
         return _categories;
@@ -1498,6 +1383,7 @@ impl Item {
             .expect("E");
         let _tags = tags
             .filter(schema::tags::id.eq_any(_tag_items))
+            .filter(schema::tags::deleted_at.is_null())
             .load::<Tag>(&_connection)
             .expect("E");
         return _tags;
@@ -1514,6 +1400,7 @@ impl Item {
         if is_admin {
              return items
                 .filter(schema::items::types.eq(1))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -1525,13 +1412,16 @@ impl Item {
                     schema::items::title,
                     schema::items::created,
                     schema::items::description.nullable(),
+                    schema::items::word_count,
+                    schema::items::reading_time,
                 ))
                 .load::<Blog>(&_connection)
                 .expect("E.");
         } else {
             return items
                 .filter(schema::items::types.eq(1))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(None))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -1543,6 +1433,8 @@ impl Item {
                     schema::items::title,
                     schema::items::created,
                     schema::items::description.nullable(),
+                    schema::items::word_count,
+                    schema::items::reading_time,
                 ))
                 .load::<Blog>(&_connection)
                 .expect("E.");
@@ -1563,6 +1455,7 @@ impl Item {
                 .or_filter(schema::items::description.ilike(&q))
                 .or_filter(schema::items::content.ilike(&q))
                 .filter(schema::items::types.eq(1))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -1574,6 +1467,8 @@ impl Item {
                     schema::items::title,
                     schema::items::created,
                     schema::items::description.nullable(),
+                    schema::items::word_count,
+                    schema::items::reading_time,
                 ))
                 .load::<Blog>(&_connection)
                 .expect("E.");
@@ -1583,7 +1478,8 @@ impl Item {
                 .or_filter(schema::items::description.ilike(&q))
                 .or_filter(schema::items::content.ilike(&q))
                 .filter(schema::items::types.eq(1))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(None))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -1595,6 +1491,8 @@ impl Item {
                     schema::items::title,
                     schema::items::created,
                     schema::items::description.nullable(),
+                    schema::items::word_count,
+                    schema::items::reading_time,
                 ))
                 .load::<Blog>(&_connection)
                 .expect("E.");
@@ -1612,6 +1510,7 @@ impl Item {
         if is_admin {
              return items
                 .filter(schema::items::types.eq(2))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -1628,7 +1527,8 @@ impl Item {
         } else {
             return items
                 .filter(schema::items::types.eq(2))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(None))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -1660,6 +1560,7 @@ impl Item {
                 .or_filter(schema::items::description.ilike(&q))
                 .or_filter(schema::items::content.ilike(&q))
                 .filter(schema::items::types.eq(2))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -1679,7 +1580,8 @@ impl Item {
                 .or_filter(schema::items::description.ilike(&q))
                 .or_filter(schema::items::content.ilike(&q))
                 .filter(schema::items::types.eq(2))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(None))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -1707,6 +1609,7 @@ impl Item {
           if is_admin {
                return items
                   .filter(schema::items::types.eq(3))
+                  .filter(schema::items::deleted_at.is_null())
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1725,7 +1628,8 @@ impl Item {
           } else {
               return items
                   .filter(schema::items::types.eq(3))
-                  .filter(schema::items::is_active.eq(true))
+                  .filter(schema::items::deleted_at.is_null())
+                  .filter(Item::get_visible_filter(None))
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1758,6 +1662,7 @@ impl Item {
                   .or_filter(schema::items::description.ilike(&q))
                   .or_filter(schema::items::content.ilike(&q))
                   .filter(schema::items::types.eq(3))
+                  .filter(schema::items::deleted_at.is_null())
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1779,7 +1684,8 @@ impl Item {
                   .or_filter(schema::items::description.ilike(&q))
                   .or_filter(schema::items::content.ilike(&q))
                   .filter(schema::items::types.eq(3))
-                  .filter(schema::items::is_active.eq(true))
+                  .filter(schema::items::deleted_at.is_null())
+                  .filter(Item::get_visible_filter(None))
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1809,6 +1715,7 @@ impl Item {
           if is_admin {
                return items
                   .filter(schema::items::types.eq(5))
+                  .filter(schema::items::deleted_at.is_null())
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1825,7 +1732,8 @@ impl Item {
           } else {
               return items
                   .filter(schema::items::types.eq(5))
-                  .filter(schema::items::is_active.eq(true))
+                  .filter(schema::items::deleted_at.is_null())
+                  .filter(Item::get_visible_filter(None))
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1856,6 +1764,7 @@ impl Item {
                   .or_filter(schema::items::description.ilike(&q))
                   .or_filter(schema::items::content.ilike(&q))
                   .filter(schema::items::types.eq(5))
+                  .filter(schema::items::deleted_at.is_null())
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1875,7 +1784,8 @@ impl Item {
                   .or_filter(schema::items::description.ilike(&q))
                   .or_filter(schema::items::content.ilike(&q))
                   .filter(schema::items::types.eq(5))
-                  .filter(schema::items::is_active.eq(true))
+                  .filter(schema::items::deleted_at.is_null())
+                  .filter(Item::get_visible_filter(None))
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1903,6 +1813,7 @@ impl Item {
           if is_admin {
                return items
                   .filter(schema::items::types.eq(4))
+                  .filter(schema::items::deleted_at.is_null())
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1914,13 +1825,16 @@ impl Item {
                       schema::items::title,
                       schema::items::description.nullable(),
                       schema::items::created,
+                      schema::items::word_count,
+                      schema::items::reading_time,
                   ))
                   .load::<Wiki>(&_connection)
                   .expect("E.");
           } else {
               return items
                   .filter(schema::items::types.eq(4))
-                  .filter(schema::items::is_active.eq(true))
+                  .filter(schema::items::deleted_at.is_null())
+                  .filter(Item::get_visible_filter(None))
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1931,7 +1845,9 @@ impl Item {
                       schema::items::is_active,
                       schema::items::title,
                       schema::items::description.nullable(),
-                      schema::items::created
+                      schema::items::created,
+                      schema::items::word_count,
+                      schema::items::reading_time,
                   ))
                   .load::<Wiki>(&_connection)
                   .expect("E.");
@@ -1952,6 +1868,7 @@ impl Item {
                   .or_filter(schema::items::description.ilike(&q))
                   .or_filter(schema::items::content.ilike(&q))
                   .filter(schema::items::types.eq(4))
+                  .filter(schema::items::deleted_at.is_null())
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1962,7 +1879,9 @@ impl Item {
                       schema::items::is_active,
                       schema::items::title,
                       schema::items::description.nullable(),
-                      schema::items::created
+                      schema::items::created,
+                      schema::items::word_count,
+                      schema::items::reading_time,
                   ))
                   .load::<Wiki>(&_connection)
                   .expect("E.");
@@ -1972,7 +1891,8 @@ impl Item {
                   .or_filter(schema::items::description.ilike(&q))
                   .or_filter(schema::items::content.ilike(&q))
                   .filter(schema::items::types.eq(4))
-                  .filter(schema::items::is_active.eq(true))
+                  .filter(schema::items::deleted_at.is_null())
+                  .filter(Item::get_visible_filter(None))
                   .order(schema::items::created.desc())
                   .limit(limit)
                   .offset(offset)
@@ -1984,6 +1904,8 @@ impl Item {
                       schema::items::title,
                       schema::items::description.nullable(),
                       schema::items::created,
+                      schema::items::word_count,
+                      schema::items::reading_time,
                   ))
                   .load::<Wiki>(&_connection)
                   .expect("E.");
@@ -2001,6 +1923,7 @@ impl Item {
         if is_admin {
              return items
                 .filter(schema::items::types.eq(6))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2015,7 +1938,8 @@ impl Item {
         } else {
             return items
                 .filter(schema::items::types.eq(6))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(None))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2045,6 +1969,7 @@ impl Item {
                 .or_filter(schema::items::description.ilike(&q))
                 .or_filter(schema::items::content.ilike(&q))
                 .filter(schema::items::types.eq(6))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2061,7 +1986,7 @@ impl Item {
                 .filter(schema::items::title.ilike(&q))
                 .or_filter(schema::items::description.ilike(&q))
                 .or_filter(schema::items::content.ilike(&q))
-                .filter(schema::items::is_active.eq(true))
+                .filter(Item::get_visible_filter(None))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2080,39 +2005,28 @@ impl Item {
         page:  i32,
         limit: i32,
         ids:   &Vec<i32>,
-        is_admin: bool
+        is_admin: bool,
+        filter: &ListFilter,
     ) -> (Vec<Blog>, i32) {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Blog>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Item::get_blogs_for_ids(limit.into(), step.into(), &ids, is_admin);
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Item::get_blogs_for_ids(limit.into(), 0, &ids, is_admin);
-        }
-        if Item::get_blogs_for_ids(1, have_next.into(), &ids, is_admin).len() > 0 {
-            next_page_number = page + 1;
-        }
-        return (object_list, next_page_number);
+        let (page_ids, next_page_number) = filter.get_page_ids(ids.clone(), is_admin, page, limit);
+        let object_list = Item::get_blogs_for_ids(limit.into(), 0, &page_ids, filter.user.as_ref());
+        return (filter.order_list(object_list, &page_ids), next_page_number);
     }
 
     pub fn get_blogs_for_ids (
         limit:  i64,
         offset: i64,
         ids:    &Vec<i32>,
-        is_admin: bool
+        user:   Option<&User>
     ) -> Vec<Blog> {
         use crate::schema::items::dsl::items;
 
         let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             return items
                 .filter(schema::items::id.eq_any(ids))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2124,6 +2038,8 @@ impl Item {
                     schema::items::title,
                     schema::items::created,
                     schema::items::description.nullable(),
+                    schema::items::word_count,
+                    schema::items::reading_time,
                 ))
                 .load::<Blog>(&_connection)
                 .expect("E.");
@@ -2131,7 +2047,8 @@ impl Item {
         else {
             return items
                 .filter(schema::items::id.eq_any(ids))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(user))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2143,6 +2060,8 @@ impl Item {
                     schema::items::title,
                     schema::items::created,
                     schema::items::description.nullable(),
+                    schema::items::word_count,
+                    schema::items::reading_time,
                 ))
                 .load::<Blog>(&_connection)
                 .expect("E.");
@@ -2153,39 +2072,28 @@ impl Item {
         page:  i32,
         limit: i32,
         ids:   &Vec<i32>,
-        is_admin: bool
+        is_admin: bool,
+        filter: &ListFilter,
     ) -> (Vec<Service>, i32) {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Service>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Item::get_services_for_ids(limit.into(), step.into(), &ids, is_admin);
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Item::get_services_for_ids(limit.into(), 0, &ids, is_admin);
-        }
-        if Item::get_services_for_ids(1, have_next.into(), &ids, is_admin).len() > 0 {
-            next_page_number = page + 1;
-        }
-        return (object_list, next_page_number);
+        let (page_ids, next_page_number) = filter.get_page_ids(ids.clone(), is_admin, page, limit);
+        let object_list = Item::get_services_for_ids(limit.into(), 0, &page_ids, filter.user.as_ref());
+        return (filter.order_list(object_list, &page_ids), next_page_number);
     }
 
     pub fn get_services_for_ids (
         limit:  i64,
         offset: i64,
         ids:    &Vec<i32>,
-        is_admin: bool
+        user:   Option<&User>
     ) -> Vec<Service> {
         use crate::schema::items::dsl::items;
 
         let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             return items
                 .filter(schema::items::id.eq_any(ids))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2203,7 +2111,8 @@ impl Item {
         else {
             return items
                 .filter(schema::items::id.eq_any(ids))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(user))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2224,39 +2133,28 @@ impl Item {
         page:  i32,
         limit: i32,
         ids:   &Vec<i32>,
-        is_admin: bool
+        is_admin: bool,
+        filter: &ListFilter,
     ) -> (Vec<Store>, i32) {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Store>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Item::get_stores_for_ids(limit.into(), step.into(), &ids, is_admin);
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Item::get_stores_for_ids(limit.into(), 0, &ids, is_admin);
-        }
-        if Item::get_stores_for_ids(1, have_next.into(), &ids, is_admin).len() > 0 {
-            next_page_number = page + 1;
-        }
-        return (object_list, next_page_number);
+        let (page_ids, next_page_number) = filter.get_page_ids(ids.clone(), is_admin, page, limit);
+        let object_list = Item::get_stores_for_ids(limit.into(), 0, &page_ids, filter.user.as_ref());
+        return (filter.order_list(object_list, &page_ids), next_page_number);
     }
 
     pub fn get_stores_for_ids (
         limit:  i64,
         offset: i64,
         ids:    &Vec<i32>,
-        is_admin: bool
+        user:   Option<&User>
     ) -> Vec<Store> {
         use crate::schema::items::dsl::items;
 
         let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             return items
                 .filter(schema::items::id.eq_any(ids))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2276,7 +2174,8 @@ impl Item {
         else {
             return items
                 .filter(schema::items::id.eq_any(ids))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(user))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2299,39 +2198,28 @@ impl Item {
         page:  i32,
         limit: i32,
         ids:   &Vec<i32>,
-        is_admin: bool
+        is_admin: bool,
+        filter: &ListFilter,
     ) -> (Vec<Wiki>, i32) {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Wiki>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Item::get_wikis_for_ids(limit.into(), step.into(), &ids, is_admin);
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Item::get_wikis_for_ids(limit.into(), 0, &ids, is_admin);
-        }
-        if Item::get_wikis_for_ids(1, have_next.into(), &ids, is_admin).len() > 0 {
-            next_page_number = page + 1;
-        }
-        return (object_list, next_page_number);
+        let (page_ids, next_page_number) = filter.get_page_ids(ids.clone(), is_admin, page, limit);
+        let object_list = Item::get_wikis_for_ids(limit.into(), 0, &page_ids, filter.user.as_ref());
+        return (filter.order_list(object_list, &page_ids), next_page_number);
     }
 
     pub fn get_wikis_for_ids (
         limit:  i64,
         offset: i64,
         ids:    &Vec<i32>,
-        is_admin: bool
+        user:   Option<&User>
     ) -> Vec<Wiki> {
         use crate::schema::items::dsl::items;
 
         let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             return items
                 .filter(schema::items::id.eq_any(ids))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2343,6 +2231,8 @@ impl Item {
                     schema::items::title,
                     schema::items::description.nullable(),
                     schema::items::created,
+                    schema::items::word_count,
+                    schema::items::reading_time,
                 ))
                 .load::<Wiki>(&_connection)
                 .expect("E.");
@@ -2350,7 +2240,8 @@ impl Item {
         else {
             return items
                 .filter(schema::items::id.eq_any(ids))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(user))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2362,6 +2253,8 @@ impl Item {
                     schema::items::title,
                     schema::items::description.nullable(),
                     schema::items::created,
+                    schema::items::word_count,
+                    schema::items::reading_time,
                 ))
                 .load::<Wiki>(&_connection)
                 .expect("E.");
@@ -2372,39 +2265,28 @@ impl Item {
         page:  i32,
         limit: i32,
         ids:   &Vec<i32>,
-        is_admin: bool
+        is_admin: bool,
+        filter: &ListFilter,
     ) -> (Vec<Work>, i32) {
-        let mut next_page_number = 0;
-        let have_next: i32;
-        let object_list: Vec<Work>;
-
-        if page > 1 {
-            let step = (page - 1) * 20;
-            have_next = page * limit + 1;
-            object_list = Item::get_works_for_ids(limit.into(), step.into(), &ids, is_admin);
-        }
-        else {
-            have_next = limit + 1;
-            object_list = Item::get_works_for_ids(limit.into(), 0, &ids, is_admin);
-        }
-        if Item::get_works_for_ids(1, have_next.into(), &ids, is_admin).len() > 0 {
-            next_page_number = page + 1;
-        }
-        return (object_list, next_page_number);
+        let (page_ids, next_page_number) = filter.get_page_ids(ids.clone(), is_admin, page, limit);
+        let object_list = Item::get_works_for_ids(limit.into(), 0, &page_ids, filter.user.as_ref());
+        return (filter.order_list(object_list, &page_ids), next_page_number);
     }
 
     pub fn get_works_for_ids (
         limit:  i64,
         offset: i64,
         ids:    &Vec<i32>,
-        is_admin: bool
+        user:   Option<&User>
     ) -> Vec<Work> {
         use crate::schema::items::dsl::items;
 
         let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             return items
                 .filter(schema::items::id.eq_any(ids))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2422,7 +2304,8 @@ impl Item {
         else {
             return items
                 .filter(schema::items::id.eq_any(ids))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(user))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2443,7 +2326,7 @@ impl Item {
         page:  i32,
         limit: i32,
         ids:   &Vec<i32>,
-        is_admin: bool
+        user:  Option<&User>
     ) -> (Vec<Help>, i32) {
         let mut next_page_number = 0;
         let have_next: i32;
@@ -2452,13 +2335,13 @@ impl Item {
         if page > 1 {
             let step = (page - 1) * 20;
             have_next = page * limit + 1;
-            object_list = Item::get_helps_for_ids(limit.into(), step.into(), &ids, is_admin);
+            object_list = Item::get_helps_for_ids(limit.into(), step.into(), &ids, user);
         }
         else {
             have_next = limit + 1;
-            object_list = Item::get_helps_for_ids(limit.into(), 0, &ids, is_admin);
+            object_list = Item::get_helps_for_ids(limit.into(), 0, &ids, user);
         }
-        if Item::get_helps_for_ids(1, have_next.into(), &ids, is_admin).len() > 0 {
+        if Item::get_helps_for_ids(1, have_next.into(), &ids, user).len() > 0 {
             next_page_number = page + 1;
         }
         return (object_list, next_page_number);
@@ -2468,14 +2351,16 @@ impl Item {
         limit:  i64,
         offset: i64,
         ids:    &Vec<i32>,
-        is_admin: bool
+        user:   Option<&User>
     ) -> Vec<Help> {
         use crate::schema::items::dsl::items;
 
         let _connection = establish_connection();
+        let is_admin = user.map(|u| u.is_superuser()).unwrap_or(false);
         if is_admin {
             return items
                 .filter(schema::items::id.eq_any(ids))
+                .filter(schema::items::deleted_at.is_null())
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2491,7 +2376,8 @@ impl Item {
         else {
             return items
                 .filter(schema::items::id.eq_any(ids))
-                .filter(schema::items::is_active.eq(true))
+                .filter(schema::items::deleted_at.is_null())
+                .filter(Item::get_visible_filter(user))
                 .order(schema::items::created.desc())
                 .limit(limit)
                 .offset(offset)
@@ -2506,6 +2392,144 @@ impl Item {
         }
     }
 
+    pub fn get_variants(&self) -> Vec<crate::models::ItemVariant> {
+        return crate::models::ItemVariant::get_item_variants(self.id);
+    }
+    // адрес страницы элемента; у помощи - страница категории
+    pub fn get_link(&self) -> String {
+        let _cat_slug = match self.get_categories().first() {
+            Some(cat) => cat.slug.clone(),
+            None => "1".to_string(),
+        };
+        let prefix = match self.types {
+            1 => "/blog/",
+            2 => "/service/",
+            3 => "/store/",
+            4 => "/wiki/",
+            5 => "/work/",
+            _ => return "/helps/".to_string() + &_cat_slug + &"/".to_string(),
+        };
+        return prefix.to_string() + &_cat_slug + &"/".to_string() + &self.slug + &"/".to_string();
+    }
+    pub fn get_author(&self) -> Option<User> {
+        let _connection = establish_connection();
+        return schema::users::table
+            .filter(schema::users::id.eq(self.user_id))
+            .first::<User>(&_connection)
+            .ok();
+    }
+
+    // то же правило, что is_visible, для списков (без секретной ссылки):
+    // сотрудники видят все, гость - открытые всем активные элементы.
+    // все выборки по видимости (списки, поиск, API, теги, авторы, вики)
+    // идут через это условие
+    pub fn get_visible_filter(user: Option<&User>) -> ItemsFilter {
+        match user {
+            Some(user) if user.perm >= 10 => Box::new(schema::items::id.is_not_null()),
+            Some(user) => Box::new(schema::items::is_active.eq(true).and(
+                schema::items::visibility.eq_any(vec![1, 2])
+                    .or(schema::items::user_id.eq(user.id))
+                    .or(schema::items::id.eq_any(crate::models::ItemAccess::get_item_ids(user.id)))
+            )),
+            None => Box::new(schema::items::is_active.eq(true).and(schema::items::visibility.eq(1))),
+        }
+    }
+    // неактивные элементы видят только сотрудники; закрытые -
+    // авторизованные, выбранные пользователи и группы, либо по секретной ссылке
+    pub fn is_visible(&self, user: Option<&User>, key: Option<&str>) -> bool {
+        if let Some(user) = user {
+            if user.perm >= 10 {
+                return true;
+            }
+        }
+        if !self.is_active {
+            return false;
+        }
+        if self.visibility == 1 {
+            return true;
+        }
+        if key.is_some() && self.share_key.as_deref() == key {
+            return true;
+        }
+        match user {
+            Some(user) => self.visibility == 2
+                || user.id == self.user_id
+                || crate::models::ItemAccess::has_access(self.id, user.id),
+            None => false,
+        }
+    }
+    pub fn is_public(&self) -> bool {
+        self.is_visible(None, None)
+    }
+    pub fn get_share_link(&self) -> Option<String> {
+        self.share_key
+            .as_ref()
+            .map(|key| self.get_link() + &"?key=".to_string() + key)
+    }
+
+    // база знаний: родитель, путь от корня, дочерние статьи
+    pub fn get_wiki_parent(&self) -> Option<crate::utils::WikiLink> {
+        let _connection = establish_connection();
+        let parent_id = crate::models::WikiParent::get_parent_id(self.id)?;
+        return schema::items::table
+            .filter(schema::items::id.eq(parent_id))
+            .filter(schema::items::deleted_at.is_null())
+            .first::<Item>(&_connection)
+            .ok()
+            .map(|i| crate::utils::WikiLink {
+                title: i.title.clone(),
+                link:  i.get_link(),
+            });
+    }
+    pub fn get_wiki_path(&self) -> Vec<crate::utils::WikiLink> {
+        let _connection = establish_connection();
+        let ids = crate::models::WikiParent::get_ancestor_ids(self.id);
+        let _items = schema::items::table
+            .filter(schema::items::id.eq_any(&ids))
+            .filter(schema::items::deleted_at.is_null())
+            .load::<Item>(&_connection)
+            .expect("E");
+        return ids.iter()
+            .filter_map(|id| _items.iter().find(|i| i.id == *id))
+            .map(|i| crate::utils::WikiLink {
+                title: i.title.clone(),
+                link:  i.get_link(),
+            })
+            .collect();
+    }
+    pub fn get_wiki_children(&self, user: Option<&User>) -> Vec<crate::utils::WikiLink> {
+        let _connection = establish_connection();
+        let ids = crate::models::WikiParent::get_children_ids(self.id);
+        let l = crate::utils::get_linguage_storage();
+        return schema::items::table
+            .filter(schema::items::id.eq_any(ids))
+            .filter(Item::get_visible_filter(user))
+            .filter(schema::items::deleted_at.is_null())
+            .order(schema::items::position.asc())
+            .load::<Item>(&_connection)
+            .expect("E")
+            .iter()
+            .map(|i| crate::utils::WikiLink {
+                title: if l == 2 { i.title_en.clone() } else { i.title.clone() },
+                link:  i.get_link(),
+            })
+            .collect();
+    }
+    // текст статьи на языке пользователя с разобранными [[ссылками]] и оглавление
+    pub fn get_wiki_content(&self, user: Option<&User>) -> (String, Vec<crate::utils::TocItem>) {
+        let content = if crate::utils::get_linguage_storage() == 2 { &self.content_en } else { &self.content };
+        let content = crate::utils::render_wiki_links(content.as_deref().unwrap_or(""), user);
+        return crate::utils::get_wiki_toc(&content);
+    }
+    pub fn get_wiki_backlinks(&self, user: Option<&User>) -> Vec<crate::utils::WikiLink> {
+        return crate::utils::get_wiki_backlinks(self, user);
+    }
+    pub fn get_work_case(&self) -> Option<crate::models::WorkCase> {
+        return crate::models::WorkCase::get_for_item(self.id);
+    }
+    pub fn get_case_techs(&self) -> Vec<ServeCategories> {
+        return crate::models::WorkCase::get_techs(self.id);
+    }
     pub fn get_serves_ids(&self) -> Vec<i32> {
         use schema::serve_items::dsl::serve_items;
 
@@ -2592,6 +2616,65 @@ impl Item {
             .load::<i32>(&_connection)
             .expect("E");
     }
+
+    // в корзину. связи и файлы остаются, чтобы их можно было
+    // восстановить; счетчики категорий и тегов пересчитываем.
+    pub fn soft_delete(&self) -> () {
+        use chrono::Duration;
+
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::items::deleted_at.eq(chrono::Local::now().naive_utc() + Duration::hours(3)))
+            .execute(&_connection)
+            .expect("E");
+        for _category in self.get_categories_obj().iter() {
+            _category.recount();
+        }
+        for _tag in self.get_tags_obj().iter() {
+            _tag.recount();
+        }
+    }
+    pub fn restore(&self) -> () {
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::items::deleted_at.eq(None::<chrono::NaiveDateTime>))
+            .execute(&_connection)
+            .expect("E");
+
+        // пока элемент лежал в корзине, категории и теги могли
+        // быть окончательно удалены - убираем висячие связи.
+        let cat_ids = schema::categories::table
+            .select(schema::categories::id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        diesel::delete (
+            schema::category::table
+                .filter(schema::category::item_id.eq(self.id))
+                .filter(schema::category::types.eq(self.types))
+                .filter(diesel::dsl::not(schema::category::category_id.eq_any(cat_ids)))
+            )
+            .execute(&_connection)
+            .expect("E");
+        let tag_ids = schema::tags::table
+            .select(schema::tags::id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        diesel::delete (
+            schema::tags_items::table
+                .filter(schema::tags_items::item_id.eq(self.id))
+                .filter(schema::tags_items::types.eq(self.types))
+                .filter(diesel::dsl::not(schema::tags_items::tag_id.eq_any(tag_ids)))
+            )
+            .execute(&_connection)
+            .expect("E");
+
+        for _category in self.get_categories_obj().iter() {
+            _category.recount();
+        }
+        for _tag in self.get_tags_obj().iter() {
+            _tag.recount();
+        }
+    }
 }
 
 #[derive(Serialize, Insertable)]
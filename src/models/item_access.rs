@@ -0,0 +1,288 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+    Connection,
+    PgConnection,
+};
+use serde::Serialize;
+use crate::schema::{
+    item_access,
+    item_group_access,
+    user_groups,
+    user_group_members,
+};
+use crate::models::{Item, User};
+use crate::utils::{
+    establish_connection,
+    ItemAccessForm,
+    UserGroupForm,
+};
+
+
+// "имя1, имя2" -> отсортированный список без повторов
+fn get_names(names: &str) -> Vec<String> {
+    let mut names: Vec<String> = names
+        .split(",")
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+// пользователь, которому открыт элемент с visibility 3
+// (например, клиент видит базу знаний своего проекта)
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+#[table_name="item_access"]
+pub struct ItemAccess {
+    pub id:      i32,
+    pub item_id: i32,
+    pub user_id: i32,
+}
+
+impl ItemAccess {
+    pub fn has_access(item_id: i32, user_id: i32) -> bool {
+        ItemAccess::get_item_ids(user_id).contains(&item_id)
+    }
+    // элементы, открытые пользователю - ему самому или его группам
+    pub fn get_item_ids(user_id: i32) -> Vec<i32> {
+        let _connection = establish_connection();
+        let mut ids = schema::item_access::table
+            .filter(schema::item_access::user_id.eq(user_id))
+            .select(schema::item_access::item_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        let group_ids = schema::user_group_members::table
+            .filter(schema::user_group_members::user_id.eq(user_id))
+            .select(schema::user_group_members::group_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        ids.extend(schema::item_group_access::table
+            .filter(schema::item_group_access::group_id.eq_any(group_ids))
+            .select(schema::item_group_access::item_id)
+            .load::<i32>(&_connection)
+            .expect("E"));
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+    pub fn get_usernames(item_id: i32) -> Vec<String> {
+        let _connection = establish_connection();
+        let ids = schema::item_access::table
+            .filter(schema::item_access::item_id.eq(item_id))
+            .select(schema::item_access::user_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        return schema::users::table
+            .filter(schema::users::id.eq_any(ids))
+            .order(schema::users::username.asc())
+            .select(schema::users::username)
+            .load::<String>(&_connection)
+            .expect("E");
+    }
+    pub fn get_group_names(item_id: i32) -> Vec<String> {
+        let _connection = establish_connection();
+        let ids = schema::item_group_access::table
+            .filter(schema::item_group_access::item_id.eq(item_id))
+            .select(schema::item_group_access::group_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        return schema::user_groups::table
+            .filter(schema::user_groups::id.eq_any(ids))
+            .order(schema::user_groups::name.asc())
+            .select(schema::user_groups::name)
+            .load::<String>(&_connection)
+            .expect("E");
+    }
+
+    // видимость, списки пользователей и групп, секретная ссылка.
+    // false - если есть неизвестные username'ы или группы, тогда ничего не меняем.
+    pub fn save(item: &Item, form: ItemAccessForm) -> bool {
+        let _connection = establish_connection();
+        let names = get_names(&form.users);
+        let _users = schema::users::table
+            .filter(schema::users::username.eq_any(&names))
+            .load::<User>(&_connection)
+            .expect("E");
+        if _users.len() != names.len() {
+            return false;
+        }
+        let group_names = get_names(&form.groups);
+        let _groups = schema::user_groups::table
+            .filter(schema::user_groups::name.eq_any(&group_names))
+            .load::<UserGroup>(&_connection)
+            .expect("E");
+        if _groups.len() != group_names.len() {
+            return false;
+        }
+
+        let visibility = if [1, 2, 3].contains(&form.visibility) { form.visibility } else { 1 };
+        let share_key = match form.share {
+            1 => Some(uuid::Uuid::new_v4().to_simple().to_string()),
+            2 => None,
+            _ => item.share_key.clone(),
+        };
+        _connection.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::update(item)
+                .set((
+                    schema::items::visibility.eq(visibility),
+                    schema::items::share_key.eq(share_key),
+                ))
+                .execute(&_connection)?;
+
+            diesel::delete(schema::item_access::table.filter(schema::item_access::item_id.eq(item.id)))
+                .execute(&_connection)?;
+            for _user in _users.iter() {
+                diesel::insert_into(schema::item_access::table)
+                    .values(&NewItemAccess {
+                        item_id: item.id,
+                        user_id: _user.id,
+                    })
+                    .execute(&_connection)?;
+            }
+            diesel::delete(schema::item_group_access::table.filter(schema::item_group_access::item_id.eq(item.id)))
+                .execute(&_connection)?;
+            for _group in _groups.iter() {
+                diesel::insert_into(schema::item_group_access::table)
+                    .values(&NewItemGroupAccess {
+                        item_id:  item.id,
+                        group_id: _group.id,
+                    })
+                    .execute(&_connection)?;
+            }
+            Ok(())
+        }).expect("E");
+        return true;
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="item_access"]
+pub struct NewItemAccess {
+    pub item_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(Insertable)]
+#[table_name="item_group_access"]
+pub struct NewItemGroupAccess {
+    pub item_id:  i32,
+    pub group_id: i32,
+}
+
+// группа пользователей; элемент с visibility 3 можно открыть всей группе
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+#[table_name="user_groups"]
+pub struct UserGroup {
+    pub id:   i32,
+    pub name: String,
+}
+
+impl UserGroup {
+    pub fn get_all() -> Vec<UserGroup> {
+        let _connection = establish_connection();
+        return schema::user_groups::table
+            .order(schema::user_groups::name.asc())
+            .load::<UserGroup>(&_connection)
+            .expect("E.");
+    }
+    pub fn get_with_id(id: i32) -> Option<UserGroup> {
+        let _connection = establish_connection();
+        return schema::user_groups::table
+            .filter(schema::user_groups::id.eq(id))
+            .first::<UserGroup>(&_connection)
+            .ok();
+    }
+    pub fn get_usernames(&self) -> Vec<String> {
+        let _connection = establish_connection();
+        let ids = schema::user_group_members::table
+            .filter(schema::user_group_members::group_id.eq(self.id))
+            .select(schema::user_group_members::user_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        return schema::users::table
+            .filter(schema::users::id.eq_any(ids))
+            .order(schema::users::username.asc())
+            .select(schema::users::username)
+            .load::<String>(&_connection)
+            .expect("E");
+    }
+
+    // Err - текст ошибки для формы: неизвестные пользователи или занятое название
+    pub fn create(form: UserGroupForm) -> Result<(), String> {
+        let _connection = establish_connection();
+        let _users = UserGroup::get_users(&form.users)?;
+        _connection.transaction::<_, diesel::result::Error, _>(|| {
+            let _group = diesel::insert_into(schema::user_groups::table)
+                .values(&NewUserGroup { name: form.name.trim().to_string() })
+                .get_result::<UserGroup>(&_connection)?;
+            _group.set_members(&_connection, &_users)
+        }).map_err(|_| "Такая группа уже есть".to_string())
+    }
+    pub fn edit(&self, form: UserGroupForm) -> Result<(), String> {
+        let _connection = establish_connection();
+        let _users = UserGroup::get_users(&form.users)?;
+        _connection.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::update(self)
+                .set(schema::user_groups::name.eq(form.name.trim()))
+                .execute(&_connection)?;
+            self.set_members(&_connection, &_users)
+        }).map_err(|_| "Такая группа уже есть".to_string())
+    }
+    pub fn delete(&self) -> () {
+        let _connection = establish_connection();
+        _connection.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::delete(schema::user_group_members::table.filter(schema::user_group_members::group_id.eq(self.id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::item_group_access::table.filter(schema::item_group_access::group_id.eq(self.id)))
+                .execute(&_connection)?;
+            diesel::delete(self)
+                .execute(&_connection)?;
+            Ok(())
+        }).expect("E");
+    }
+
+    fn get_users(names: &str) -> Result<Vec<User>, String> {
+        let _connection = establish_connection();
+        let names = get_names(names);
+        let _users = schema::users::table
+            .filter(schema::users::username.eq_any(&names))
+            .load::<User>(&_connection)
+            .expect("E");
+        if _users.len() != names.len() {
+            return Err("Есть неизвестные пользователи".to_string());
+        }
+        Ok(_users)
+    }
+    fn set_members(&self, _connection: &PgConnection, users: &Vec<User>) -> Result<(), diesel::result::Error> {
+        diesel::delete(schema::user_group_members::table.filter(schema::user_group_members::group_id.eq(self.id)))
+            .execute(_connection)?;
+        for _user in users.iter() {
+            diesel::insert_into(schema::user_group_members::table)
+                .values(&NewUserGroupMember {
+                    group_id: self.id,
+                    user_id:  _user.id,
+                })
+                .execute(_connection)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="user_groups"]
+pub struct NewUserGroup {
+    pub name: String,
+}
+
+#[derive(Insertable)]
+#[table_name="user_group_members"]
+pub struct NewUserGroupMember {
+    pub group_id: i32,
+    pub user_id:  i32,
+}
@@ -57,6 +57,7 @@ pub struct File {
     pub position:       i16,
     pub view:           i32,
     pub seconds:        i32,
+    pub deleted_at:     Option<chrono::NaiveDateTime>,
 }
 impl File {
     pub fn update_file_with_id(user: User, file_id: i32, form: crate::utils::CategoriesForm) -> i16 {
@@ -89,6 +90,24 @@ impl File {
         }
         return 1;
     }
+
+    // в корзину. сам файл с диска удаляется только при очистке корзины
+    pub fn soft_delete(&self) -> () {
+        use chrono::Duration;
+
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::files::deleted_at.eq(chrono::Local::now().naive_utc() + Duration::hours(3)))
+            .execute(&_connection)
+            .expect("E");
+    }
+    pub fn restore(&self) -> () {
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::files::deleted_at.eq(None::<chrono::NaiveDateTime>))
+            .execute(&_connection)
+            .expect("E");
+    }
 }
 #[derive(Debug, Deserialize, Insertable)]
 #[table_name="files"]
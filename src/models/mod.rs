@@ -6,6 +6,15 @@ mod order;
 mod serve;
 mod media;
 mod chat;
+mod variant;
+mod cart;
+mod work_case;
+mod wiki;
+mod help_vote;
+mod item_access;
+mod discount;
+mod currency;
+mod timeline;
 
 pub use self::{
     item::*,
@@ -16,4 +25,13 @@ pub use self::{
     order::*,
     media::*,
     chat::*,
+    variant::*,
+    cart::*,
+    work_case::*,
+    wiki::*,
+    help_vote::*,
+    item_access::*,
+    discount::*,
+    currency::*,
+    timeline::*,
 };
@@ -7,16 +7,29 @@ use crate::diesel::{
     ExpressionMethods,
 };
 use serde::{Serialize, Deserialize};
-use crate::models::{Serve, TechCategories};
+use crate::models::{Serve, TechCategories, ItemVariant};
 use crate::schema::{
     orders,
     order_files,
+    order_items,
+    order_serves,
 };
 use crate::utils::{
     establish_connection,
 };
 
 
+// тип заказа (1 услуга, 2 товар, 3 работа) -> тип элемента (2, 3, 5):
+// правила скидок и калькулятор считают по типам элементов
+pub fn get_order_item_types(types: i16) -> i16 {
+    match types {
+        1 => 2,
+        2 => 3,
+        3 => 5,
+        _ => types,
+    }
+}
+
 #[derive(Debug, Serialize, Identifiable, Queryable)]
 #[table_name="orders"]
 pub struct Order {
@@ -33,9 +46,91 @@ pub struct Order {
     pub user_id:        i32,
     pub price:          i32,
     pub price_acc:      Option<i32>,
+    pub status:         i16,
+    pub variant_id:     Option<i32>,
+    pub discount_rule_id: Option<i32>,
+    pub promo_code_id:  Option<i32>,
+    pub man_hours:      Option<i32>,
+    pub work_days:      Option<i32>,
+    pub deadline:       Option<chrono::NaiveDate>,
 }
 
 impl Order {
+    pub fn get_status(&self) -> String {
+        return match self.status {
+            1 => "Новый".to_string(),
+            2 => "Принят".to_string(),
+            3 => "Отменен".to_string(),
+            _ => "Непонятно".to_string(),
+        };
+    }
+    // по какому правилу или промокоду дана скидка
+    pub fn get_discount_title(&self) -> Option<String> {
+        if let Some(id) = self.promo_code_id {
+            return crate::models::PromoCode::get_with_id(id).map(|c| "Промокод ".to_string() + &c.code);
+        }
+        if let Some(id) = self.discount_rule_id {
+            return crate::models::DiscountRule::get_with_id(id).map(|r| r.title);
+        }
+        return None;
+    }
+    pub fn get_variant(&self) -> Option<ItemVariant> {
+        if self.variant_id.is_none() {
+            return None;
+        }
+        let _connection = establish_connection();
+        return schema::item_variants::table
+            .filter(schema::item_variants::id.eq(self.variant_id.unwrap()))
+            .first::<ItemVariant>(&_connection)
+            .ok();
+    }
+    // принятие заказа. Если заказан вариант товара, списываем его со склада;
+    // при нулевом остатке заказ не принимается.
+    pub fn accept(&self) -> bool {
+        use diesel::Connection;
+        use diesel::result::Error;
+
+        let _connection = establish_connection();
+        let variant_id = self.variant_id;
+        let order_id = self.id;
+        let _res = _connection.transaction::<_, Error, _>(|| {
+            let updated = diesel::update (
+                schema::orders::table
+                    .filter(schema::orders::id.eq(order_id))
+                    .filter(schema::orders::status.eq(1))
+                )
+                .set(schema::orders::status.eq(2))
+                .execute(&_connection)?;
+            if updated == 0 {
+                return Err(Error::RollbackTransaction);
+            }
+            if let Some(id) = variant_id {
+                if !ItemVariant::decrement_stock(&_connection, id, 1)? {
+                    return Err(Error::RollbackTransaction);
+                }
+            }
+            // заказ из корзины: списываем каждую позицию с вариантом
+            let _items = schema::order_items::table
+                .filter(schema::order_items::order_id.eq(order_id))
+                .filter(schema::order_items::variant_id.is_not_null())
+                .load::<OrderItem>(&_connection)?;
+            for _item in _items.iter() {
+                if !ItemVariant::decrement_stock(&_connection, _item.variant_id.unwrap(), _item.count as i32)? {
+                    return Err(Error::RollbackTransaction);
+                }
+            }
+            Ok(())
+        });
+        return _res.is_ok();
+    }
+    pub fn get_items(&self) -> Vec<OrderItem> {
+        let _connection = establish_connection();
+        return schema::order_items::table
+            .filter(schema::order_items::order_id.eq(self.id))
+            .order(schema::order_items::id.asc())
+            .load::<OrderItem>(&_connection)
+            .expect("E");
+    }
     pub fn get_orders_list(page: i32, limit: i32) -> (Vec<Order>, i32) {
         let mut next_page_number = 0;
         let have_next: i32;
@@ -99,7 +194,14 @@ impl Order {
             .load::<Order>(&_connection)
             .expect("E.");
     }
-    pub fn get_serves(&self) -> Vec<Serve> {
+    pub fn get_serves(&self) -> Vec<OrderServe> {
+        // строки заказа - из снимка на момент заказа; у старых заказов
+        // снимка нет, для них собираем строки из текущих опций
+        let _snapshots = self.get_order_serves();
+        if !_snapshots.is_empty() {
+            return _snapshots;
+        }
+
         use schema::serve_items::dsl::serve_items;
         use schema::serve::dsl::serve;
 
@@ -115,6 +217,17 @@ impl Order {
             .filter(schema::serve::id.eq_any(_serve_items))
             .order(schema::serve::position.desc())
             .load::<Serve>(&_connection)
+            .expect("E")
+            .iter()
+            .map(|s| OrderServe::from_serve(self.id, s))
+            .collect();
+    }
+    pub fn get_order_serves(&self) -> Vec<OrderServe> {
+        let _connection = establish_connection();
+        return schema::order_serves::table
+            .filter(schema::order_serves::order_id.eq(self.id))
+            .order(schema::order_serves::id.asc())
+            .load::<OrderServe>(&_connection)
             .expect("E");
     }
     pub fn get_serves_ids(&self) -> Vec<i32> {
@@ -166,6 +279,8 @@ pub struct NewOrder {
     pub created:        chrono::NaiveDateTime,
     pub user_id:        i32,
     pub price:          i32,
+    pub status:         i16,
+    pub variant_id:     Option<i32>,
 }
 impl NewOrder {
     pub fn create (
@@ -178,6 +293,7 @@ impl NewOrder {
         description:    Option<String>,
         description_en: Option<String>,
         user_id:        i32,
+        variant_id:     Option<i32>,
     ) -> Self {
         use chrono::Duration;
 
@@ -193,6 +309,8 @@ impl NewOrder {
             created:        chrono::Local::now().naive_utc() + Duration::hours(3),
             user_id:        user_id,
             price:          0,
+            status:         1,
+            variant_id:     variant_id,
         }
     }
 }
@@ -228,3 +346,128 @@ impl NewOrderFile {
         }
     }
 }
+
+// позиция заказа из корзины; цена фиксируется на момент заказа
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+pub struct OrderItem {
+    pub id:         i32,
+    pub order_id:   i32,
+    pub item_id:    i32,
+    pub variant_id: Option<i32>,
+    pub title:      String,
+    pub count:      i16,
+    pub price:      i32,
+}
+impl OrderItem {
+    pub fn get_total(&self) -> i32 {
+        return self.price * self.count as i32;
+    }
+}
+
+#[derive(Serialize, Insertable)]
+#[table_name="order_items"]
+pub struct NewOrderItem {
+    pub order_id:   i32,
+    pub item_id:    i32,
+    pub variant_id: Option<i32>,
+    pub title:      String,
+    pub count:      i16,
+    pub price:      i32,
+}
+
+// опция заказа на момент заказа
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+pub struct OrderServe {
+    pub id:             i32,
+    pub order_id:       i32,
+    pub serve_id:       i32,
+    pub name:           String,
+    pub name_en:        String,
+    pub price:          i32,
+    pub man_hours:      i16,
+    pub category_id:    i32,
+    pub tech_cat_id:    i32,
+    pub parent_id:      Option<i32>,
+    pub parent_name:    Option<String>,
+    pub parent_name_en: Option<String>,
+}
+impl OrderServe {
+    // строка без снимка (id 0) - для заказов, сделанных до order_serves
+    pub fn from_serve(order_id: i32, serve: &Serve) -> OrderServe {
+        use schema::serve::dsl::serve as serve_table;
+
+        let _connection = establish_connection();
+        let parent = match serve.serve_id {
+            Some(parent_id) => serve_table
+                .filter(schema::serve::id.eq(parent_id))
+                .first::<Serve>(&_connection)
+                .ok(),
+            None => None,
+        };
+        OrderServe {
+            id:             0,
+            order_id:       order_id,
+            serve_id:       serve.id,
+            name:           serve.name.clone(),
+            name_en:        serve.name_en.clone(),
+            price:          serve.price,
+            man_hours:      serve.man_hours,
+            category_id:    serve.category_id,
+            tech_cat_id:    serve.tech_cat_id,
+            parent_id:      serve.serve_id,
+            parent_name:    parent.as_ref().map(|p| p.name.clone()),
+            parent_name_en: parent.as_ref().map(|p| p.name_en.clone()),
+        }
+    }
+    // вариант опции показываем вместе с родителем: "Дизайн → Индивидуальный"
+    pub fn get_full_name(&self, l: u8) -> String {
+        let name = if l == 2 { &self.name_en } else { &self.name };
+        let parent_name = if l == 2 { &self.parent_name_en } else { &self.parent_name };
+        match parent_name {
+            Some(parent_name) => parent_name.clone() + " → " + name,
+            None => name.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Insertable)]
+#[table_name="order_serves"]
+pub struct NewOrderServe {
+    pub order_id:       i32,
+    pub serve_id:       i32,
+    pub name:           String,
+    pub name_en:        String,
+    pub price:          i32,
+    pub man_hours:      i16,
+    pub category_id:    i32,
+    pub tech_cat_id:    i32,
+    pub parent_id:      Option<i32>,
+    pub parent_name:    Option<String>,
+    pub parent_name_en: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_order_item_types;
+    use crate::utils::CalculateParams;
+
+    fn get_calculate_types(types: Option<&str>) -> i16 {
+        CalculateParams {
+            tech_cat:  Some(1),
+            tech_cats: None,
+            serves:    None,
+            types:     types.map(|t| t.to_string()),
+            promo:     None,
+        }.get_types()
+    }
+
+    // скидка зависит только от цены, типа и промокода: при одинаковом
+    // типе заказ услуги и /api/calculate/ получают одну и ту же скидку
+    #[test]
+    fn order_types_match_calculator() {
+        assert_eq!(get_order_item_types(1), get_calculate_types(None));
+        assert_eq!(get_order_item_types(1), get_calculate_types(Some("services")));
+        assert_eq!(get_order_item_types(2), get_calculate_types(Some("stores")));
+        assert_eq!(get_order_item_types(3), get_calculate_types(Some("works")));
+    }
+}
@@ -5,6 +5,7 @@ use crate::diesel::{
     QueryDsl,
     RunQueryDsl,
     ExpressionMethods,
+    BoolExpressionMethods,
 };
 use serde::{Serialize, Deserialize};
 use crate::schema::{
@@ -13,6 +14,8 @@ use crate::schema::{
     serve,
     serve_items,
     tech_categories_items,
+    serve_relations,
+    serve_prices,
 };
 use crate::utils::{
     establish_connection,
@@ -41,6 +44,13 @@ pub struct TechCategories {
 }
 
 impl TechCategories {
+    pub fn get_with_id(id: i32) -> Option<TechCategories> {
+        let _connection = establish_connection();
+        return schema::tech_categories::table
+            .filter(schema::tech_categories::id.eq(id))
+            .first::<TechCategories>(&_connection)
+            .ok();
+    }
     pub fn update_category_with_id(user: User, cat_id: i32, form: CategoriesForm) -> i16 {
         let _connection = establish_connection();
         let l = get_linguage_storage();
@@ -174,6 +184,7 @@ pub struct ServeCategories {
     pub view:           i32,
     pub height:         f64,
     pub seconds:        i32,
+    pub is_parallel:    bool,
 }
 impl ServeCategories {
     pub fn update_category_with_id(user: User, cat_id: i32, form: crate::utils::ServeCategoriesForm) -> i16 {
@@ -192,6 +203,7 @@ impl ServeCategories {
                     schema::serve_categories::name.eq(&form.name),
                     schema::serve_categories::description.eq(&form.description),
                     schema::serve_categories::position.eq(form.position),
+                    schema::serve_categories::is_parallel.eq(form.is_parallel),
                     //schema::serve_categories::image.eq(&form.image),
                 ))
                 .execute(&_connection)
@@ -203,6 +215,7 @@ impl ServeCategories {
                     schema::serve_categories::name_en.eq(&form.name),
                     schema::serve_categories::description_en.eq(&form.description),
                     schema::serve_categories::position.eq(form.position),
+                    schema::serve_categories::is_parallel.eq(form.is_parallel),
                     //schema::serve_categories::image.eq(&form.image),
                 )) 
                 .execute(&_connection)
@@ -227,6 +240,7 @@ impl ServeCategories {
                 view:           0,
                 height:         0.0,
                 seconds:        0,
+                is_parallel:    form.is_parallel,
             };
             diesel::insert_into(serve_categories::table)
                 .values(&new_cat)
@@ -247,6 +261,7 @@ impl ServeCategories {
                 view:           0,
                 height:         0.0,
                 seconds:        0,
+                is_parallel:    form.is_parallel,
             };
             diesel::insert_into(serve_categories::table)
                 .values(&new_cat)
@@ -321,6 +336,7 @@ pub struct NewServeCategories {
     pub view:           i32,
     pub height:         f64,
     pub seconds:        i32,
+    pub is_parallel:    bool,
 }
 
 /////// Serve //////
@@ -465,6 +481,23 @@ impl Serve {
         }
         return "".to_string();
     }
+    pub fn get_relations(&self) -> Vec<ServeRelation> {
+        let _connection = establish_connection();
+        return schema::serve_relations::table
+            .filter(schema::serve_relations::serve_id.eq(self.id))
+            .order(schema::serve_relations::types.asc())
+            .load::<ServeRelation>(&_connection)
+            .expect("E");
+    }
+    // история цены, последние изменения первыми
+    pub fn get_prices(&self) -> Vec<ServePrice> {
+        let _connection = establish_connection();
+        return schema::serve_prices::table
+            .filter(schema::serve_prices::serve_id.eq(self.id))
+            .order(schema::serve_prices::created.desc())
+            .load::<ServePrice>(&_connection)
+            .expect("E");
+    }
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -547,3 +580,111 @@ pub struct NewTechCategoriesItem {
     pub types:       i16,
     pub is_active:   i16,
 }
+
+/////// ServeRelation //////
+// 1 требует, 2 исключает (в обе стороны), 3 рекомендует
+#[derive(Debug, Serialize, Identifiable, Queryable)]
+#[table_name="serve_relations"]
+pub struct ServeRelation {
+    pub id:        i32,
+    pub serve_id:  i32,
+    pub target_id: i32,
+    pub types:     i16,
+}
+impl ServeRelation {
+    pub fn get_types_ru(&self) -> String {
+        return match self.types {
+            1 => "требует".to_string(),
+            2 => "исключает".to_string(),
+            3 => "рекомендует".to_string(),
+            _ => "Непонятно".to_string(),
+        };
+    }
+    pub fn get_with_id(id: i32) -> Option<ServeRelation> {
+        let _connection = establish_connection();
+        return schema::serve_relations::table
+            .filter(schema::serve_relations::id.eq(id))
+            .first::<ServeRelation>(&_connection)
+            .ok();
+    }
+    pub fn get_target(&self) -> Option<Serve> {
+        let _connection = establish_connection();
+        return schema::serve::table
+            .filter(schema::serve::id.eq(self.target_id))
+            .first::<Serve>(&_connection)
+            .ok();
+    }
+    // связи выбранных опций; исключения - и с той, и с другой стороны
+    pub fn get_for_serves(ids: &Vec<i32>) -> Vec<ServeRelation> {
+        let _connection = establish_connection();
+        return schema::serve_relations::table
+            .filter(schema::serve_relations::serve_id.eq_any(ids)
+                .or(schema::serve_relations::types.eq(2)
+                    .and(schema::serve_relations::target_id.eq_any(ids))))
+            .order(schema::serve_relations::id.asc())
+            .load::<ServeRelation>(&_connection)
+            .expect("E");
+    }
+    pub fn create(serve_id: i32, target_id: i32, types: i16) -> Option<ServeRelation> {
+        let _connection = establish_connection();
+        let new_relation = NewServeRelation {
+            serve_id:  serve_id,
+            target_id: target_id,
+            types:     types,
+        };
+        return diesel::insert_into(schema::serve_relations::table)
+            .values(&new_relation)
+            .get_result::<ServeRelation>(&_connection)
+            .ok();
+    }
+    pub fn delete(&self) -> () {
+        let _connection = establish_connection();
+        diesel::delete(self)
+            .execute(&_connection)
+            .expect("E");
+    }
+}
+#[derive(Insertable)]
+#[table_name="serve_relations"]
+pub struct NewServeRelation {
+    pub serve_id:  i32,
+    pub target_id: i32,
+    pub types:     i16,
+}
+
+/////// ServePrice //////
+#[derive(Debug, Serialize, Identifiable, Queryable)]
+#[table_name="serve_prices"]
+pub struct ServePrice {
+    pub id:        i32,
+    pub serve_id:  i32,
+    pub price:     i32,
+    pub man_hours: i16,
+    pub user_id:   i32,
+    pub created:   chrono::NaiveDateTime,
+}
+impl ServePrice {
+    pub fn create(serve_id: i32, price: i32, man_hours: i16, user_id: i32) -> () {
+        let _connection = establish_connection();
+        let new_price = NewServePrice {
+            serve_id:  serve_id,
+            price:     price,
+            man_hours: man_hours,
+            user_id:   user_id,
+            created:   chrono::Local::now().naive_utc() + chrono::Duration::hours(3),
+        };
+        diesel::insert_into(schema::serve_prices::table)
+            .values(&new_price)
+            .execute(&_connection)
+            .expect("E");
+    }
+}
+#[derive(Insertable)]
+#[table_name="serve_prices"]
+pub struct NewServePrice {
+    pub serve_id:  i32,
+    pub price:     i32,
+    pub man_hours: i16,
+    pub user_id:   i32,
+    pub created:   chrono::NaiveDateTime,
+}
@@ -17,7 +17,7 @@ CREATE TABLE orders (
     title          VARCHAR(100) NOT NULL,
     title_en       VARCHAR(100) NOT NULL,
     types          SMALLINT NOT NULL, -- 1 услуга, 2 товар, 3 работа
-    object_id      INT NOT NULL,
+    object_id      INT NOT NULL,      -- 0 - заказ из корзины (позиции в order_items)
     username       VARCHAR(200) NOT NULL,
     email          VARCHAR(200) NOT NULL,
     description    VARCHAR(1000),
@@ -25,7 +25,96 @@ CREATE TABLE orders (
     created        TIMESTAMP NOT NULL,
     user_id        INT NOT NULL,
     price          INT NOT NULL,
-    price_acc      INT
+    price_acc      INT,
+    status         SMALLINT NOT NULL DEFAULT 1, -- 1 новый, 2 принят, 3 отменен
+    variant_id     INT,                         -- вариант товара
+    discount_rule_id INT,                       -- примененное правило скидки
+    promo_code_id  INT,                         -- примененный промокод
+    man_hours      INT,                         -- трудоемкость выбранных опций
+    work_days      INT,                         -- оценка срока в рабочих днях
+    deadline       DATE                         -- ориентировочная дата сдачи
+);
+
+-- правила скидок: от суммы threshold (включительно) - скидка percent %.
+-- из подходящих берется самая большая
+CREATE TABLE discount_rules (
+    id        SERIAL PRIMARY KEY,
+    title     VARCHAR(100) NOT NULL,
+    threshold INT NOT NULL,
+    percent   SMALLINT NOT NULL,
+    types     SMALLINT NOT NULL DEFAULT 0, -- тип элементов (2 услуги, 3 товары...), 0 - любые
+    starts_at TIMESTAMP,                   -- пусто - без ограничений
+    ends_at   TIMESTAMP,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created   TIMESTAMP NOT NULL
+);
+INSERT INTO discount_rules (title,threshold,percent,types,created)
+VALUES ('От 1 000 000 ₽',1000000,5,0,now());
+INSERT INTO discount_rules (title,threshold,percent,types,created)
+VALUES ('От 2 000 000 ₽',2000000,7,0,now());
+INSERT INTO discount_rules (title,threshold,percent,types,created)
+VALUES ('От 3 000 000 ₽',3000000,10,0,now());
+
+-- промокоды: не суммируются с правилами, берется большая скидка
+CREATE TABLE promo_codes (
+    id        SERIAL PRIMARY KEY,
+    code      VARCHAR(50) NOT NULL,
+    percent   SMALLINT NOT NULL,
+    types     SMALLINT NOT NULL DEFAULT 0, -- 0 - любые
+    min_price INT NOT NULL DEFAULT 0,
+    max_uses  INT NOT NULL DEFAULT 0,      -- 0 - без ограничений
+    uses      INT NOT NULL DEFAULT 0,
+    starts_at TIMESTAMP,
+    ends_at   TIMESTAMP,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created   TIMESTAMP NOT NULL,
+
+    UNIQUE(code)
+);
+
+-- валюты: все цены хранятся в рублях (базовая валюта),
+-- остальные показываются ориентировочно по курсу из админки
+CREATE TABLE currencies (
+    id        SERIAL PRIMARY KEY,
+    code      VARCHAR(3) NOT NULL,   -- ISO 4217: RUB, USD, EUR
+    name      VARCHAR(100) NOT NULL,
+    name_en   VARCHAR(100) NOT NULL,
+    symbol    VARCHAR(5) NOT NULL,
+    rate      FLOAT NOT NULL,        -- сколько рублей за 1 единицу валюты
+    is_base   BOOLEAN NOT NULL DEFAULT false,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    position  SMALLINT NOT NULL DEFAULT 0,
+    updated   TIMESTAMP NOT NULL,
+
+    UNIQUE(code)
+);
+INSERT INTO currencies (code,name,name_en,symbol,rate,is_base,position,updated)
+VALUES ('RUB','Рубль','Ruble','₽',1.0,true,1,now());
+INSERT INTO currencies (code,name,name_en,symbol,rate,is_base,position,updated)
+VALUES ('USD','Доллар США','US Dollar','$',90.0,false,2,now());
+INSERT INTO currencies (code,name,name_en,symbol,rate,is_base,position,updated)
+VALUES ('EUR','Евро','Euro','€',100.0,false,3,now());
+
+-- настройки оценки сроков (одна запись): сколько человек
+-- в команде и сколько продуктивных часов в день у каждого
+CREATE TABLE timeline_settings (
+    id            SERIAL PRIMARY KEY,
+    workers       SMALLINT NOT NULL DEFAULT 2,
+    hours_per_day SMALLINT NOT NULL DEFAULT 6,
+    start_delay   SMALLINT NOT NULL DEFAULT 1,     -- через сколько рабочих дней после заказа начинаем
+    work_weekends BOOLEAN NOT NULL DEFAULT false,
+    updated       TIMESTAMP NOT NULL
+);
+INSERT INTO timeline_settings (workers,hours_per_day,start_delay,work_weekends,updated)
+VALUES (2,6,1,false,now());
+
+-- праздники и прочие нерабочие дни
+CREATE TABLE holidays (
+    id    SERIAL PRIMARY KEY,
+    day   DATE NOT NULL,
+    title VARCHAR(100) NOT NULL,
+
+    UNIQUE(day)
 );
 
 CREATE TABLE order_files (
@@ -132,12 +221,31 @@ CREATE TABLE tags (
     view      INT NOT NULL,
     height    FLOAT NOT NULL,
     seconds   INT NOT NULL,
+    deleted_at TIMESTAMP,     -- удален в корзину
+    slug      VARCHAR(100) NOT NULL,
+
+    UNIQUE(slug),
 
     CONSTRAINT fk_tag_creator
         FOREIGN KEY(user_id)
             REFERENCES users(id)
 );
 
+-- другие названия тега (slug пустой) и адреса слитых
+-- или переименованных тегов, ведущие на tag_id -------
+CREATE TABLE tag_aliases (
+    id     SERIAL PRIMARY KEY,
+    tag_id INT NOT NULL,
+    name   VARCHAR(100) NOT NULL,
+    slug   VARCHAR(100) NOT NULL,
+
+    CONSTRAINT fk_tag_aliases
+        FOREIGN KEY(tag_id)
+            REFERENCES tags(id)
+);
+CREATE INDEX tag_aliases_tag_id_idx ON tag_aliases (tag_id);
+CREATE INDEX tag_aliases_slug_idx ON tag_aliases (slug);
+
 
 CREATE TABLE tags_items (
     id      SERIAL PRIMARY KEY,
@@ -166,6 +274,7 @@ CREATE TABLE categories (
     seconds        INT NOT NULL,
     types          SMALLINT NOT NULL, -- категория блога, категория услуги ......
     slug           VARCHAR(100) NOT NULL,
+    deleted_at     TIMESTAMP,         -- удалена в корзину
 
     UNIQUE(slug)
 );  
@@ -212,6 +321,13 @@ CREATE TABLE items (
     price_acc      INT,
     types          SMALLINT NOT NULL, -- блог, услуга, товар ......
     slug           VARCHAR(100) NOT NULL,
+    deleted_at     TIMESTAMP,         -- удален в корзину
+    word_count      INT NOT NULL DEFAULT 0,      -- слов в content
+    word_count_en   INT NOT NULL DEFAULT 0,      -- слов в content_en
+    reading_time    SMALLINT NOT NULL DEFAULT 0, -- минут чтения
+    reading_time_en SMALLINT NOT NULL DEFAULT 0,
+    visibility      SMALLINT NOT NULL DEFAULT 1, -- 1 всем, 2 авторизованным, 3 выбранным пользователям и группам
+    share_key       VARCHAR(100),                -- секретная ссылка: ?key=...
 
     UNIQUE(slug),
 
@@ -221,6 +337,192 @@ CREATE TABLE items (
 );
 CREATE INDEX items_creator_idx ON items (user_id);
 
+-- варианты товаров (например, тарифы лицензии) -------
+CREATE TABLE item_variants (
+    id        SERIAL PRIMARY KEY,
+    item_id   INT NOT NULL,
+    title     VARCHAR(100) NOT NULL,
+    title_en  VARCHAR(100) NOT NULL,
+    sku       VARCHAR(100) NOT NULL, -- артикул
+    price     INT NOT NULL,
+    price_acc INT,
+    stock     INT NOT NULL,          -- остаток на складе
+    position  SMALLINT NOT NULL,
+
+    UNIQUE(sku),
+
+    CONSTRAINT fk_item_variants
+        FOREIGN KEY(item_id)
+            REFERENCES items(id)
+);
+CREATE INDEX item_variants_item_id_idx ON item_variants (item_id);
+
+-- корзина: владелец - пользователь куки или зарегистрированный -------
+CREATE TABLE cart_items (
+    id          SERIAL PRIMARY KEY,
+    owner_id    INT NOT NULL,
+    owner_types SMALLINT NOT NULL, -- 1 пользователь куки, 2 зарегистрированный
+    item_id     INT NOT NULL,
+    variant_id  INT,
+    count       SMALLINT NOT NULL,
+    created     TIMESTAMP NOT NULL
+);
+CREATE INDEX cart_items_owner_idx ON cart_items (owner_id, owner_types);
+
+-- позиции заказа из корзины (цены на момент заказа) -------
+CREATE TABLE order_items (
+    id         SERIAL PRIMARY KEY,
+    order_id   INT NOT NULL,
+    item_id    INT NOT NULL,
+    variant_id INT,
+    title      VARCHAR(200) NOT NULL,
+    count      SMALLINT NOT NULL,
+    price      INT NOT NULL,      -- цена за единицу
+
+    CONSTRAINT fk_order_items
+        FOREIGN KEY(order_id)
+            REFERENCES orders(id)
+);
+
+CREATE INDEX order_items_order_id_idx ON order_items (order_id);
+
+-- опции заказа в том виде, в каком их заказали: название, цена, часы,
+-- категория, тех. категория и название родительской опции варианта не
+-- меняются вместе с опцией. serve_id и parent_id без внешних ключей -
+-- опции могут удалить, а заказ останется
+CREATE TABLE order_serves (
+    id             SERIAL PRIMARY KEY,
+    order_id       INT NOT NULL,
+    serve_id       INT NOT NULL,
+    name           VARCHAR(100) NOT NULL,
+    name_en        VARCHAR(100) NOT NULL,
+    price          INT NOT NULL,
+    man_hours      SMALLINT NOT NULL,
+    category_id    INT NOT NULL,
+    tech_cat_id    INT NOT NULL,
+    parent_id      INT,
+    parent_name    VARCHAR(100),
+    parent_name_en VARCHAR(100),
+
+    CONSTRAINT fk_order_serves
+        FOREIGN KEY(order_id)
+            REFERENCES orders(id)
+);
+CREATE INDEX order_serves_order_id_idx ON order_serves (order_id);
+
+-- кейс работы портфолио (одна запись на работу) -------
+CREATE TABLE work_cases (
+    id          SERIAL PRIMARY KEY,
+    item_id     INT NOT NULL,
+    client      VARCHAR(200),
+    client_en   VARCHAR(200),
+    industry    VARCHAR(100),     -- отрасль, по ней фильтр на страницах категорий
+    industry_en VARCHAR(100),
+    year        SMALLINT,
+    duration    VARCHAR(100),     -- например, "3 месяца"
+    duration_en VARCHAR(100),
+    team_size   SMALLINT,
+    results     VARCHAR(5000),    -- результаты и метрики
+    results_en  VARCHAR(5000),
+
+    UNIQUE(item_id),
+
+    CONSTRAINT fk_work_cases
+        FOREIGN KEY(item_id)
+            REFERENCES items(id)
+);
+
+-- пользователи, которым открыт элемент с visibility 3 -------
+CREATE TABLE item_access (
+    id      SERIAL PRIMARY KEY,
+    item_id INT NOT NULL,
+    user_id INT NOT NULL,
+
+    UNIQUE(item_id, user_id),
+
+    CONSTRAINT fk_item_access_item
+        FOREIGN KEY(item_id)
+            REFERENCES items(id),
+    CONSTRAINT fk_item_access_user
+        FOREIGN KEY(user_id)
+            REFERENCES users(id)
+);
+CREATE INDEX item_access_user_id_idx ON item_access (user_id);
+
+-- группы пользователей (например, команда клиента) -------
+CREATE TABLE user_groups (
+    id   SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+
+    UNIQUE(name)
+);
+
+CREATE TABLE user_group_members (
+    id       SERIAL PRIMARY KEY,
+    group_id INT NOT NULL,
+    user_id  INT NOT NULL,
+
+    UNIQUE(group_id, user_id),
+
+    CONSTRAINT fk_user_group_members_group
+        FOREIGN KEY(group_id)
+            REFERENCES user_groups(id),
+    CONSTRAINT fk_user_group_members_user
+        FOREIGN KEY(user_id)
+            REFERENCES users(id)
+);
+CREATE INDEX user_group_members_user_id_idx ON user_group_members (user_id);
+
+-- группы, которым открыт элемент с visibility 3 -------
+CREATE TABLE item_group_access (
+    id       SERIAL PRIMARY KEY,
+    item_id  INT NOT NULL,
+    group_id INT NOT NULL,
+
+    UNIQUE(item_id, group_id),
+
+    CONSTRAINT fk_item_group_access_item
+        FOREIGN KEY(item_id)
+            REFERENCES items(id),
+    CONSTRAINT fk_item_group_access_group
+        FOREIGN KEY(group_id)
+            REFERENCES user_groups(id)
+);
+CREATE INDEX item_group_access_group_id_idx ON item_group_access (group_id);
+
+-- дерево статей базы знаний (types 4): у статьи один родитель -------
+CREATE TABLE wiki_parents (
+    id        SERIAL PRIMARY KEY,
+    item_id   INT NOT NULL,
+    parent_id INT NOT NULL,
+
+    UNIQUE(item_id),
+
+    CONSTRAINT fk_wiki_parents_item
+        FOREIGN KEY(item_id)
+            REFERENCES items(id),
+    CONSTRAINT fk_wiki_parents_parent
+        FOREIGN KEY(parent_id)
+            REFERENCES items(id)
+);
+CREATE INDEX wiki_parents_parent_id_idx ON wiki_parents (parent_id);
+
+-- оценки статей помощи (types 6) пользователями куки -------
+CREATE TABLE help_votes (
+    id         SERIAL PRIMARY KEY,
+    item_id    INT NOT NULL,
+    user_id    INT NOT NULL,      -- пользователь куки
+    is_helpful BOOLEAN NOT NULL,
+    comment    VARCHAR(1000),
+    created    TIMESTAMP NOT NULL,
+
+    UNIQUE(item_id, user_id),
+
+    CONSTRAINT fk_help_votes
+        FOREIGN KEY(item_id)
+            REFERENCES items(id)
+);
+
 
 CREATE TABLE item_comments (
     id        SERIAL PRIMARY KEY,
@@ -272,6 +574,7 @@ CREATE TABLE files (
     position       SMALLINT NOT NULL,
     view           INT NOT NULL,
     seconds        INT NOT NULL,
+    deleted_at     TIMESTAMP,         -- удален в корзину
 
     UNIQUE(src)
 );
@@ -310,6 +613,7 @@ CREATE TABLE serve_categories (
     view           INT NOT NULL,
     height         FLOAT NOT NULL,
     seconds        INT NOT NULL,
+    is_parallel    BOOLEAN NOT NULL DEFAULT false, -- работы можно вести параллельно с другими категориями
 
     CONSTRAINT fk_tech_category
         FOREIGN KEY(category_id)
@@ -343,6 +647,38 @@ CREATE TABLE serve (
             REFERENCES users(id)
 );
 
+-- связи между опциями: 1 требует, 2 исключает, 3 рекомендует.
+-- "исключает" действует в обе стороны
+CREATE TABLE serve_relations (
+    id        SERIAL PRIMARY KEY,
+    serve_id  INT NOT NULL,
+    target_id INT NOT NULL,
+    types     SMALLINT NOT NULL,
+
+    UNIQUE(serve_id, target_id),
+    CONSTRAINT fk_serve_relations_serve
+        FOREIGN KEY(serve_id)
+            REFERENCES serve(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_serve_relations_target
+        FOREIGN KEY(target_id)
+            REFERENCES serve(id)
+            ON DELETE CASCADE
+);
+
+-- история цен опций: новая строка при создании опции и при каждом
+-- изменении цены или трудоемкости. serve_id без внешнего ключа -
+-- история остается и после удаления опции
+CREATE TABLE serve_prices (
+    id        SERIAL PRIMARY KEY,
+    serve_id  INT NOT NULL,
+    price     INT NOT NULL,
+    man_hours SMALLINT NOT NULL,
+    user_id   INT NOT NULL,      -- кто поменял
+    created   TIMESTAMP NOT NULL
+);
+CREATE INDEX serve_prices_serve_id_idx ON serve_prices (serve_id);
+
 -- связь опции с объетками сервисов, работ, товаров
 CREATE TABLE serve_items (
     id       SERIAL PRIMARY KEY,
@@ -351,6 +687,23 @@ CREATE TABLE serve_items (
     types    SMALLINT NOT NULL
 );
 
+-- технологии кейса (serve_categories, например Rust, React Native) -------
+CREATE TABLE work_case_techs (
+    id          SERIAL PRIMARY KEY,
+    item_id     INT NOT NULL,
+    category_id INT NOT NULL,
+
+    UNIQUE(item_id, category_id),
+
+    CONSTRAINT fk_work_case_techs_item
+        FOREIGN KEY(item_id)
+            REFERENCES items(id),
+    CONSTRAINT fk_work_case_techs_category
+        FOREIGN KEY(category_id)
+            REFERENCES serve_categories(id)
+);
+CREATE INDEX work_case_techs_item_id_idx ON work_case_techs (item_id);
+
 -- это те tech_categories, которые привязываются к объеткам.
 -- бывают открытые (активные) и дополнительные.
 CREATE TABLE tech_categories_items (
@@ -5,38 +5,79 @@ use crate::diesel::{
     QueryDsl,
     RunQueryDsl,
     ExpressionMethods,
+    BoolExpressionMethods,
+    PgTextExpressionMethods,
+    Connection,
 };
 use serde::{Serialize, Deserialize};
 use crate::schema::{
     tags,
     tags_items,
+    tag_aliases,
 };
 use crate::utils::{
     establish_connection,
-    get_linguage_storage,
+    get_slug,
     CategoriesForm
 };
-use crate::models::User;
+use crate::models::{User, Item};
 
 
 #[derive(Serialize, Queryable)]
 pub struct SmallTag {
     pub name:  String,
+    pub slug:  String,
     pub count: i16,
 }
 
-#[derive(Debug, Serialize, Queryable, Identifiable)]
-#[table_name="tags"]
-pub struct Tag {
-    pub id:       i32,
+// тег облака: level 1..5 - размер шрифта
+#[derive(Serialize)]
+pub struct CloudTag {
+    pub name:   String,
+    #[serde(skip)]
+    pub name_en: String,
+    pub slug:   String,
+    pub count:  i16,
+    pub weight: f64,
+    pub level:  u8,
+}
+
+// тег, страницы которого смотрят чаще всего за последние дни
+#[derive(Serialize)]
+pub struct TrendingTag {
     pub name:     String,
+    #[serde(skip)]
     pub name_en:  String,
-    pub position: i16,
+    pub slug:     String,
     pub count:    i16,
-    pub user_id:  i32,
-    pub view:     i32,
-    pub height:   f64,
-    pub seconds:  i32,
+    pub views:    i32, // просмотры за окно
+    pub previous: i32, // просмотры за такое же окно перед ним
+}
+
+// подсказка для поля тегов в формах объектов
+#[derive(Serialize, Queryable)]
+pub struct TagHint {
+    pub id:      i32,
+    pub name:    String,
+    pub name_en: String,
+    pub slug:    String,
+    pub count:   i16,
+}
+
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+#[table_name="tags"]
+pub struct Tag {
+    pub id:         i32,
+    pub name:       String,
+    pub name_en:    String,
+    pub position:   i16,
+    pub count:      i16,
+    pub user_id:    i32,
+    pub view:       i32,
+    pub height:     f64,
+    pub seconds:    i32,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+    pub slug:       String,
 }
 impl Tag {
     pub fn get_tags_with_ids(_tag_items: Vec<i32>, l: u8) -> Vec<SmallTag> {
@@ -44,8 +85,10 @@ impl Tag {
         if l == 1 {
             return schema::tags::table
                 .filter(schema::tags::id.eq_any(_tag_items))
+                .filter(schema::tags::deleted_at.is_null())
                 .select((
                     schema::tags::name,
+                    schema::tags::slug,
                     schema::tags::count,
                 ))
                 .load::<SmallTag>(&_connection)
@@ -54,8 +97,10 @@ impl Tag {
         else if l == 2 {
             return schema::tags::table
                 .filter(schema::tags::id.eq_any(_tag_items))
+                .filter(schema::tags::deleted_at.is_null())
                 .select((
                     schema::tags::name_en,
+                    schema::tags::slug,
                     schema::tags::count,
                 ))
                 .load::<SmallTag>(&_connection)
@@ -70,80 +115,318 @@ impl Tag {
             .first::<Tag>(&_connection)
             .expect("E.");
     } 
-    pub fn get_tag_with_slug(name: &String) -> Tag {
+    pub fn get_tag_with_slug(slug: &String) -> Option<Tag> {
         let _connection = establish_connection();
         return schema::tags::table
-            .filter(schema::tags::name.eq(name))
+            .filter(schema::tags::slug.eq(slug))
+            .filter(schema::tags::deleted_at.is_null())
             .first::<Tag>(&_connection)
-            .expect("E.");
+            .ok();
+    }
+    // slug для старого адреса: раньше теги открывались по названию,
+    // слитые теги и другие названия остаются в tag_aliases
+    pub fn get_redirect_slug(slug: &String) -> Option<String> {
+        let _connection = establish_connection();
+        let by_name = schema::tags::table
+            .filter(schema::tags::name.eq(slug))
+            .filter(schema::tags::deleted_at.is_null())
+            .select(schema::tags::slug)
+            .first::<String>(&_connection);
+        if by_name.is_ok() {
+            return by_name.ok();
+        }
+        let tag_id = schema::tag_aliases::table
+            .filter(schema::tag_aliases::slug.eq(slug)
+                .or(schema::tag_aliases::name.eq(slug)))
+            .select(schema::tag_aliases::tag_id)
+            .first::<i32>(&_connection)
+            .ok()?;
+        return schema::tags::table
+            .filter(schema::tags::id.eq(tag_id))
+            .filter(schema::tags::deleted_at.is_null())
+            .select(schema::tags::slug)
+            .first::<String>(&_connection)
+            .ok();
+    }
+    // тег с таким же названием или slug без учета регистра
+    // или с таким другим названием
+    pub fn get_tag_with_name(name: &str) -> Option<Tag> {
+        let _connection = establish_connection();
+        let name = name.trim();
+        // % и _ в названии - обычные символы, не шаблон ilike
+        let pattern = name
+            .replace("\\", "\\\\")
+            .replace("%", "\\%")
+            .replace("_", "\\_");
+        let _tag = schema::tags::table
+            .filter(schema::tags::deleted_at.is_null())
+            .filter(schema::tags::name.ilike(&pattern)
+                .or(schema::tags::name_en.ilike(&pattern))
+                .or(schema::tags::slug.eq(name.to_lowercase())))
+            .order(schema::tags::id.asc())
+            .first::<Tag>(&_connection)
+            .ok();
+        if _tag.is_some() {
+            return _tag;
+        }
+        let tag_id = schema::tag_aliases::table
+            .filter(schema::tag_aliases::name.ilike(&pattern)
+                .or(schema::tag_aliases::slug.eq(name.to_lowercase())))
+            .select(schema::tag_aliases::tag_id)
+            .first::<i32>(&_connection)
+            .ok()?;
+        return schema::tags::table
+            .filter(schema::tags::id.eq(tag_id))
+            .filter(schema::tags::deleted_at.is_null())
+            .first::<Tag>(&_connection)
+            .ok();
+    }
+    // свободный slug: rust, rust-2, rust-3...
+    pub fn get_unique_slug(text: &str, tag_id: i32) -> String {
+        let _connection = establish_connection();
+        let mut base = get_slug(text);
+        if base.is_empty() {
+            base = "tag".to_string();
+        }
+        let mut slug = base.clone();
+        let mut n = 1;
+        loop {
+            let is_busy = schema::tags::table
+                .filter(schema::tags::slug.eq(&slug))
+                .filter(schema::tags::id.ne(tag_id))
+                .select(schema::tags::id)
+                .first::<i32>(&_connection)
+                .is_ok()
+                || schema::tag_aliases::table
+                .filter(schema::tag_aliases::slug.eq(&slug))
+                .filter(schema::tag_aliases::tag_id.ne(tag_id))
+                .select(schema::tag_aliases::id)
+                .first::<i32>(&_connection)
+                .is_ok();
+            if !is_busy {
+                return slug;
+            }
+            n += 1;
+            slug = base.clone() + &"-".to_string() + &n.to_string();
+        }
     }
     pub fn update_tag_with_id(id: i32, form: CategoriesForm) -> i16 {
         let _connection = establish_connection();
-        let l = get_linguage_storage();
         let _tag = schema::tags::table
             .filter(schema::tags::id.eq(id))
             .first::<Tag>(&_connection)
             .expect("E.");
-        if l == 1 {
-            diesel::update(&_tag)
-                .set((
-                    schema::tags::name.eq(&form.name),
-                    schema::tags::position.eq(form.position),
-                ))
-                .execute(&_connection)
-                .expect("E");
-        }
-        else if l == 2 {
-            diesel::update(&_tag)
-                .set((
-                    schema::tags::name_en.eq(&form.name),
-                    schema::tags::position.eq(form.position),
-                ))
-                .execute(&_connection)
-                .expect("E");
+        let slug = if form.slug.trim().is_empty() {
+            Tag::get_unique_slug(&form.name, _tag.id)
+        } else {
+            Tag::get_unique_slug(&form.slug, _tag.id)
+        };
+        // прежний адрес продолжает открывать тег
+        if slug != _tag.slug {
+            _tag.add_alias(&_tag.name, &_tag.slug);
         }
+        diesel::update(&_tag)
+            .set((
+                schema::tags::name.eq(&form.name),
+                schema::tags::name_en.eq(&form.name_en),
+                schema::tags::position.eq(form.position),
+                schema::tags::slug.eq(slug),
+            ))
+            .execute(&_connection)
+            .expect("E");
         return 1;
     }
+    // дубль по названию не создаем
     pub fn create(user: User, form: CategoriesForm) -> i16 {
         let _connection = establish_connection();
-        let l = get_linguage_storage();
-        if l == 1 { 
-            let new_tag = NewTag {
-                name:     form.name.clone(),
-                name_en:  "".to_string(),
-                position: form.position,
-                count:    0,
-                user_id:  user.id,
-                view:     0,
-                height:   0.0,
-                seconds:  0,
-            };
-            diesel::insert_into(schema::tags::table)
-                .values(&new_tag)
-                .execute(&_connection)
-                .expect("E.");
-        }
-        else if l == 2 {
-            let new_tag = NewTag {
-                name:     "".to_string(),
-                name_en:  form.name.clone(),
-                position: form.position,
-                count:    0,
-                user_id:  user.id,
-                view:     0,
-                height:   0.0,
-                seconds:  0,
-            }; 
-            diesel::insert_into(schema::tags::table)
-                .values(&new_tag)
-                .execute(&_connection)
-                .expect("E.");
+        if Tag::get_tag_with_name(&form.name).is_some() {
+            return 0;
         }
+        let slug = if form.slug.trim().is_empty() { &form.name } else { &form.slug };
+        let new_tag = NewTag {
+            name:     form.name.clone(),
+            name_en:  form.name_en.clone(),
+            position: form.position,
+            count:    0,
+            user_id:  user.id,
+            view:     0,
+            height:   0.0,
+            seconds:  0,
+            slug:     Tag::get_unique_slug(slug, 0),
+        };
+        diesel::insert_into(schema::tags::table)
+            .values(&new_tag)
+            .execute(&_connection)
+            .expect("E.");
         return 1;
     }
+    // тег, введенный в форме объекта: существующий с таким названием или новый
+    pub fn get_or_create(name: &str, user_id: i32) -> Option<Tag> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        if let Some(_tag) = Tag::get_tag_with_name(name) {
+            return Some(_tag);
+        }
+        let _connection = establish_connection();
+        let new_tag = NewTag {
+            name:     name.to_string(),
+            name_en:  name.to_string(),
+            position: 0,
+            count:    0,
+            user_id:  user_id,
+            view:     0,
+            height:   0.0,
+            seconds:  0,
+            slug:     Tag::get_unique_slug(name, 0),
+        };
+        return diesel::insert_into(schema::tags::table)
+            .values(&new_tag)
+            .get_result::<Tag>(&_connection)
+            .ok();
+    }
+    // выбранные теги + новые по названиям, без повторов
+    pub fn get_ids_with_names(ids: Vec<i32>, names: &Vec<String>, user_id: i32) -> Vec<i32> {
+        let mut list = ids;
+        for name in names.iter() {
+            if let Some(_tag) = Tag::get_or_create(name, user_id) {
+                list.push(_tag.id);
+            }
+        }
+        let mut unique: Vec<i32> = Vec::new();
+        for id in list.into_iter() {
+            if !unique.contains(&id) {
+                unique.push(id);
+            }
+        }
+        return unique;
+    }
+    // автодополнение по name / name_en, сначала популярные
+    pub fn search(q: &str, limit: i64) -> Vec<TagHint> {
+        let _connection = establish_connection();
+        let q = "%".to_string() + q.trim() + "%";
+        return schema::tags::table
+            .filter(schema::tags::deleted_at.is_null())
+            .filter(schema::tags::name.ilike(&q).or(schema::tags::name_en.ilike(&q)))
+            .order((schema::tags::count.desc(), schema::tags::name.asc()))
+            .limit(limit)
+            .select((
+                schema::tags::id,
+                schema::tags::name,
+                schema::tags::name_en,
+                schema::tags::slug,
+                schema::tags::count,
+            ))
+            .load::<TagHint>(&_connection)
+            .expect("E.");
+    }
+    // "rust+actix" - объекты со всеми тегами, "rust,actix" - с любым из них
+    pub fn parse_slugs(param: &str) -> (Vec<String>, bool) {
+        let is_and = !param.contains(",");
+        let mut slugs: Vec<String> = Vec::new();
+        for slug in param.split(|c| c == '+' || c == ',' || c == ' ') {
+            let slug = slug.trim().to_string();
+            if !slug.is_empty() && !slugs.contains(&slug) {
+                slugs.push(slug);
+            }
+        }
+        return (slugs, is_and);
+    }
+    // теги в порядке адреса; если какого-то нет - список будет короче
+    pub fn get_tags_with_slugs(slugs: &Vec<String>) -> Vec<Tag> {
+        let _connection = establish_connection();
+        let mut _tags = schema::tags::table
+            .filter(schema::tags::slug.eq_any(slugs))
+            .filter(schema::tags::deleted_at.is_null())
+            .load::<Tag>(&_connection)
+            .expect("E.");
+        let mut list = Vec::new();
+        for slug in slugs.iter() {
+            if let Some(pos) = _tags.iter().position(|t| &t.slug == slug) {
+                list.push(_tags.remove(pos));
+            }
+        }
+        return list;
+    }
+    // объекты с выбранными тегами: (item_id, types).
+    // для не админов - только опубликованные и открытые всем
+    pub fn get_items_for_tags(tag_ids: &Vec<i32>, is_and: bool, user: Option<&User>) -> Vec<(i32, i16)> {
+        let _connection = establish_connection();
+        let _tag_items = schema::tags_items::table
+            .filter(schema::tags_items::tag_id.eq_any(tag_ids))
+            .select((
+                schema::tags_items::item_id,
+                schema::tags_items::types,
+                schema::tags_items::tag_id,
+            ))
+            .load::<(i32, i16, i32)>(&_connection)
+            .expect("E.");
+
+        let mut stack: Vec<(i32, i16, Vec<i32>)> = Vec::new();
+        for (item_id, types, tag_id) in _tag_items.into_iter() {
+            match stack.iter_mut().find(|i| i.0 == item_id) {
+                Some(i) => if !i.2.contains(&tag_id) {
+                    i.2.push(tag_id);
+                },
+                None => stack.push((item_id, types, vec![tag_id])),
+            }
+        }
+        let ids: Vec<i32> = stack
+            .iter()
+            .filter(|i| !is_and || i.2.len() == tag_ids.len())
+            .map(|i| i.0)
+            .collect();
+
+        return schema::items::table
+            .filter(schema::items::id.eq_any(&ids))
+            .filter(schema::items::deleted_at.is_null())
+            .filter(Item::get_visible_filter(user))
+            .select((schema::items::id, schema::items::types))
+            .load::<(i32, i16)>(&_connection)
+            .expect("E.");
+    }
+    // теги, которые стоят у этих объектов вместе с выбранными, - чтобы сузить выборку
+    pub fn get_related_tags(tag_ids: &Vec<i32>, item_ids: &Vec<i32>, limit: usize) -> Vec<SmallTag> {
+        let _connection = establish_connection();
+        let _related_ids = schema::tags_items::table
+            .filter(schema::tags_items::item_id.eq_any(item_ids))
+            .filter(schema::tags_items::tag_id.ne_all(tag_ids))
+            .select(schema::tags_items::tag_id)
+            .load::<i32>(&_connection)
+            .expect("E.");
+        let mut counts: Vec<(i32, i16)> = Vec::new();
+        for id in _related_ids.into_iter() {
+            match counts.iter_mut().find(|c| c.0 == id) {
+                Some(c) => c.1 += 1,
+                None => counts.push((id, 1)),
+            }
+        }
+        counts.sort_by(|a, b| b.1.cmp(&a.1));
+        counts.truncate(limit);
+
+        let _tags = schema::tags::table
+            .filter(schema::tags::id.eq_any(counts.iter().map(|c| c.0).collect::<Vec<i32>>()))
+            .filter(schema::tags::deleted_at.is_null())
+            .select((schema::tags::id, schema::tags::name, schema::tags::slug))
+            .load::<(i32, String, String)>(&_connection)
+            .expect("E.");
+        let mut list = Vec::new();
+        for (id, count) in counts.iter() {
+            if let Some(_tag) = _tags.iter().find(|t| &t.0 == id) {
+                list.push(SmallTag {
+                    name:  _tag.1.clone(),
+                    slug:  _tag.2.clone(),
+                    count: *count,
+                });
+            }
+        }
+        return list;
+    }
     pub fn get_all_tags() -> Vec<Tag> {
         let _connection = establish_connection();
         return schema::tags::table
+            .filter(schema::tags::deleted_at.is_null())
             .load::<Tag>(&_connection)
             .expect("E.");
     }
@@ -172,16 +455,258 @@ impl Tag {
 
         let _connection = establish_connection();
         return tags
+            .filter(schema::tags::deleted_at.is_null())
             .order(schema::tags::count.desc())
             .limit(limit)
             .offset(offset)
             .select((
                 schema::tags::name,
+                schema::tags::slug,
                 schema::tags::count
             ))
             .load::<SmallTag>(&_connection)
             .expect("E.");
     }
+
+    // облако: вес из числа объектов и вовлеченности - просмотры и минуты на странице тега
+    pub fn get_tag_cloud(limit: i64) -> Vec<CloudTag> {
+        let _connection = establish_connection();
+        let _tags = schema::tags::table
+            .filter(schema::tags::deleted_at.is_null())
+            .filter(schema::tags::count.gt(0))
+            .load::<Tag>(&_connection)
+            .expect("E.");
+        let mut list: Vec<CloudTag> = _tags.into_iter().map(|t| CloudTag {
+            weight: t.count as f64
+                + 2.0 * (t.view as f64).ln_1p()
+                + (t.seconds as f64 / 60.0).ln_1p(),
+            name:   t.name,
+            name_en: t.name_en,
+            slug:   t.slug,
+            count:  t.count,
+            level:  1,
+        }).collect();
+        list.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap_or(std::cmp::Ordering::Equal));
+        list.truncate(limit as usize);
+
+        if let (Some(max), Some(min)) = (list.first().map(|t| t.weight), list.last().map(|t| t.weight)) {
+            for t in list.iter_mut() {
+                if max > min {
+                    t.level = 1 + ((t.weight - min) / (max - min) * 4.0).round() as u8;
+                }
+            }
+        }
+        list.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        return list;
+    }
+
+    // тренды: просмотры страниц тегов (page 32) за последние days дней
+    // против такого же окна перед ними
+    pub fn get_trending_tags(days: i64, limit: usize) -> Vec<TrendingTag> {
+        use chrono::Duration;
+
+        let _connection = establish_connection();
+        let now = chrono::Local::now().naive_utc() + Duration::hours(3);
+        let start = now - Duration::days(days);
+        let _stats = schema::cookie_stats::table
+            .filter(schema::cookie_stats::page.eq(32))
+            .filter(schema::cookie_stats::created.gt(start - Duration::days(days)))
+            .select((schema::cookie_stats::link, schema::cookie_stats::created))
+            .load::<(String, chrono::NaiveDateTime)>(&_connection)
+            .expect("E.");
+
+        // slug -> (за окно, за прошлое окно)
+        let mut views: Vec<(String, i32, i32)> = Vec::new();
+        for (link, created) in _stats.iter() {
+            let slugs = if let Some(slug) = link.strip_prefix("/tag/") {
+                vec![slug.trim_end_matches('/').to_string()]
+            } else if let Some(param) = link.strip_prefix("/tags/") {
+                Tag::parse_slugs(param.trim_end_matches('/')).0
+            } else {
+                continue;
+            };
+            for slug in slugs.into_iter().filter(|s| !s.is_empty()) {
+                let i = match views.iter().position(|v| v.0 == slug) {
+                    Some(i) => i,
+                    None => {
+                        views.push((slug, 0, 0));
+                        views.len() - 1
+                    },
+                };
+                if created > &start {
+                    views[i].1 += 1;
+                } else {
+                    views[i].2 += 1;
+                }
+            }
+        }
+        views.retain(|v| v.1 > 0);
+        views.sort_by(|a, b| b.1.cmp(&a.1).then((b.1 - b.2).cmp(&(a.1 - a.2))));
+
+        let mut list = Vec::new();
+        for (slug, recent, previous) in views.into_iter() {
+            if list.len() >= limit {
+                break;
+            }
+            if let Some(_tag) = Tag::get_tag_with_slug(&slug) {
+                list.push(TrendingTag {
+                    name:     _tag.name,
+                    name_en:  _tag.name_en,
+                    slug:     _tag.slug,
+                    count:    _tag.count,
+                    views:    recent,
+                    previous: previous,
+                });
+            }
+        }
+        return list;
+    }
+
+    // пересчитываем count по активным и не удаленным элементам
+    pub fn recount(&self) -> () {
+        let _connection = establish_connection();
+        let ids = schema::tags_items::table
+            .filter(schema::tags_items::tag_id.eq(self.id))
+            .select(schema::tags_items::item_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        let count = schema::items::table
+            .filter(schema::items::id.eq_any(ids))
+            .filter(Item::get_visible_filter(None))
+            .filter(schema::items::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(&_connection)
+            .expect("E");
+        diesel::update(self)
+            .set(schema::tags::count.eq(count as i16))
+            .execute(&_connection)
+            .expect("E");
+    }
+    pub fn get_aliases(&self) -> Vec<TagAlias> {
+        let _connection = establish_connection();
+        return schema::tag_aliases::table
+            .filter(schema::tag_aliases::tag_id.eq(self.id))
+            .order(schema::tag_aliases::name.asc())
+            .load::<TagAlias>(&_connection)
+            .expect("E");
+    }
+    pub fn add_alias(&self, name: &str, slug: &str) -> () {
+        let _connection = establish_connection();
+        let _exists = schema::tag_aliases::table
+            .filter(schema::tag_aliases::tag_id.eq(self.id))
+            .filter(schema::tag_aliases::name.eq(name))
+            .filter(schema::tag_aliases::slug.eq(slug))
+            .select(schema::tag_aliases::id)
+            .first::<i32>(&_connection)
+            .is_ok();
+        if !_exists {
+            diesel::insert_into(schema::tag_aliases::table)
+                .values(&NewTagAlias {
+                    tag_id: self.id,
+                    name:   name.to_string(),
+                    slug:   slug.to_string(),
+                })
+                .execute(&_connection)
+                .expect("E.");
+        }
+    }
+    // другие названия через запятую. false - если название занято другим тегом
+    pub fn set_aliases(&self, names: &str) -> bool {
+        let _connection = establish_connection();
+        let mut names: Vec<String> = names
+            .split(",")
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .collect();
+        names.sort_by_key(|n| n.to_lowercase());
+        names.dedup_by_key(|n| n.to_lowercase());
+        for name in names.iter() {
+            if let Some(_tag) = Tag::get_tag_with_name(name) {
+                if _tag.id != self.id {
+                    return false;
+                }
+            }
+        }
+        // адреса слитых и переименованных тегов (slug не пустой) не трогаем
+        diesel::delete(schema::tag_aliases::table
+            .filter(schema::tag_aliases::tag_id.eq(self.id))
+            .filter(schema::tag_aliases::slug.eq("")))
+            .execute(&_connection)
+            .expect("E");
+        for name in names.iter() {
+            if name.to_lowercase() != self.name.to_lowercase() {
+                self.add_alias(name, "");
+            }
+        }
+        return true;
+    }
+
+    // переносит связи с элементами и другие названия в target,
+    // тег удаляется, его адрес ведет на target
+    pub fn merge_into(&self, target: &Tag) -> bool {
+        if self.id == target.id {
+            return false;
+        }
+        let _connection = establish_connection();
+        let result = _connection.transaction::<_, diesel::result::Error, _>(|| {
+            let _tag_items = schema::tags_items::table
+                .filter(schema::tags_items::tag_id.eq(self.id))
+                .load::<TagItems>(&_connection)?;
+            for _tag_item in _tag_items.iter() {
+                let _exists = schema::tags_items::table
+                    .filter(schema::tags_items::tag_id.eq(target.id))
+                    .filter(schema::tags_items::item_id.eq(_tag_item.item_id))
+                    .filter(schema::tags_items::types.eq(_tag_item.types))
+                    .select(schema::tags_items::id)
+                    .first::<i32>(&_connection)
+                    .is_ok();
+                if _exists {
+                    diesel::delete(_tag_item).execute(&_connection)?;
+                }
+                else {
+                    diesel::update(_tag_item)
+                        .set(schema::tags_items::tag_id.eq(target.id))
+                        .execute(&_connection)?;
+                }
+            }
+            diesel::update(schema::tag_aliases::table.filter(schema::tag_aliases::tag_id.eq(self.id)))
+                .set(schema::tag_aliases::tag_id.eq(target.id))
+                .execute(&_connection)?;
+            diesel::insert_into(schema::tag_aliases::table)
+                .values(&NewTagAlias {
+                    tag_id: target.id,
+                    name:   self.name.clone(),
+                    slug:   self.slug.clone(),
+                })
+                .execute(&_connection)?;
+            diesel::delete(self).execute(&_connection)?;
+            Ok(())
+        });
+        if result.is_err() {
+            return false;
+        }
+        target.recount();
+        return true;
+    }
+
+    // в корзину. связи с элементами остаются до очистки корзины
+    pub fn soft_delete(&self) -> () {
+        use chrono::Duration;
+
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::tags::deleted_at.eq(chrono::Local::now().naive_utc() + Duration::hours(3)))
+            .execute(&_connection)
+            .expect("E");
+    }
+    pub fn restore(&self) -> () {
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set(schema::tags::deleted_at.eq(None::<chrono::NaiveDateTime>))
+            .execute(&_connection)
+            .expect("E");
+        self.recount();
+    }
 }
 
 #[derive(Insertable)]
@@ -195,6 +720,7 @@ pub struct NewTag {
     pub view:     i32,
     pub height:   f64,
     pub seconds:  i32,
+    pub slug:     String,
 }
 
 #[derive(Queryable, Serialize, Deserialize, AsChangeset)]
@@ -235,3 +761,22 @@ pub struct NewTagItems {
     pub types:   i16,
     pub created: chrono::NaiveDateTime,
 }
+
+// другие названия тега (slug пустой) и адреса слитых
+// или переименованных тегов, которые ведут на tag_id
+#[derive(Identifiable, Serialize, Queryable)]
+#[table_name="tag_aliases"]
+pub struct TagAlias {
+    pub id:     i32,
+    pub tag_id: i32,
+    pub name:   String,
+    pub slug:   String,
+}
+
+#[derive(Insertable)]
+#[table_name="tag_aliases"]
+pub struct NewTagAlias {
+    pub tag_id: i32,
+    pub name:   String,
+    pub slug:   String,
+}
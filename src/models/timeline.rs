@@ -0,0 +1,119 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+};
+use serde::Serialize;
+use crate::schema::{
+    timeline_settings,
+    holidays,
+};
+use crate::utils::{
+    establish_connection,
+    TimelineSettingsForm,
+    HolidayForm,
+};
+
+
+#[derive(Debug, Serialize, Identifiable, Queryable)]
+#[table_name="timeline_settings"]
+pub struct TimelineSettings {
+    pub id:            i32,
+    pub workers:       i16,
+    pub hours_per_day: i16,
+    pub start_delay:   i16,
+    pub work_weekends: bool,
+    pub updated:       chrono::NaiveDateTime,
+}
+
+impl TimelineSettings {
+    // настройки одни; если записи нет - значения по умолчанию из main.sql
+    pub fn get() -> TimelineSettings {
+        let _connection = establish_connection();
+        return schema::timeline_settings::table
+            .order(schema::timeline_settings::id.asc())
+            .first::<TimelineSettings>(&_connection)
+            .unwrap_or(TimelineSettings {
+                id:            0,
+                workers:       2,
+                hours_per_day: 6,
+                start_delay:   1,
+                work_weekends: false,
+                updated:       chrono::Local::now().naive_utc() + chrono::Duration::hours(3),
+            });
+    }
+    pub fn edit(&self, form: TimelineSettingsForm) -> () {
+        let _connection = establish_connection();
+        diesel::update(self)
+            .set((
+                schema::timeline_settings::workers.eq(form.workers),
+                schema::timeline_settings::hours_per_day.eq(form.hours_per_day),
+                schema::timeline_settings::start_delay.eq(form.start_delay),
+                schema::timeline_settings::work_weekends.eq(form.work_weekends),
+                schema::timeline_settings::updated.eq(chrono::Local::now().naive_utc() + chrono::Duration::hours(3)),
+            ))
+            .execute(&_connection)
+            .expect("E");
+    }
+}
+
+#[derive(Debug, Serialize, Identifiable, Queryable)]
+#[table_name="holidays"]
+pub struct Holiday {
+    pub id:    i32,
+    pub day:   chrono::NaiveDate,
+    pub title: String,
+}
+
+impl Holiday {
+    pub fn get_all() -> Vec<Holiday> {
+        let _connection = establish_connection();
+        return schema::holidays::table
+            .order(schema::holidays::day.asc())
+            .load::<Holiday>(&_connection)
+            .expect("E.");
+    }
+    pub fn get_with_id(id: i32) -> Option<Holiday> {
+        let _connection = establish_connection();
+        return schema::holidays::table
+            .filter(schema::holidays::id.eq(id))
+            .first::<Holiday>(&_connection)
+            .ok();
+    }
+    // даты праздников начиная с from - для расчета календаря
+    pub fn get_days_from(from: chrono::NaiveDate) -> Vec<chrono::NaiveDate> {
+        let _connection = establish_connection();
+        return schema::holidays::table
+            .filter(schema::holidays::day.ge(from))
+            .select(schema::holidays::day)
+            .load::<chrono::NaiveDate>(&_connection)
+            .expect("E.");
+    }
+    pub fn create(form: HolidayForm) -> Option<Holiday> {
+        let _connection = establish_connection();
+        let new_holiday = NewHoliday {
+            day:   form.day?,
+            title: form.title,
+        };
+        return diesel::insert_into(schema::holidays::table)
+            .values(&new_holiday)
+            .get_result::<Holiday>(&_connection)
+            .ok();
+    }
+    pub fn delete(&self) -> () {
+        let _connection = establish_connection();
+        diesel::delete(self)
+            .execute(&_connection)
+            .expect("E");
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="holidays"]
+pub struct NewHoliday {
+    pub day:   chrono::NaiveDate,
+    pub title: String,
+}
@@ -18,7 +18,7 @@ use crate::utils::{establish_connection, NewUserForm};
 use crate::errors::Error;
 
 
-#[derive(Debug, Queryable, Serialize, Identifiable)]
+#[derive(Debug, Clone, Queryable, Serialize, Identifiable)]
 pub struct User {
     pub id:       i32,
     pub username: String,
@@ -41,6 +41,57 @@ impl User {
                 .execute(&_connection);
         }))
     }
+    pub fn get_image(&self) -> String {
+        if self.image.is_some() {
+            return self.image.as_deref().unwrap().to_string();
+        }
+        else {
+            return "/static/images/img.jpg".to_string();
+        }
+    }
+    // публикации автора: активные элементы всех типов
+    pub fn get_author_items_list(&self, page: i32, limit: i32, viewer: Option<&User>) -> (Vec<crate::models::Item>, i32) {
+        let mut next_page_number = 0;
+        let have_next: i32;
+        let object_list: Vec<crate::models::Item>;
+
+        if page > 1 {
+            let step = (page - 1) * limit;
+            have_next = page * limit + 1;
+            object_list = self.get_author_items(limit.into(), step.into(), viewer);
+        }
+        else {
+            have_next = limit + 1;
+            object_list = self.get_author_items(limit.into(), 0, viewer);
+        }
+        if self.get_author_items(1, have_next.into(), viewer).len() > 0 {
+            next_page_number = page + 1;
+        }
+
+        return (object_list, next_page_number);
+    }
+    pub fn get_author_items(&self, limit: i64, offset: i64, viewer: Option<&User>) -> Vec<crate::models::Item> {
+        let _connection = establish_connection();
+        return schema::items::table
+            .filter(schema::items::user_id.eq(self.id))
+            .filter(crate::models::Item::get_visible_filter(viewer))
+            .filter(schema::items::deleted_at.is_null())
+            .order(schema::items::created.desc())
+            .limit(limit)
+            .offset(offset)
+            .load::<crate::models::Item>(&_connection)
+            .expect("E.");
+    }
+    pub fn get_author_items_count(&self, viewer: Option<&User>) -> i64 {
+        let _connection = establish_connection();
+        return schema::items::table
+            .filter(schema::items::user_id.eq(self.id))
+            .filter(crate::models::Item::get_visible_filter(viewer))
+            .filter(schema::items::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(&_connection)
+            .unwrap_or(0);
+    }
     pub fn get_user_with_username(username: &String) -> Result<User, Error> {
         let _connection = establish_connection();
         return Ok(schema::users::table
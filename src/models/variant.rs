@@ -0,0 +1,173 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+    PgConnection,
+};
+use serde::{Serialize, Deserialize};
+use crate::schema::item_variants;
+use crate::utils::{
+    establish_connection,
+    get_price_acc_values,
+    VariantForm,
+};
+
+
+// варианты товара: свой артикул, цена и остаток
+#[derive(Debug, Serialize, Clone, Queryable, Identifiable)]
+pub struct ItemVariant {
+    pub id:        i32,
+    pub item_id:   i32,
+    pub title:     String,
+    pub title_en:  String,
+    pub sku:       String,
+    pub price:     i32,
+    pub price_acc: Option<i32>,
+    pub stock:     i32,
+    pub position:  i16,
+}
+
+impl ItemVariant {
+    pub fn get_item_variants(item_id: i32) -> Vec<ItemVariant> {
+        let _connection = establish_connection();
+        return schema::item_variants::table
+            .filter(schema::item_variants::item_id.eq(item_id))
+            .order(schema::item_variants::position.asc())
+            .load::<ItemVariant>(&_connection)
+            .expect("E");
+    }
+    pub fn is_available(&self) -> bool {
+        return self.stock > 0;
+    }
+    // цена с учетом скидки
+    pub fn get_price(&self) -> i32 {
+        return self.price - self.price_acc.unwrap_or(0);
+    }
+    pub fn get_stock_text(&self) -> String {
+        if self.stock > 0 {
+            return "В наличии: ".to_string() + &self.stock.to_string();
+        }
+        else {
+            return "Нет в наличии".to_string();
+        }
+    }
+
+    // списание со склада при принятии заказа, в транзакции заказа.
+    // false - если нужного остатка уже нет.
+    pub fn decrement_stock(_connection: &PgConnection, variant_id: i32, count: i32) -> Result<bool, diesel::result::Error> {
+        let updated = diesel::update (
+            schema::item_variants::table
+                .filter(schema::item_variants::id.eq(variant_id))
+                .filter(schema::item_variants::stock.ge(count))
+            )
+            .set(schema::item_variants::stock.eq(schema::item_variants::stock - count))
+            .execute(_connection)?;
+        Ok(updated > 0)
+    }
+
+    pub fn get_with_id(id: i32) -> Option<ItemVariant> {
+        let _connection = establish_connection();
+        return schema::item_variants::table
+            .filter(schema::item_variants::id.eq(id))
+            .first::<ItemVariant>(&_connection)
+            .ok();
+    }
+    // артикул уникален; except_id - сам редактируемый вариант
+    pub fn is_sku_taken(sku: &str, except_id: Option<i32>) -> bool {
+        let _connection = establish_connection();
+        return schema::item_variants::table
+            .filter(schema::item_variants::sku.eq(sku))
+            .filter(schema::item_variants::id.ne(except_id.unwrap_or(0)))
+            .select(schema::item_variants::id)
+            .first::<i32>(&_connection)
+            .is_ok();
+    }
+    // вариант из заказов удалять нельзя - заказ на него ссылается
+    pub fn is_ordered(&self) -> bool {
+        let _connection = establish_connection();
+        let in_orders = schema::orders::table
+            .filter(schema::orders::variant_id.eq(self.id))
+            .select(schema::orders::id)
+            .first::<i32>(&_connection)
+            .is_ok();
+        let in_items = schema::order_items::table
+            .filter(schema::order_items::variant_id.eq(self.id))
+            .select(schema::order_items::id)
+            .first::<i32>(&_connection)
+            .is_ok();
+        return in_orders || in_items;
+    }
+
+    pub fn create(item_id: i32, form: VariantForm) -> Option<ItemVariant> {
+        let _connection = establish_connection();
+        let new_variant = NewItemVariant {
+            item_id:   item_id,
+            title:     form.title.clone(),
+            title_en:  form.title_en.clone(),
+            sku:       form.sku.clone(),
+            price:     form.price,
+            price_acc: get_price_acc_values(&form.price, 3),
+            stock:     form.stock,
+            position:  form.position,
+        };
+        return diesel::insert_into(schema::item_variants::table)
+            .values(&new_variant)
+            .get_result::<ItemVariant>(&_connection)
+            .ok();
+    }
+    pub fn update_variant(&self, form: VariantForm) -> bool {
+        let _connection = establish_connection();
+        let _edit = EditItemVariant {
+            title:     form.title.clone(),
+            title_en:  form.title_en.clone(),
+            sku:       form.sku.clone(),
+            price:     form.price,
+            price_acc: get_price_acc_values(&form.price, 3),
+            stock:     form.stock,
+            position:  form.position,
+        };
+        return diesel::update(self)
+            .set(_edit)
+            .execute(&_connection)
+            .is_ok();
+    }
+    // из корзин вариант убираем вместе с ним
+    pub fn delete(&self) -> () {
+        let _connection = establish_connection();
+        diesel::delete(schema::cart_items::table.filter(schema::cart_items::variant_id.eq(self.id)))
+            .execute(&_connection)
+            .expect("E");
+        diesel::delete(self)
+            .execute(&_connection)
+            .expect("E");
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="item_variants"]
+pub struct NewItemVariant {
+    pub item_id:   i32,
+    pub title:     String,
+    pub title_en:  String,
+    pub sku:       String,
+    pub price:     i32,
+    pub price_acc: Option<i32>,
+    pub stock:     i32,
+    pub position:  i16,
+}
+
+#[derive(Queryable, Serialize, Deserialize, AsChangeset, Debug)]
+#[table_name="item_variants"]
+#[changeset_options(treat_none_as_null="true")]
+pub struct EditItemVariant {
+    pub title:     String,
+    pub title_en:  String,
+    pub sku:       String,
+    pub price:     i32,
+    pub price_acc: Option<i32>,
+    pub stock:     i32,
+    pub position:  i16,
+}
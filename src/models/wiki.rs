@@ -0,0 +1,121 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+};
+use serde::Serialize;
+use crate::schema::wiki_parents;
+use crate::models::Item;
+use crate::utils::establish_connection;
+
+
+// дерево статей базы знаний: родитель статьи
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+pub struct WikiParent {
+    pub id:        i32,
+    pub item_id:   i32,
+    pub parent_id: i32,
+}
+
+impl WikiParent {
+    pub fn get_parent_id(item_id: i32) -> Option<i32> {
+        let _connection = establish_connection();
+        return schema::wiki_parents::table
+            .filter(schema::wiki_parents::item_id.eq(item_id))
+            .select(schema::wiki_parents::parent_id)
+            .first::<i32>(&_connection)
+            .ok();
+    }
+    pub fn get_parent_slug(item_id: i32) -> String {
+        let _connection = establish_connection();
+        match WikiParent::get_parent_id(item_id) {
+            Some(parent_id) => schema::items::table
+                .filter(schema::items::id.eq(parent_id))
+                .select(schema::items::slug)
+                .first::<String>(&_connection)
+                .unwrap_or("".to_string()),
+            None => "".to_string(),
+        }
+    }
+    pub fn get_children_ids(parent_id: i32) -> Vec<i32> {
+        let _connection = establish_connection();
+        return schema::wiki_parents::table
+            .filter(schema::wiki_parents::parent_id.eq(parent_id))
+            .select(schema::wiki_parents::item_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+    }
+    // id предков, от корня к родителю статьи.
+    // глубина ограничена на случай испорченных данных.
+    pub fn get_ancestor_ids(item_id: i32) -> Vec<i32> {
+        let mut ids = Vec::new();
+        let mut current = item_id;
+        while let Some(parent_id) = WikiParent::get_parent_id(current) {
+            if parent_id == item_id || ids.contains(&parent_id) || ids.len() > 20 {
+                break;
+            }
+            ids.push(parent_id);
+            current = parent_id;
+        }
+        ids.reverse();
+        ids
+    }
+
+    // родитель по slug, пустая строка - корневая статья
+    pub fn set_parent_slug(item_id: i32, slug: &str) -> bool {
+        if slug.is_empty() {
+            return WikiParent::set_parent(item_id, None);
+        }
+        let _connection = establish_connection();
+        let parent_id = schema::items::table
+            .filter(schema::items::slug.eq(slug))
+            .filter(schema::items::types.eq(4))
+            .select(schema::items::id)
+            .first::<i32>(&_connection);
+        match parent_id {
+            Ok(parent_id) => WikiParent::set_parent(item_id, Some(parent_id)),
+            Err(_) => false,
+        }
+    }
+    // None - статья становится корневой.
+    // false - если родитель не статья или получится цикл.
+    pub fn set_parent(item_id: i32, parent_id: Option<i32>) -> bool {
+        let _connection = establish_connection();
+        if let Some(parent_id) = parent_id {
+            let _parent = schema::items::table
+                .filter(schema::items::id.eq(parent_id))
+                .filter(schema::items::types.eq(4))
+                .filter(schema::items::deleted_at.is_null())
+                .first::<Item>(&_connection);
+            if _parent.is_err()
+                || parent_id == item_id
+                || WikiParent::get_ancestor_ids(parent_id).contains(&item_id) {
+                return false;
+            }
+        }
+
+        diesel::delete(schema::wiki_parents::table.filter(schema::wiki_parents::item_id.eq(item_id)))
+            .execute(&_connection)
+            .expect("E");
+        if let Some(parent_id) = parent_id {
+            diesel::insert_into(schema::wiki_parents::table)
+                .values(&NewWikiParent {
+                    item_id:   item_id,
+                    parent_id: parent_id,
+                })
+                .execute(&_connection)
+                .expect("E.");
+        }
+        return true;
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="wiki_parents"]
+pub struct NewWikiParent {
+    pub item_id:   i32,
+    pub parent_id: i32,
+}
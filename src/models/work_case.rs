@@ -0,0 +1,275 @@
+use crate::schema;
+use crate::diesel::{
+    Queryable,
+    Insertable,
+    QueryDsl,
+    RunQueryDsl,
+    ExpressionMethods,
+    BoolExpressionMethods,
+};
+use serde::{Serialize, Deserialize};
+use crate::schema::{
+    work_cases,
+    work_case_techs,
+};
+use crate::models::ServeCategories;
+use crate::utils::{
+    establish_connection,
+    WorkCaseForm,
+};
+
+
+// структурированные поля кейса работы портфолио
+#[derive(Debug, Serialize, Clone, Queryable, Identifiable)]
+pub struct WorkCase {
+    pub id:          i32,
+    pub item_id:     i32,
+    pub client:      Option<String>,
+    pub client_en:   Option<String>,
+    pub industry:    Option<String>,
+    pub industry_en: Option<String>,
+    pub year:        Option<i16>,
+    pub duration:    Option<String>,
+    pub duration_en: Option<String>,
+    pub team_size:   Option<i16>,
+    pub results:     Option<String>,
+    pub results_en:  Option<String>,
+}
+
+fn get_field(value: &Option<String>, value_en: &Option<String>, l: u8) -> Option<String> {
+    if l == 2 && value_en.is_some() {
+        return value_en.clone();
+    }
+    value.clone()
+}
+fn get_value(value: &String) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    Some(value.to_string())
+}
+
+impl WorkCase {
+    pub fn get_for_item(item_id: i32) -> Option<WorkCase> {
+        let _connection = establish_connection();
+        return schema::work_cases::table
+            .filter(schema::work_cases::item_id.eq(item_id))
+            .first::<WorkCase>(&_connection)
+            .ok();
+    }
+    pub fn get_client(&self, l: u8) -> Option<String> {
+        get_field(&self.client, &self.client_en, l)
+    }
+    pub fn get_industry(&self, l: u8) -> Option<String> {
+        get_field(&self.industry, &self.industry_en, l)
+    }
+    pub fn get_duration(&self, l: u8) -> Option<String> {
+        get_field(&self.duration, &self.duration_en, l)
+    }
+    pub fn get_results(&self, l: u8) -> Option<String> {
+        get_field(&self.results, &self.results_en, l)
+    }
+
+    pub fn get_techs(item_id: i32) -> Vec<ServeCategories> {
+        let _connection = establish_connection();
+        let ids = schema::work_case_techs::table
+            .filter(schema::work_case_techs::item_id.eq(item_id))
+            .select(schema::work_case_techs::category_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        return schema::serve_categories::table
+            .filter(schema::serve_categories::id.eq_any(ids))
+            .order(schema::serve_categories::position.asc())
+            .load::<ServeCategories>(&_connection)
+            .expect("E");
+    }
+    pub fn get_tech_ids(item_id: i32) -> Vec<i32> {
+        let _connection = establish_connection();
+        return schema::work_case_techs::table
+            .filter(schema::work_case_techs::item_id.eq(item_id))
+            .select(schema::work_case_techs::category_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+    }
+    // все технологии для формы кейса
+    pub fn get_all_techs() -> Vec<ServeCategories> {
+        let _connection = establish_connection();
+        return schema::serve_categories::table
+            .order(schema::serve_categories::name.asc())
+            .load::<ServeCategories>(&_connection)
+            .expect("E");
+    }
+
+    // значения для фильтра на страницах категорий работ:
+    // отрасли, годы и технологии (id, название), которые есть в кейсах
+    pub fn get_filter_values(l: u8) -> (Vec<String>, Vec<i16>, Vec<(i32, String)>) {
+        let _connection = establish_connection();
+        let _cases = schema::work_cases::table
+            .load::<WorkCase>(&_connection)
+            .expect("E");
+
+        let mut industries = Vec::new();
+        let mut years = Vec::new();
+        for case in _cases.iter() {
+            if let Some(industry) = case.get_industry(l) {
+                if !industries.contains(&industry) {
+                    industries.push(industry);
+                }
+            }
+            if let Some(year) = case.year {
+                if !years.contains(&year) {
+                    years.push(year);
+                }
+            }
+        }
+        industries.sort();
+        years.sort();
+        years.reverse();
+
+        let tech_ids = schema::work_case_techs::table
+            .select(schema::work_case_techs::category_id)
+            .distinct()
+            .load::<i32>(&_connection)
+            .expect("E");
+        let techs = schema::serve_categories::table
+            .filter(schema::serve_categories::id.eq_any(tech_ids))
+            .order(schema::serve_categories::position.asc())
+            .load::<ServeCategories>(&_connection)
+            .expect("E")
+            .into_iter()
+            .map(|c| (c.id, if l == 2 { c.name_en } else { c.name }))
+            .collect();
+        (industries, years, techs)
+    }
+
+    // id работ, подходящих под фильтр кейса.
+    // отрасль сравнивается с обоими языками.
+    pub fn get_filtered_item_ids(industry: Option<&str>, year: Option<i16>, tech: Option<i32>) -> Vec<i32> {
+        let _connection = establish_connection();
+        let mut query = schema::work_cases::table
+            .select(schema::work_cases::item_id)
+            .into_boxed();
+        if let Some(industry) = industry {
+            query = query.filter(schema::work_cases::industry.eq(industry)
+                .or(schema::work_cases::industry_en.eq(industry)));
+        }
+        if let Some(year) = year {
+            query = query.filter(schema::work_cases::year.eq(year));
+        }
+        if let Some(tech) = tech {
+            query = query.filter(schema::work_cases::item_id.eq_any(
+                schema::work_case_techs::table
+                    .filter(schema::work_case_techs::category_id.eq(tech))
+                    .select(schema::work_case_techs::item_id)
+            ));
+        }
+        return query
+            .load::<i32>(&_connection)
+            .expect("E");
+    }
+
+    // создает или обновляет кейс работы, технологии перезаписываются
+    pub fn save(item_id: i32, form: WorkCaseForm) -> () {
+        let _connection = establish_connection();
+        let _case = EditWorkCase {
+            client:      get_value(&form.client),
+            client_en:   get_value(&form.client_en),
+            industry:    get_value(&form.industry),
+            industry_en: get_value(&form.industry_en),
+            year:        form.year,
+            duration:    get_value(&form.duration),
+            duration_en: get_value(&form.duration_en),
+            team_size:   form.team_size,
+            results:     get_value(&form.results),
+            results_en:  get_value(&form.results_en),
+        };
+        if let Some(_exists) = WorkCase::get_for_item(item_id) {
+            diesel::update(&_exists)
+                .set(_case)
+                .execute(&_connection)
+                .expect("E");
+        }
+        else {
+            let _new = NewWorkCase {
+                item_id:     item_id,
+                client:      _case.client,
+                client_en:   _case.client_en,
+                industry:    _case.industry,
+                industry_en: _case.industry_en,
+                year:        _case.year,
+                duration:    _case.duration,
+                duration_en: _case.duration_en,
+                team_size:   _case.team_size,
+                results:     _case.results,
+                results_en:  _case.results_en,
+            };
+            diesel::insert_into(schema::work_cases::table)
+                .values(&_new)
+                .execute(&_connection)
+                .expect("E.");
+        }
+
+        diesel::delete(schema::work_case_techs::table.filter(schema::work_case_techs::item_id.eq(item_id)))
+            .execute(&_connection)
+            .expect("E");
+        let mut techs = form.techs.clone();
+        techs.sort();
+        techs.dedup();
+        for category_id in techs.iter() {
+            let _exists = schema::serve_categories::table
+                .filter(schema::serve_categories::id.eq(category_id))
+                .select(schema::serve_categories::id)
+                .first::<i32>(&_connection)
+                .is_ok();
+            if _exists {
+                diesel::insert_into(schema::work_case_techs::table)
+                    .values(&NewWorkCaseTech {
+                        item_id:     item_id,
+                        category_id: *category_id,
+                    })
+                    .execute(&_connection)
+                    .expect("E.");
+            }
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="work_cases"]
+pub struct NewWorkCase {
+    pub item_id:     i32,
+    pub client:      Option<String>,
+    pub client_en:   Option<String>,
+    pub industry:    Option<String>,
+    pub industry_en: Option<String>,
+    pub year:        Option<i16>,
+    pub duration:    Option<String>,
+    pub duration_en: Option<String>,
+    pub team_size:   Option<i16>,
+    pub results:     Option<String>,
+    pub results_en:  Option<String>,
+}
+
+#[derive(Queryable, Serialize, Deserialize, AsChangeset, Debug)]
+#[table_name="work_cases"]
+#[changeset_options(treat_none_as_null="true")]
+pub struct EditWorkCase {
+    pub client:      Option<String>,
+    pub client_en:   Option<String>,
+    pub industry:    Option<String>,
+    pub industry_en: Option<String>,
+    pub year:        Option<i16>,
+    pub duration:    Option<String>,
+    pub duration_en: Option<String>,
+    pub team_size:   Option<i16>,
+    pub results:     Option<String>,
+    pub results_en:  Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name="work_case_techs"]
+pub struct NewWorkCaseTech {
+    pub item_id:     i32,
+    pub category_id: i32,
+}
@@ -10,6 +10,15 @@ use crate::views::{
     tag_progs,
     serve_progs,
     help_progs,
+    transfer_progs,
+    trash_progs,
+    cart_progs,
+    author_progs,
+    api_progs,
+    discount_progs,
+    currency_progs,
+    user_group_progs,
+    timeline_progs,
     search_progs,
     pages,
     progs,
@@ -31,5 +40,14 @@ pub fn routes(cfg: &mut web::ServiceConfig) {
     .configure(auth::auth_routes)
     .configure(help_progs::help_routes)
     .configure(order_progs::order_routes)
+    .configure(transfer_progs::transfer_routes)
+    .configure(trash_progs::trash_routes)
+    .configure(cart_progs::cart_routes)
+    .configure(author_progs::author_routes)
+    .configure(api_progs::api_routes)
+    .configure(discount_progs::discount_routes)
+    .configure(currency_progs::currency_routes)
+    .configure(user_group_progs::user_group_routes)
+    .configure(timeline_progs::timeline_routes)
     ;
 }
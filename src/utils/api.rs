@@ -0,0 +1,517 @@
+// публичное API каталога /api/v1/: только опубликованные элементы,
+// открытые всем (is_active, visibility 1, не в корзине).
+// параметры: ?page=2&limit=20&lang=en&category=slug&q=текст&types=blogs&days=7
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    http::header,
+};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use serde::{Deserialize, Serialize};
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+    BoolExpressionMethods,
+    PgTextExpressionMethods,
+};
+use crate::models::{
+    Item,
+    Serve,
+    ServeCategories,
+    TechCategories,
+    Categories,
+    Tag,
+    CloudTag,
+    TrendingTag,
+};
+use crate::utils::{
+    establish_connection,
+    get_linguage_storage,
+};
+
+
+#[derive(Debug, Deserialize)]
+pub struct ApiParams {
+    pub page:     Option<i32>,
+    pub limit:    Option<i64>,
+    pub lang:     Option<String>, // ru, en
+    pub category: Option<String>, // slug категории
+    pub q:        Option<String>,
+    pub types:    Option<String>, // для поиска: blogs, services...
+    pub days:     Option<i64>,    // окно трендов тегов
+}
+impl ApiParams {
+    pub fn get(req: &HttpRequest) -> ApiParams {
+        match web::Query::<ApiParams>::from_query(&req.query_string()) {
+            Ok(params) => params.into_inner(),
+            Err(_) => ApiParams {
+                page:     None,
+                limit:    None,
+                lang:     None,
+                category: None,
+                q:        None,
+                types:    None,
+                days:     None,
+            },
+        }
+    }
+    pub fn get_page(&self) -> i32 {
+        match self.page {
+            Some(page) if page > 1 => page,
+            _ => 1,
+        }
+    }
+    // по умолчанию 20, не больше 100
+    pub fn get_limit(&self) -> i64 {
+        match self.limit {
+            Some(limit) if limit > 0 => limit.min(100),
+            _ => 20,
+        }
+    }
+    pub fn get_offset(&self) -> i64 {
+        (self.get_page() as i64 - 1) * self.get_limit()
+    }
+    // по умолчанию неделя, не больше 30 дней
+    pub fn get_days(&self) -> i64 {
+        match self.days {
+            Some(days) if days > 0 => days.min(30),
+            _ => 7,
+        }
+    }
+    pub fn get_linguage(&self) -> u8 {
+        match self.lang.as_deref() {
+            Some("en") => 2,
+            Some("ru") => 1,
+            _ => get_linguage_storage(),
+        }
+    }
+}
+
+// blogs -> 1 ... helps -> 6
+pub fn get_api_types(name: &str) -> Option<i16> {
+    match name {
+        "blogs"    => Some(1),
+        "services" => Some(2),
+        "stores"   => Some(3),
+        "wikis"    => Some(4),
+        "works"    => Some(5),
+        "helps"    => Some(6),
+        _ => None,
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ApiList<T> {
+    pub page:      i32,
+    pub next_page: i32, // 0 - страниц больше нет
+    pub items:     Vec<T>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ApiItem {
+    pub id:           i32,
+    pub types:        i16,
+    pub slug:         String,
+    pub title:        String,
+    pub description:  Option<String>,
+    pub image:        String,
+    pub link:         String,
+    pub price:        i32,
+    pub price_acc:    Option<i32>,
+    pub created:      chrono::NaiveDateTime,
+    pub word_count:   i32,
+    pub reading_time: i16,
+    pub categories:   Vec<String>,
+    pub tags:         Vec<String>,
+}
+#[derive(Serialize, Debug)]
+pub struct ApiItemDetail {
+    #[serde(flatten)]
+    pub item:    ApiItem,
+    pub content: Option<String>,
+    pub serves:  Vec<ApiServe>,
+}
+#[derive(Serialize, Debug)]
+pub struct ApiCategory {
+    pub id:          i32,
+    pub slug:        String,
+    pub name:        String,
+    pub description: Option<String>,
+    pub image:       Option<String>,
+    pub count:       i16,
+}
+#[derive(Serialize, Debug)]
+pub struct ApiTag {
+    pub name:  String,
+    pub slug:  String,
+    pub count: i16,
+}
+// выборка по нескольким тегам: объекты по типам и теги для уточнения
+#[derive(Serialize, Debug)]
+pub struct ApiTagSet {
+    pub tags:    Vec<ApiTag>,
+    pub mode:    String, // and, or
+    pub groups:  Vec<ApiTagGroup>,
+    pub related: Vec<ApiTag>,
+}
+#[derive(Serialize, Debug)]
+pub struct ApiTagGroup {
+    pub types: String,
+    pub count: usize,
+    pub items: ApiList<ApiItem>,
+}
+#[derive(Serialize, Debug)]
+pub struct ApiServe {
+    pub id:          i32,
+    pub name:        String,
+    pub description: Option<String>,
+    pub price:       i32,
+    pub man_hours:   i16,
+    pub is_default:  bool,
+    pub parent_id:   Option<i32>, // вариант опции
+}
+#[derive(Serialize, Debug)]
+pub struct ApiServeCategory {
+    pub id:            i32,
+    pub name:          String,
+    pub description:   Option<String>,
+    pub default_price: i32,
+    pub serves:        Vec<ApiServe>,
+}
+#[derive(Serialize, Debug)]
+pub struct ApiTechCategory {
+    pub id:         i32,
+    pub name:       String,
+    pub level:      i16,
+    pub categories: Vec<ApiServeCategory>,
+}
+
+fn get_text(value: &String, value_en: &String, l: u8) -> String {
+    if l == 2 && !value_en.is_empty() {
+        return value_en.clone();
+    }
+    value.clone()
+}
+fn get_option(value: &Option<String>, value_en: &Option<String>, l: u8) -> Option<String> {
+    if l == 2 && value_en.is_some() {
+        return value_en.clone();
+    }
+    value.clone()
+}
+
+impl ApiItem {
+    pub fn from_item(item: &Item, l: u8) -> ApiItem {
+        let (word_count, reading_time) = if l == 2 {
+            (item.word_count_en, item.reading_time_en)
+        } else {
+            (item.word_count, item.reading_time)
+        };
+        ApiItem {
+            id:           item.id,
+            types:        item.types,
+            slug:         item.slug.clone(),
+            title:        get_text(&item.title, &item.title_en, l),
+            description:  get_option(&item.description, &item.description_en, l),
+            image:        item.get_image(),
+            link:         item.get_link(),
+            price:        item.price,
+            price_acc:    item.price_acc,
+            created:      item.created,
+            word_count:   word_count,
+            reading_time: reading_time,
+            categories:   item.get_categories().into_iter().map(|c| c.slug).collect(),
+            tags:         item.get_tags(l).into_iter().map(|t| t.name).collect(),
+        }
+    }
+}
+impl ApiItemDetail {
+    pub fn from_item(item: &Item, l: u8) -> ApiItemDetail {
+        ApiItemDetail {
+            item:    ApiItem::from_item(item, l),
+            content: get_option(&item.content, &item.content_en, l),
+            serves:  item.get_serves().iter().map(|s| ApiServe::from_serve(s, l)).collect(),
+        }
+    }
+}
+impl ApiServe {
+    pub fn from_serve(serve: &Serve, l: u8) -> ApiServe {
+        ApiServe {
+            id:          serve.id,
+            name:        get_text(&serve.name, &serve.name_en, l),
+            description: get_option(&serve.description, &serve.description_en, l),
+            price:       serve.price,
+            man_hours:   serve.man_hours,
+            is_default:  serve.is_default,
+            parent_id:   serve.serve_id,
+        }
+    }
+}
+
+// страница списка: грузим на один элемент больше, чтобы узнать о следующей
+fn get_api_list(mut _items: Vec<Item>, params: &ApiParams) -> ApiList<ApiItem> {
+    let l = params.get_linguage();
+    let mut next_page = 0;
+    if _items.len() as i64 > params.get_limit() {
+        _items.truncate(params.get_limit() as usize);
+        next_page = params.get_page() + 1;
+    }
+    ApiList {
+        page:      params.get_page(),
+        next_page: next_page,
+        items:     _items.iter().map(|i| ApiItem::from_item(i, l)).collect(),
+    }
+}
+
+pub fn get_api_items(types: i16, params: &ApiParams) -> ApiList<ApiItem> {
+    let _connection = establish_connection();
+    let mut query = schema::items::table
+        .filter(schema::items::types.eq(types))
+        .filter(schema::items::deleted_at.is_null())
+        .filter(Item::get_visible_filter(None))
+        .into_boxed();
+    if let Some(slug) = params.category.as_deref() {
+        let cat_ids = schema::categories::table
+            .filter(schema::categories::slug.eq(slug))
+            .filter(schema::categories::types.eq(types))
+            .select(schema::categories::id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        let ids = schema::category::table
+            .filter(schema::category::category_id.eq_any(cat_ids))
+            .select(schema::category::item_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        query = query.filter(schema::items::id.eq_any(ids));
+    }
+    let _items = query
+        .order(schema::items::created.desc())
+        .limit(params.get_limit() + 1)
+        .offset(params.get_offset())
+        .load::<Item>(&_connection)
+        .expect("E");
+    get_api_list(_items, params)
+}
+
+pub fn get_api_item(types: i16, slug: &str, l: u8) -> Option<ApiItemDetail> {
+    let _connection = establish_connection();
+    return schema::items::table
+        .filter(schema::items::slug.eq(slug))
+        .filter(schema::items::types.eq(types))
+        .filter(schema::items::deleted_at.is_null())
+        .filter(Item::get_visible_filter(None))
+        .first::<Item>(&_connection)
+        .ok()
+        .map(|i| ApiItemDetail::from_item(&i, l));
+}
+
+pub fn get_api_search(params: &ApiParams) -> ApiList<ApiItem> {
+    let _connection = establish_connection();
+    let q = "%".to_string() + params.q.as_deref().unwrap_or("").trim() + &"%".to_string();
+    let mut query = schema::items::table
+        .filter(schema::items::title.ilike(&q)
+            .or(schema::items::title_en.ilike(&q))
+            .or(schema::items::description.ilike(&q))
+            .or(schema::items::content.ilike(&q)))
+        .filter(schema::items::deleted_at.is_null())
+        .filter(Item::get_visible_filter(None))
+        .into_boxed();
+    if let Some(types) = params.types.as_deref().and_then(get_api_types) {
+        query = query.filter(schema::items::types.eq(types));
+    }
+    let _items = query
+        .order(schema::items::created.desc())
+        .limit(params.get_limit() + 1)
+        .offset(params.get_offset())
+        .load::<Item>(&_connection)
+        .expect("E");
+    get_api_list(_items, params)
+}
+
+pub fn get_api_categories(types: i16, l: u8) -> Vec<ApiCategory> {
+    let _connection = establish_connection();
+    return schema::categories::table
+        .filter(schema::categories::types.eq(types))
+        .filter(schema::categories::deleted_at.is_null())
+        .order(schema::categories::position.asc())
+        .load::<Categories>(&_connection)
+        .expect("E")
+        .iter()
+        .map(|c| ApiCategory {
+            id:          c.id,
+            slug:        c.slug.clone(),
+            name:        get_text(&c.name, &c.name_en, l),
+            description: get_option(&c.description, &c.description_en, l),
+            image:       c.image.clone(),
+            count:       c.count,
+        })
+        .collect();
+}
+
+pub fn get_api_tags(params: &ApiParams) -> ApiList<ApiTag> {
+    let _connection = establish_connection();
+    let l = params.get_linguage();
+    let mut _tags = schema::tags::table
+        .filter(schema::tags::deleted_at.is_null())
+        .order(schema::tags::count.desc())
+        .limit(params.get_limit() + 1)
+        .offset(params.get_offset())
+        .select((
+            schema::tags::name,
+            schema::tags::name_en,
+            schema::tags::slug,
+            schema::tags::count,
+        ))
+        .load::<(String, String, String, i16)>(&_connection)
+        .expect("E");
+    let mut next_page = 0;
+    if _tags.len() as i64 > params.get_limit() {
+        _tags.truncate(params.get_limit() as usize);
+        next_page = params.get_page() + 1;
+    }
+    ApiList {
+        page:      params.get_page(),
+        next_page: next_page,
+        items:     _tags.iter().map(|t| ApiTag {
+            name:  get_text(&t.0, &t.1, l),
+            slug:  t.2.clone(),
+            count: t.3,
+        }).collect(),
+    }
+}
+
+// /api/v1/tags/rust+actix/ - все теги, /api/v1/tags/rust,actix/ - любой;
+// ?types=blogs - только один тип, page и limit - внутри каждого типа
+pub fn get_api_tag_set(param: &str, params: &ApiParams) -> Option<ApiTagSet> {
+    let _connection = establish_connection();
+    let l = params.get_linguage();
+    let (slugs, is_and) = Tag::parse_slugs(param);
+    let _tags = Tag::get_tags_with_slugs(&slugs);
+    if _tags.is_empty() || _tags.len() != slugs.len() || _tags.len() > 5 {
+        return None;
+    }
+    let tag_ids: Vec<i32> = _tags.iter().map(|t| t.id).collect();
+    let _items = Tag::get_items_for_tags(&tag_ids, is_and, None);
+    let only_types = params.types.as_deref().and_then(get_api_types);
+
+    let mut groups = Vec::new();
+    for name in ["blogs", "services", "stores", "wikis", "works", "helps"].iter() {
+        let types = get_api_types(name).expect("E");
+        if only_types.is_some() && only_types != Some(types) {
+            continue;
+        }
+        let ids: Vec<i32> = _items.iter().filter(|i| i.1 == types).map(|i| i.0).collect();
+        if ids.is_empty() {
+            continue;
+        }
+        let _list = schema::items::table
+            .filter(schema::items::id.eq_any(&ids))
+            .order(schema::items::created.desc())
+            .limit(params.get_limit() + 1)
+            .offset(params.get_offset())
+            .load::<Item>(&_connection)
+            .expect("E");
+        groups.push(ApiTagGroup {
+            types: name.to_string(),
+            count: ids.len(),
+            items: get_api_list(_list, params),
+        });
+    }
+
+    let item_ids: Vec<i32> = _items.iter().map(|i| i.0).collect();
+    Some(ApiTagSet {
+        tags:    _tags.iter().map(|t| ApiTag {
+            name:  get_text(&t.name, &t.name_en, l),
+            slug:  t.slug.clone(),
+            count: t.count,
+        }).collect(),
+        mode:    if is_and { "and".to_string() } else { "or".to_string() },
+        groups:  groups,
+        related: Tag::get_related_tags(&tag_ids, &item_ids, 15).into_iter().map(|t| ApiTag {
+            name:  t.name,
+            slug:  t.slug,
+            count: t.count,
+        }).collect(),
+    })
+}
+
+pub fn get_api_tag_cloud(params: &ApiParams) -> Vec<CloudTag> {
+    let l = params.get_linguage();
+    let mut list = Tag::get_tag_cloud(params.get_limit());
+    for t in list.iter_mut() {
+        t.name = get_text(&t.name, &t.name_en, l);
+    }
+    list
+}
+
+pub fn get_api_trending_tags(params: &ApiParams) -> Vec<TrendingTag> {
+    let l = params.get_linguage();
+    let mut list = Tag::get_trending_tags(params.get_days(), params.get_limit() as usize);
+    for t in list.iter_mut() {
+        t.name = get_text(&t.name, &t.name_en, l);
+    }
+    list
+}
+
+// технологии -> категории опций -> опции с вариантами
+pub fn get_api_serves(l: u8) -> Vec<ApiTechCategory> {
+    let _connection = establish_connection();
+    let _techs = schema::tech_categories::table
+        .order(schema::tech_categories::position.asc())
+        .load::<TechCategories>(&_connection)
+        .expect("E");
+    let _cats = schema::serve_categories::table
+        .order(schema::serve_categories::position.asc())
+        .load::<ServeCategories>(&_connection)
+        .expect("E");
+    let _serves = schema::serve::table
+        .order(schema::serve::position.asc())
+        .load::<Serve>(&_connection)
+        .expect("E");
+
+    _techs.iter().map(|tech| ApiTechCategory {
+        id:         tech.id,
+        name:       get_text(&tech.name, &tech.name_en, l),
+        level:      tech.level,
+        categories: _cats.iter()
+            .filter(|c| c.category_id == tech.id)
+            .map(|c| ApiServeCategory {
+                id:            c.id,
+                name:          get_text(&c.name, &c.name_en, l),
+                description:   get_option(&c.description, &c.description_en, l),
+                default_price: c.default_price,
+                serves:        _serves.iter()
+                    .filter(|s| s.category_id == c.id)
+                    .map(|s| ApiServe::from_serve(s, l))
+                    .collect(),
+            })
+            .collect(),
+    }).collect()
+}
+
+// json с ETag: при совпадении If-None-Match отдаем 304 без тела
+pub fn get_api_response<T: Serialize>(req: &HttpRequest, data: &T) -> HttpResponse {
+    let body = serde_json::to_string(data).unwrap_or("null".to_string());
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let etag = format!("\"{:x}\"", hasher.finish());
+
+    let if_none_match = req.headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok());
+    if let Some(value) = if_none_match {
+        if value.split(",").any(|v| v.trim().trim_start_matches("W/") == etag) {
+            return HttpResponse::NotModified()
+                .insert_header((header::ETAG, etag))
+                .finish();
+        }
+    }
+    HttpResponse::Ok()
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::CACHE_CONTROL, "public, max-age=60"))
+        .content_type("application/json")
+        .body(body)
+}
@@ -0,0 +1,356 @@
+// расчет стоимости услуги по выбранным опциям. Один и тот же
+// для калькулятора на сайте (/api/calculate/) и для заказов,
+// чтобы цена на странице и в заказе всегда совпадала.
+use actix_web::{
+    HttpRequest,
+    web,
+};
+use serde::{Deserialize, Serialize};
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+};
+use crate::models::{Serve, ServeRelation, Discount};
+use crate::utils::{
+    establish_connection,
+    get_api_types,
+    get_timeline,
+    Timeline,
+};
+
+
+// ?tech_cat=3&serves=1,2,5&types=services&promo=CODE
+// или сразу несколько тех. категорий: ?tech_cats=3,4&serves=...
+#[derive(Debug, Deserialize)]
+pub struct CalculateParams {
+    pub tech_cat:  Option<i32>,
+    pub tech_cats: Option<String>,
+    pub serves:    Option<String>,
+    pub types:     Option<String>, // для правил скидок, по умолчанию услуги
+    pub promo:     Option<String>,
+}
+impl CalculateParams {
+    pub fn get(req: &HttpRequest) -> CalculateParams {
+        match web::Query::<CalculateParams>::from_query(&req.query_string()) {
+            Ok(params) => params.into_inner(),
+            Err(_) => CalculateParams {
+                tech_cat:  None,
+                tech_cats: None,
+                serves:    None,
+                types:     None,
+                promo:     None,
+            },
+        }
+    }
+    pub fn get_types(&self) -> i16 {
+        self.types.as_deref().and_then(get_api_types).unwrap_or(2)
+    }
+    // None - если в списке есть не числа
+    pub fn get_serve_ids(&self) -> Option<Vec<i32>> {
+        get_ids(self.serves.as_deref().unwrap_or(""))
+    }
+    pub fn get_tech_cat_ids(&self) -> Option<Vec<i32>> {
+        let mut ids = get_ids(self.tech_cats.as_deref().unwrap_or(""))?;
+        if let Some(id) = self.tech_cat {
+            if !ids.contains(&id) {
+                ids.insert(0, id);
+            }
+        }
+        Some(ids)
+    }
+}
+
+fn get_ids(list: &str) -> Option<Vec<i32>> {
+    let mut ids = Vec::new();
+    for id in list.split(",") {
+        if id.trim().is_empty() {
+            continue;
+        }
+        match id.trim().parse::<i32>() {
+            Ok(id) => ids.push(id),
+            Err(_) => return None,
+        }
+    }
+    Some(ids)
+}
+
+#[derive(Serialize, Debug)]
+pub struct CalculateLine {
+    pub id:          i32,
+    pub name:        String,
+    pub category_id: i32,
+    pub tech_cat_id: i32,
+    pub parent_id:   Option<i32>, // вариант опции
+    pub price:       i32,
+    pub man_hours:   i16,
+    pub is_default:  bool,
+    pub is_added:    bool,        // обязательная опция, добавлена расчетом
+}
+
+// что калькулятор поменял в выборе по связям опций и почему
+#[derive(Serialize, Debug)]
+pub struct CalculateNote {
+    pub serve_id: Option<i32>,    // None - противоречив выбор в целом
+    pub action:   String,         // added, removed, recommended, conflict
+    pub text:     String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Calculation {
+    pub lines:     Vec<CalculateLine>,
+    pub ignored:   Vec<i32>,      // чужие опции и лишние варианты из одной группы
+    pub notes:     Vec<CalculateNote>,
+    pub man_hours: i32,
+    pub price:     i32,
+    pub price_acc: Option<i32>,   // скидка
+    pub discount:  Option<String>, // правило или промокод, по которому скидка
+    pub total:     i32,
+    pub timeline:  Timeline,       // оценка сроков по man_hours
+}
+impl Calculation {
+    pub fn get_serve_ids(&self) -> Vec<i32> {
+        self.lines.iter().map(|line| line.id).collect()
+    }
+    pub fn get_tech_cat_ids(&self) -> Vec<i32> {
+        let mut ids = Vec::new();
+        for line in self.lines.iter() {
+            if !ids.contains(&line.tech_cat_id) {
+                ids.push(line.tech_cat_id);
+            }
+        }
+        ids
+    }
+}
+
+fn get_line(_serve: &Serve, is_added: bool, l: u8) -> CalculateLine {
+    CalculateLine {
+        id:          _serve.id,
+        name:        if l == 2 { _serve.name_en.clone() } else { _serve.name.clone() },
+        category_id: _serve.category_id,
+        tech_cat_id: _serve.tech_cat_id,
+        parent_id:   _serve.serve_id,
+        price:       _serve.price,
+        man_hours:   _serve.man_hours,
+        is_default:  _serve.is_default,
+        is_added:    is_added,
+    }
+}
+
+fn get_note(serve_id: Option<i32>, action: &str, text: String) -> CalculateNote {
+    CalculateNote {
+        serve_id: serve_id,
+        action:   action.to_string(),
+        text:     text,
+    }
+}
+
+// больше проходов по связям не делаем: значит, связи кольцевые
+const MAX_RELATION_PASSES: usize = 10;
+
+// связи опций (serve_relations): недостающие обязательные опции добавляем,
+// несовместимые убираем, рекомендуемые только подсказываем.
+// нужная опция может быть из другой тех. категории - тогда берем ее из базы.
+fn apply_relations(lines: &mut Vec<CalculateLine>, serves: &Vec<Serve>, l: u8) -> Vec<CalculateNote> {
+    let _connection = establish_connection();
+    let get_serve = |id: i32| -> Option<Serve> {
+        match serves.iter().find(|s| s.id == id) {
+            Some(_serve) => Some(_serve.clone()),
+            None => schema::serve::table
+                .filter(schema::serve::id.eq(id))
+                .first::<Serve>(&_connection)
+                .ok(),
+        }
+    };
+    let mut notes = Vec::new();
+    let mut excluded: Vec<i32> = Vec::new();
+
+    // добавленные опции сами могут что-то требовать - проходим,
+    // пока выбор меняется (с ограничением на случай кольцевых связей)
+    let mut is_settled = false;
+    for _ in 0..MAX_RELATION_PASSES {
+        let ids: Vec<i32> = lines.iter().map(|line| line.id).collect();
+        let relations = ServeRelation::get_for_serves(&ids);
+        let mut changed = false;
+
+        // из несовместимых убираем ту, что добавил калькулятор, иначе - вторую
+        for relation in relations.iter().filter(|r| r.types == 2) {
+            let first = lines.iter().position(|line| line.id == relation.serve_id);
+            let second = lines.iter().position(|line| line.id == relation.target_id);
+            if let (Some(first), Some(second)) = (first, second) {
+                let (drop, keep) = match lines[first].is_added && !lines[second].is_added {
+                    true => (first, second),
+                    false => (second, first),
+                };
+                let text = match l {
+                    2 => format!("\"{}\" removed: incompatible with \"{}\"", lines[drop].name, lines[keep].name),
+                    _ => format!("«{}» убрана: несовместима с «{}»", lines[drop].name, lines[keep].name),
+                };
+                notes.push(get_note(Some(lines[drop].id), "removed", text));
+                excluded.push(lines[drop].id);
+                lines.remove(drop);
+                changed = true;
+                break;
+            }
+        }
+        if changed {
+            continue;
+        }
+
+        for relation in relations.iter().filter(|r| r.types == 1 && ids.contains(&r.serve_id)) {
+            let target = match get_serve(relation.target_id) {
+                Some(target) => target,
+                None => continue,
+            };
+            // требование опции-родителя выполняет и любой ее вариант
+            let is_done = lines.iter().any(|line| {
+                line.id == target.id || (target.serve_id.is_none() && line.parent_id == Some(target.id))
+            });
+            if is_done {
+                continue;
+            }
+            let requiring = match lines.iter().position(|line| line.id == relation.serve_id) {
+                Some(requiring) => requiring,
+                None => continue,
+            };
+            let requiring_name = lines[requiring].name.clone();
+            let target_name = if l == 2 { target.name_en.clone() } else { target.name.clone() };
+
+            // нужную опцию уже убрали как несовместимую - убираем и ту, что ее требует
+            if excluded.contains(&target.id) {
+                let text = match l {
+                    2 => format!("\"{}\" removed: it requires \"{}\", which is incompatible with the selection", requiring_name, target_name),
+                    _ => format!("«{}» убрана: ей нужна «{}», а она несовместима с выбранными опциями", requiring_name, target_name),
+                };
+                notes.push(get_note(Some(lines[requiring].id), "removed", text));
+                excluded.push(lines[requiring].id);
+                lines.remove(requiring);
+                changed = true;
+                break;
+            }
+
+            // из группы (опция и ее варианты) остается одна - нужная
+            let group_id = target.serve_id.unwrap_or(target.id);
+            for line in lines.iter().filter(|line| line.id == group_id || line.parent_id == Some(group_id)) {
+                let text = match l {
+                    2 => format!("\"{}\" replaced with \"{}\", required by \"{}\"", line.name, target_name, requiring_name),
+                    _ => format!("«{}» заменена на «{}»: ее требует «{}»", line.name, target_name, requiring_name),
+                };
+                notes.push(get_note(Some(line.id), "removed", text));
+            }
+            lines.retain(|line| line.id != group_id && line.parent_id != Some(group_id));
+            let text = match l {
+                2 => format!("\"{}\" added: required by \"{}\"", target_name, requiring_name),
+                _ => format!("«{}» добавлена: ее требует «{}»", target_name, requiring_name),
+            };
+            notes.push(get_note(Some(target.id), "added", text));
+            lines.push(get_line(&target, true, l));
+            changed = true;
+            break;
+        }
+        if !changed {
+            is_settled = true;
+            break;
+        }
+    }
+    // связи так и не сошлись - выбор противоречив, заказ с ним не принимаем
+    if !is_settled {
+        let text = match l {
+            2 => "Option relations conflict with each other, please change the selection".to_string(),
+            _ => "Связи выбранных опций противоречат друг другу - измените выбор".to_string(),
+        };
+        notes.push(get_note(None, "conflict", text));
+    }
+
+    // обязательную опцию (is_default) связи могли убрать как несовместимую -
+    // без нее выбор неполный, заказ с ним не принимаем
+    for parent in serves.iter().filter(|s| s.serve_id.is_none() && s.is_default) {
+        let is_chosen = lines.iter().any(|line| line.id == parent.id || line.parent_id == Some(parent.id));
+        if !is_chosen {
+            let text = match l {
+                2 => format!("\"{}\" is required, but the selection excludes it, please change the selection", parent.name_en),
+                _ => format!("«{}» обязательна, но выбранные опции ее исключают - измените выбор", parent.name),
+            };
+            notes.push(get_note(Some(parent.id), "conflict", text));
+        }
+    }
+
+    let ids: Vec<i32> = lines.iter().map(|line| line.id).collect();
+    for relation in ServeRelation::get_for_serves(&ids).iter().filter(|r| r.types == 3 && ids.contains(&r.serve_id)) {
+        if ids.contains(&relation.target_id) || notes.iter().any(|n| n.serve_id == Some(relation.target_id)) {
+            continue;
+        }
+        if let Some(target) = get_serve(relation.target_id) {
+            let name = lines.iter().find(|line| line.id == relation.serve_id).map(|line| line.name.clone()).unwrap_or_default();
+            let text = match l {
+                2 => format!("We recommend \"{}\" with \"{}\"", target.name_en, name),
+                _ => format!("К «{}» рекомендуем «{}»", name, target.name),
+            };
+            notes.push(get_note(Some(target.id), "recommended", text));
+        }
+    }
+    notes
+}
+
+// группа - опция и ее варианты (serve_id = id опции), из группы берется одна.
+// если ничего не выбрано, а опция обязательна (is_default) - берем вариант
+// по умолчанию или саму опцию.
+pub fn get_calculation(tech_cat_ids: &Vec<i32>, serve_ids: &Vec<i32>, types: i16, promo: Option<&str>, l: u8) -> Calculation {
+    let _connection = establish_connection();
+    let _serves = schema::serve::table
+        .filter(schema::serve::tech_cat_id.eq_any(tech_cat_ids))
+        .order((schema::serve::category_id.asc(), schema::serve::position.asc()))
+        .load::<Serve>(&_connection)
+        .expect("E");
+
+    let mut lines = Vec::new();
+    let mut used: Vec<i32> = Vec::new();
+    for parent in _serves.iter().filter(|s| s.serve_id.is_none()) {
+        let group: Vec<&Serve> = _serves
+            .iter()
+            .filter(|s| s.id == parent.id || s.serve_id == Some(parent.id))
+            .collect();
+
+        let chosen = serve_ids
+            .iter()
+            .find_map(|id| group.iter().find(|s| &s.id == id))
+            .map(|s| (*s, false));
+        let chosen = match chosen {
+            Some(chosen) => Some(chosen),
+            None if parent.is_default => Some((
+                *group.iter()
+                    .find(|s| s.serve_id.is_some() && s.is_default)
+                    .unwrap_or(&parent),
+                true,
+            )),
+            None => None,
+        };
+        if let Some((_serve, is_added)) = chosen {
+            used.push(_serve.id);
+            lines.push(get_line(_serve, is_added, l));
+        }
+    }
+
+    let mut ignored = Vec::new();
+    for id in serve_ids.iter() {
+        if !used.contains(id) && !ignored.contains(id) {
+            ignored.push(*id);
+        }
+    }
+    let notes = apply_relations(&mut lines, &_serves, l);
+    let price: i32 = lines.iter().map(|line| line.price).sum();
+    let discount = Discount::get(price, types, promo);
+    let timeline = get_timeline(&lines);
+    Calculation {
+        man_hours: lines.iter().map(|line| line.man_hours as i32).sum(),
+        lines:     lines,
+        ignored:   ignored,
+        notes:     notes,
+        price:     price,
+        price_acc: discount.amount,
+        discount:  discount.title,
+        total:     price - discount.amount.unwrap_or(0),
+        timeline:  timeline,
+    }
+}
@@ -0,0 +1,258 @@
+// сортировка и фильтры списков на страницах категорий и тегов.
+// параметры: ?sort=price&order=asc&price_min=1000&price_max=50000&tags=rust,actix-web
+// для работ еще: &industry=Финансы&year=2022&tech=3 (кейсы, work_cases)
+use actix_web::{
+    HttpRequest,
+    web,
+};
+use actix_session::SessionExt;
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+    dsl::sql,
+    sql_types::Integer,
+};
+use serde::{Deserialize, Serialize};
+use crate::models::{
+    Blog,
+    Service,
+    Store,
+    Wiki,
+    Work,
+    Item,
+    User,
+};
+use crate::utils::{
+    establish_connection,
+    get_linguage_storage,
+    get_viewer,
+};
+
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListFilter {
+    pub sort:      String,      // created, price, view, seconds, words
+    pub order:     String,      // desc, asc
+    pub price_min: Option<i32>,
+    pub price_max: Option<i32>,
+    pub tags:      Vec<String>, // slug тегов, элемент должен иметь все
+    pub industry:  Option<String>,
+    pub year:      Option<i16>,
+    pub tech:      Option<i32>,    // serve_categories.id
+    pub l:         u8,             // язык страницы - для объема текста
+    #[serde(skip)]
+    pub user:      Option<User>,   // кто смотрит список - для видимости элементов
+}
+
+pub fn get_list_filter(req: &HttpRequest) -> ListFilter {
+    #[derive(Debug, Deserialize)]
+    struct Params {
+        pub sort:      Option<String>,
+        pub order:     Option<String>,
+        pub price_min: Option<i32>,
+        pub price_max: Option<i32>,
+        pub tags:      Option<String>,
+        pub industry:  Option<String>,
+        pub year:      Option<i16>,
+        pub tech:      Option<i32>,
+    }
+    let mut filter = ListFilter::new();
+    filter.user = get_viewer(&req.get_session());
+    let params_some = web::Query::<Params>::from_query(&req.query_string());
+    if let Ok(params) = params_some {
+        if let Some(sort) = params.sort.as_deref() {
+            if ["created", "price", "view", "seconds", "words"].contains(&sort) {
+                filter.sort = sort.to_string();
+            }
+        }
+        if params.order.as_deref() == Some("asc") {
+            filter.order = "asc".to_string();
+        }
+        filter.price_min = params.price_min;
+        filter.price_max = params.price_max;
+        if let Some(tags) = params.tags.as_deref() {
+            filter.tags = tags
+                .split(",")
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+        }
+        filter.industry = params.industry.clone().filter(|i| !i.trim().is_empty());
+        filter.year = params.year;
+        filter.tech = params.tech;
+    }
+    filter
+}
+
+// чтобы вернуть список в порядке отобранных id
+pub trait ListId {
+    fn list_id(&self) -> i32;
+}
+impl ListId for Blog {
+    fn list_id(&self) -> i32 { self.id }
+}
+impl ListId for Service {
+    fn list_id(&self) -> i32 { self.id }
+}
+impl ListId for Store {
+    fn list_id(&self) -> i32 { self.id }
+}
+impl ListId for Wiki {
+    fn list_id(&self) -> i32 { self.id }
+}
+impl ListId for Work {
+    fn list_id(&self) -> i32 { self.id }
+}
+
+impl ListFilter {
+    // без фильтров, новые первыми - для превью категорий
+    pub fn new() -> ListFilter {
+        ListFilter {
+            sort:      "created".to_string(),
+            order:     "desc".to_string(),
+            price_min: None,
+            price_max: None,
+            tags:      Vec::new(),
+            industry:  None,
+            year:      None,
+            tech:      None,
+            l:         get_linguage_storage(),
+            user:      None,
+        }
+    }
+    pub fn is_default(&self) -> bool {
+        self.sort == "created"
+            && self.order == "desc"
+            && self.price_min.is_none()
+            && self.price_max.is_none()
+            && self.tags.is_empty()
+            && !self.has_case_filter()
+    }
+    pub fn has_case_filter(&self) -> bool {
+        self.industry.is_some() || self.year.is_some() || self.tech.is_some()
+    }
+    pub fn has_tag(&self, slug: &str) -> bool {
+        self.tags.iter().any(|t| t == slug)
+    }
+
+    // строка для ссылок пагинации: "&sort=price&order=asc..."
+    pub fn get_query(&self) -> String {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        if self.sort != "created" {
+            params.push(("sort", self.sort.clone()));
+        }
+        if self.order != "desc" {
+            params.push(("order", self.order.clone()));
+        }
+        if let Some(min) = self.price_min {
+            params.push(("price_min", min.to_string()));
+        }
+        if let Some(max) = self.price_max {
+            params.push(("price_max", max.to_string()));
+        }
+        if !self.tags.is_empty() {
+            params.push(("tags", self.tags.join(",")));
+        }
+        if let Some(industry) = &self.industry {
+            params.push(("industry", industry.clone()));
+        }
+        if let Some(year) = self.year {
+            params.push(("year", year.to_string()));
+        }
+        if let Some(tech) = self.tech {
+            params.push(("tech", tech.to_string()));
+        }
+        match serde_urlencoded::to_string(&params) {
+            Ok(query) if !query.is_empty() => "&".to_string() + &query,
+            _ => String::new(),
+        }
+    }
+
+    // id текущей страницы из ids с учетом фильтров и сортировки
+    // и номер следующей страницы (0 - если ее нет)
+    pub fn get_page_ids(&self, ids: Vec<i32>, is_admin: bool, page: i32, limit: i32) -> (Vec<i32>, i32) {
+        let _connection = establish_connection();
+        let mut query = schema::items::table
+            .filter(schema::items::id.eq_any(ids))
+            .filter(schema::items::deleted_at.is_null())
+            .select(schema::items::id)
+            .into_boxed();
+        if !is_admin {
+            query = query.filter(Item::get_visible_filter(self.user.as_ref()));
+        }
+
+        // цена с учетом скидки
+        let price = || sql::<Integer>("(items.price - COALESCE(items.price_acc, 0))");
+        if let Some(min) = self.price_min {
+            query = query.filter(price().ge(min));
+        }
+        if let Some(max) = self.price_max {
+            query = query.filter(price().le(max));
+        }
+
+        // по slug - он одинаков на обоих языках и без запятых
+        for slug in self.tags.iter() {
+            let tag_ids = schema::tags::table
+                .filter(schema::tags::slug.eq(slug))
+                .filter(schema::tags::deleted_at.is_null())
+                .select(schema::tags::id)
+                .load::<i32>(&_connection)
+                .expect("E");
+            let item_ids = schema::tags_items::table
+                .filter(schema::tags_items::tag_id.eq_any(tag_ids))
+                .select(schema::tags_items::item_id)
+                .load::<i32>(&_connection)
+                .expect("E");
+            query = query.filter(schema::items::id.eq_any(item_ids));
+        }
+
+        if self.has_case_filter() {
+            let item_ids = crate::models::WorkCase::get_filtered_item_ids(
+                self.industry.as_deref(),
+                self.year,
+                self.tech,
+            );
+            query = query.filter(schema::items::id.eq_any(item_ids));
+        }
+
+        let is_asc = self.order == "asc";
+        query = match (self.sort.as_str(), is_asc) {
+            ("price", true)    => query.order(price().asc()),
+            ("price", false)   => query.order(price().desc()),
+            ("view", true)     => query.order(schema::items::view.asc()),
+            ("view", false)    => query.order(schema::items::view.desc()),
+            ("seconds", true)  => query.order(schema::items::seconds.asc()),
+            ("seconds", false) => query.order(schema::items::seconds.desc()),
+            // объем текста на языке страницы
+            ("words", true)  if self.l == 2 => query.order(schema::items::word_count_en.asc()),
+            ("words", false) if self.l == 2 => query.order(schema::items::word_count_en.desc()),
+            ("words", true)    => query.order(schema::items::word_count.asc()),
+            ("words", false)   => query.order(schema::items::word_count.desc()),
+            (_, true)          => query.order(schema::items::created.asc()),
+            (_, false)         => query.order(schema::items::created.desc()),
+        };
+        // при равных значениях - постоянный порядок между страницами
+        query = if is_asc {
+            query.then_order_by(schema::items::id.asc())
+        } else {
+            query.then_order_by(schema::items::id.desc())
+        };
+
+        let page = if page > 1 { page } else { 1 };
+        let mut page_ids = query
+            .offset(((page - 1) * limit).into())
+            .limit((limit + 1).into())
+            .load::<i32>(&_connection)
+            .expect("E");
+        let next_page_number = if page_ids.len() > limit as usize { page + 1 } else { 0 };
+        page_ids.truncate(limit as usize);
+        (page_ids, next_page_number)
+    }
+
+    pub fn order_list<T: ListId>(&self, mut list: Vec<T>, ids: &Vec<i32>) -> Vec<T> {
+        list.sort_by_key(|o| ids.iter().position(|i| *i == o.list_id()).unwrap_or(usize::MAX));
+        list
+    }
+}
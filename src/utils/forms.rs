@@ -47,13 +47,15 @@ pub struct FeedbackForm {
 }
 #[derive(Deserialize, Serialize, Debug)]
 pub struct CategoriesForm {
-    pub name:        String,
-    pub description: String,
-    pub position:    i16,
-    pub image:       String,
-    pub level:       i16,
-    pub types:       i16,
-    pub slug:        String,
+    pub name:           String,
+    pub name_en:        String,
+    pub description:    String,
+    pub description_en: String,
+    pub position:       i16,
+    pub image:          String,
+    pub level:          i16,
+    pub types:          i16,
+    pub slug:           String,
 }
 
 
@@ -65,13 +67,15 @@ pub struct ContentForm {
 
 pub async fn category_form(payload: &mut Multipart, owner_id: i32) -> CategoriesForm {
     let mut form: CategoriesForm = CategoriesForm {
-        name:        "".to_string(),
-        description: "".to_string(),
-        position:    0,
-        image:       "".to_string(),
-        level:       0,
-        types:       0,
-        slug:        "".to_string(),
+        name:           "".to_string(),
+        name_en:        "".to_string(),
+        description:    "".to_string(),
+        description_en: "".to_string(),
+        position:       0,
+        image:          "".to_string(),
+        level:          0,
+        types:          0,
+        slug:           "".to_string(),
     };
 
     while let Some(item) = payload.next().await {
@@ -131,8 +135,12 @@ pub async fn category_form(payload: &mut Multipart, owner_id: i32) -> Categories
                     let data_string = s.to_string();
                     if field.name() == "name" {
                         form.name = data_string
+                    } else if field.name() == "name_en" {
+                        form.name_en = data_string
                     } else if field.name() == "description" {
                         form.description = data_string
+                    } else if field.name() == "description_en" {
+                        form.description_en = data_string
                     } else if field.name() == "slug" {
                         form.slug = data_string
                     }
@@ -172,11 +180,14 @@ pub async fn content_form(payload: &mut Multipart) -> ContentForm {
 #[derive(Deserialize, Serialize, Debug)]
 pub struct ItemForms {
     pub title:          String,
+    pub title_en:       String,
     pub description:    Option<String>,
+    pub description_en: Option<String>,
     pub link:           Option<String>,
     pub main_image:     Option<String>,
     pub category_list:  Vec<i32>,
     pub tags_list:      Vec<i32>,
+    pub new_tags:       Vec<String>,
     pub serve_list:     Vec<i32>,
     pub close_tech_cats_list: Vec<i32>,
     pub position:       i16,
@@ -188,11 +199,14 @@ pub struct ItemForms {
 pub async fn item_form(payload: &mut Multipart, owner_id: i32) -> ItemForms {
     let mut form: ItemForms = ItemForms {
         title:          "".to_string(),
+        title_en:       "".to_string(),
         description:    None,
+        description_en: None,
         link:           None,
         main_image:     None,
         category_list:  Vec::new(),
         tags_list:      Vec::new(),
+        new_tags:       Vec::new(),
         serve_list:     Vec::new(),
         close_tech_cats_list: Vec::new(),
         position:       0,
@@ -203,7 +217,7 @@ pub async fn item_form(payload: &mut Multipart, owner_id: i32) -> ItemForms {
     while let Some(item) = payload.next().await {
         let mut field: Field = item.expect("split_payload err");
         let name = field.name();
-        let string_list = ["title", "description", "link", "slug"];
+        let string_list = ["title", "title_en", "description", "description_en", "link", "slug"];
 
         if string_list.contains(&name) {
             let mut _content = "".to_string();
@@ -213,8 +227,12 @@ pub async fn item_form(payload: &mut Multipart, owner_id: i32) -> ItemForms {
                     let data_string = s.to_string();
                     if field.name() == "title" {
                         form.title = data_string;
+                    } else if field.name() == "title_en" {
+                        form.title_en = data_string;
                     } else if field.name() == "description" {
                         form.description = Some(data_string);
+                    } else if field.name() == "description_en" {
+                        form.description_en = Some(data_string);
                     } else if field.name() == "link" {
                         form.link = Some(data_string);
                     }
@@ -284,6 +302,19 @@ pub async fn item_form(payload: &mut Multipart, owner_id: i32) -> ItemForms {
                 }
             }
         }
+        // теги, которых еще нет - создадим при сохранении
+        else if name == "new_tags[]" {
+            let mut _content = "".to_string();
+            while let Some(chunk) = field.next().await {
+                let data = chunk.expect("split_payload err chunk");
+                if let Ok(s) = str::from_utf8(&data) {
+                    _content.push_str(s);
+                }
+            }
+            if !_content.trim().is_empty() {
+                form.new_tags.push(_content.trim().to_string());
+            }
+        }
 
         else if name == "main_image" {
             let _new_path = field.content_disposition().get_filename().unwrap();
@@ -347,6 +378,8 @@ pub struct OrderForms {
     pub email:          String,
     pub files:          Vec<String>,
     pub serve_list:     Vec<i32>,
+    pub variant_id:     Option<i32>,
+    pub promo:          Option<String>,
 }
 
 // форма для заказов
@@ -364,19 +397,25 @@ pub async fn order_form(payload: &mut Multipart, owner_id: i32) -> OrderForms {
         email:          "".to_string(),
         files:          Vec::new(),
         serve_list:     Vec::new(),
+        variant_id:     None,
+        promo:          None,
     };
 
     while let Some(item) = payload.next().await {
         let mut field: Field = item.expect("split_payload err");
         let name = field.name();
-        let string_list = ["title", "title_en", "description", "description_en", "email", "username"];
+        let string_list = ["title", "title_en", "description", "description_en", "email", "username", "promo"];
 
         if string_list.contains(&name) {
             while let Some(chunk) = field.next().await {
                 let data = chunk.expect("split_payload err chunk");
                 if let Ok(s) = str::from_utf8(&data) {
                     let data_string = s.to_string();
-                    if field.name() == "title" {
+                    if field.name() == "promo" {
+                        if !data_string.trim().is_empty() {
+                            form.promo = Some(data_string);
+                        }
+                    } else if field.name() == "title" {
                         form.title = data_string;
                     } else if field.name() == "description" {
                         form.description = Some(data_string);
@@ -406,6 +445,16 @@ pub async fn order_form(payload: &mut Multipart, owner_id: i32) -> OrderForms {
                 }
             }
         }
+        else if name == "variant_id" {
+            while let Some(chunk) = field.next().await {
+                let data = chunk.expect("split_payload err chunk");
+                if let Ok(s) = str::from_utf8(&data) {
+                    if let Ok(_int) = s.parse::<i32>() {
+                        form.variant_id = Some(_int);
+                    }
+                }
+            }
+        }
         else if name == "serve_list" {
             while let Some(chunk) = field.next().await {
                 let data = chunk.expect("split_payload err chunk");
@@ -508,6 +557,7 @@ pub struct ServeCategoriesForm {
     pub category_id:   i32,
     pub position:      i16,
     pub default_price: i32,
+    pub is_parallel:   bool,
 }
 pub async fn serve_category_form(payload: &mut Multipart, _owner_id: i32) -> ServeCategoriesForm {
     let mut form: ServeCategoriesForm = ServeCategoriesForm {
@@ -516,6 +566,7 @@ pub async fn serve_category_form(payload: &mut Multipart, _owner_id: i32) -> Ser
         category_id:   0,
         position:      0,
         default_price: 0,
+        is_parallel:   false,
     };
 
     while let Some(item) = payload.next().await {
@@ -559,10 +610,696 @@ pub async fn serve_category_form(payload: &mut Multipart, _owner_id: i32) -> Ser
                         form.name = data_string
                     } else if field.name() == "description" {
                         form.description = data_string
+                    } else if field.name() == "is_parallel" {
+                        form.is_parallel = true;
+                    }
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ImportForm {
+    pub format:  String,
+    pub dry_run: bool,
+    pub data:    String,
+}
+
+// форма загрузки каталога: файл json или csv, формат и флаг проверки
+pub async fn import_form(payload: &mut Multipart) -> ImportForm {
+    let mut form: ImportForm = ImportForm {
+        format:  "".to_string(),
+        dry_run: false,
+        data:    "".to_string(),
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+
+        if name == "file" {
+            if let Some(filename) = field.content_disposition().get_filename() {
+                if form.format.is_empty() && filename.ends_with(".csv") {
+                    form.format = "csv".to_string();
+                }
+            }
+            // файл собираем целиком, чтобы не порвать utf-8 на границе чанков
+            let mut bytes: Vec<u8> = Vec::new();
+            while let Some(chunk) = field.next().await {
+                let data = chunk.expect("split_payload err chunk");
+                bytes.extend_from_slice(&data);
+            }
+            form.data = String::from_utf8_lossy(&bytes).to_string();
+        }
+        else {
+            while let Some(chunk) = field.next().await {
+                let data = chunk.expect("split_payload err chunk");
+                if let Ok(s) = str::from_utf8(&data) {
+                    let data_string = s.to_string();
+                    if name == "format" {
+                        form.format = data_string
+                    } else if name == "dry_run" {
+                        form.dry_run = data_string == "1" || data_string == "true" || data_string == "on";
                     }
                 }
             }
         }
     }
+    if form.format.is_empty() {
+        form.format = "json".to_string();
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct VariantForm {
+    pub title:    String,
+    pub title_en: String,
+    pub sku:      String,
+    pub price:    i32,
+    pub stock:    i32,
+    pub position: i16,
+}
+
+// форма варианта товара
+pub async fn variant_form(payload: &mut Multipart) -> VariantForm {
+    let mut form: VariantForm = VariantForm {
+        title:    "".to_string(),
+        title_en: "".to_string(),
+        sku:      "".to_string(),
+        price:    0,
+        stock:    0,
+        position: 0,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.trim().to_string();
+                if name == "title" {
+                    form.title = data_string;
+                } else if name == "title_en" {
+                    form.title_en = data_string;
+                } else if name == "sku" {
+                    form.sku = data_string;
+                } else if name == "price" {
+                    form.price = data_string.parse().unwrap_or(0);
+                } else if name == "stock" {
+                    form.stock = data_string.parse().unwrap_or(0);
+                } else if name == "position" {
+                    form.position = data_string.parse().unwrap_or(0);
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CartForm {
+    pub item_id:    i32,
+    pub variant_id: Option<i32>,
+    pub count:      i16,
+}
+
+// добавление в корзину и изменение количества
+pub async fn cart_form(payload: &mut Multipart) -> CartForm {
+    let mut form: CartForm = CartForm {
+        item_id:    0,
+        variant_id: None,
+        count:      1,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.trim().to_string();
+                if name == "item_id" {
+                    form.item_id = data_string.parse().unwrap_or(0);
+                } else if name == "variant_id" {
+                    form.variant_id = data_string.parse().ok();
+                } else if name == "count" {
+                    form.count = data_string.parse().unwrap_or(1);
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CheckoutForm {
+    pub username:    String,
+    pub email:       String,
+    pub description: Option<String>,
+    pub promo:       Option<String>,
+}
+
+// оформление заказа из корзины
+pub async fn checkout_form(payload: &mut Multipart) -> CheckoutForm {
+    let mut form: CheckoutForm = CheckoutForm {
+        username:    "".to_string(),
+        email:       "".to_string(),
+        description: None,
+        promo:       None,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.to_string();
+                if name == "username" {
+                    form.username += &data_string;
+                } else if name == "email" {
+                    form.email += &data_string;
+                } else if name == "description" && !data_string.trim().is_empty() {
+                    form.description = Some(form.description.unwrap_or("".to_string()) + &data_string);
+                } else if name == "promo" && !data_string.trim().is_empty() {
+                    form.promo = Some(form.promo.unwrap_or("".to_string()) + &data_string);
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WorkCaseForm {
+    pub client:      String,
+    pub client_en:   String,
+    pub industry:    String,
+    pub industry_en: String,
+    pub year:        Option<i16>,
+    pub duration:    String,
+    pub duration_en: String,
+    pub team_size:   Option<i16>,
+    pub results:     String,
+    pub results_en:  String,
+    pub techs:       Vec<i32>, // serve_categories
+}
+
+// форма кейса работы портфолио
+pub async fn work_case_form(payload: &mut Multipart) -> WorkCaseForm {
+    let mut form: WorkCaseForm = WorkCaseForm {
+        client:      "".to_string(),
+        client_en:   "".to_string(),
+        industry:    "".to_string(),
+        industry_en: "".to_string(),
+        year:        None,
+        duration:    "".to_string(),
+        duration_en: "".to_string(),
+        team_size:   None,
+        results:     "".to_string(),
+        results_en:  "".to_string(),
+        techs:       Vec::new(),
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.to_string();
+                if name == "client" {
+                    form.client += &data_string;
+                } else if name == "client_en" {
+                    form.client_en += &data_string;
+                } else if name == "industry" {
+                    form.industry += &data_string;
+                } else if name == "industry_en" {
+                    form.industry_en += &data_string;
+                } else if name == "year" {
+                    form.year = data_string.trim().parse().ok();
+                } else if name == "duration" {
+                    form.duration += &data_string;
+                } else if name == "duration_en" {
+                    form.duration_en += &data_string;
+                } else if name == "team_size" {
+                    form.team_size = data_string.trim().parse().ok();
+                } else if name == "results" {
+                    form.results += &data_string;
+                } else if name == "results_en" {
+                    form.results_en += &data_string;
+                } else if name == "techs[]" {
+                    if let Ok(id) = data_string.trim().parse() {
+                        form.techs.push(id);
+                    }
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WikiParentForm {
+    pub parent: String, // slug родительской статьи
+}
+
+pub async fn wiki_parent_form(payload: &mut Multipart) -> WikiParentForm {
+    let mut form: WikiParentForm = WikiParentForm {
+        parent: "".to_string(),
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                if name == "parent" {
+                    form.parent += s.trim();
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct HelpVoteForm {
+    pub is_helpful: bool,
+    pub comment:    Option<String>,
+}
+
+// оценка статьи помощи: is_helpful - "1" или "0"
+pub async fn help_vote_form(payload: &mut Multipart) -> HelpVoteForm {
+    let mut form: HelpVoteForm = HelpVoteForm {
+        is_helpful: false,
+        comment:    None,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.to_string();
+                if name == "is_helpful" {
+                    form.is_helpful = data_string.trim() == "1";
+                } else if name == "comment" && !data_string.trim().is_empty() {
+                    form.comment = Some(form.comment.unwrap_or("".to_string()) + &data_string);
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ItemAccessForm {
+    pub visibility: i16,    // 1 всем, 2 авторизованным, 3 выбранным
+    pub users:      String, // username'ы через запятую
+    pub groups:     String, // названия групп через запятую
+    pub share:      i16,    // 0 оставить ссылку, 1 новая ссылка, 2 убрать
+}
+
+pub async fn item_access_form(payload: &mut Multipart) -> ItemAccessForm {
+    let mut form: ItemAccessForm = ItemAccessForm {
+        visibility: 1,
+        users:      "".to_string(),
+        groups:     "".to_string(),
+        share:      0,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.to_string();
+                if name == "visibility" {
+                    let _int: i16 = data_string.trim().parse().unwrap_or(1);
+                    form.visibility = _int;
+                } else if name == "users" {
+                    form.users += &data_string;
+                } else if name == "groups" {
+                    form.groups += &data_string;
+                } else if name == "share" {
+                    let _int: i16 = data_string.trim().parse().unwrap_or(0);
+                    form.share = _int;
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct UserGroupForm {
+    pub name:  String,
+    pub users: String, // username'ы через запятую
+}
+
+pub async fn user_group_form(payload: &mut Multipart) -> UserGroupForm {
+    let mut form: UserGroupForm = UserGroupForm {
+        name:  "".to_string(),
+        users: "".to_string(),
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.to_string();
+                if name == "name" {
+                    form.name += &data_string;
+                } else if name == "users" {
+                    form.users += &data_string;
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TagAliasesForm {
+    pub aliases: String, // названия через запятую
+}
+
+pub async fn tag_aliases_form(payload: &mut Multipart) -> TagAliasesForm {
+    let mut form: TagAliasesForm = TagAliasesForm {
+        aliases: "".to_string(),
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                if name == "aliases" {
+                    form.aliases += s;
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TagMergeForm {
+    pub target: String, // slug тега, в который сливаем
+}
+
+pub async fn tag_merge_form(payload: &mut Multipart) -> TagMergeForm {
+    let mut form: TagMergeForm = TagMergeForm {
+        target: "".to_string(),
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                if name == "target" {
+                    form.target += s.trim();
+                }
+            }
+        }
+    }
+    form
+}
+
+// дата из <input type="date">: 2024-05-31 -> начало дня
+fn get_form_date(s: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+        .ok()
+        .map(|d| d.and_hms(0, 0, 0))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DiscountRuleForm {
+    pub title:     String,
+    pub threshold: i32,
+    pub percent:   i16,
+    pub types:     i16,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at:   Option<chrono::NaiveDateTime>,
+    pub is_active: bool,
+}
+
+// правило скидки
+pub async fn discount_rule_form(payload: &mut Multipart) -> DiscountRuleForm {
+    let mut form: DiscountRuleForm = DiscountRuleForm {
+        title:     "".to_string(),
+        threshold: 0,
+        percent:   0,
+        types:     0,
+        starts_at: None,
+        ends_at:   None,
+        is_active: false,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.trim().to_string();
+                if name == "title" {
+                    form.title = data_string;
+                } else if name == "threshold" {
+                    form.threshold = data_string.parse().unwrap_or(0);
+                } else if name == "percent" {
+                    form.percent = data_string.parse().unwrap_or(0);
+                } else if name == "types" {
+                    form.types = data_string.parse().unwrap_or(0);
+                } else if name == "starts_at" {
+                    form.starts_at = get_form_date(&data_string);
+                } else if name == "ends_at" {
+                    form.ends_at = get_form_date(&data_string);
+                } else if name == "is_active" {
+                    form.is_active = true;
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PromoCodeForm {
+    pub code:      String,
+    pub percent:   i16,
+    pub types:     i16,
+    pub min_price: i32,
+    pub max_uses:  i32,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at:   Option<chrono::NaiveDateTime>,
+    pub is_active: bool,
+}
+
+// промокод
+pub async fn promo_code_form(payload: &mut Multipart) -> PromoCodeForm {
+    let mut form: PromoCodeForm = PromoCodeForm {
+        code:      "".to_string(),
+        percent:   0,
+        types:     0,
+        min_price: 0,
+        max_uses:  0,
+        starts_at: None,
+        ends_at:   None,
+        is_active: false,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.trim().to_string();
+                if name == "code" {
+                    form.code = data_string;
+                } else if name == "percent" {
+                    form.percent = data_string.parse().unwrap_or(0);
+                } else if name == "types" {
+                    form.types = data_string.parse().unwrap_or(0);
+                } else if name == "min_price" {
+                    form.min_price = data_string.parse().unwrap_or(0);
+                } else if name == "max_uses" {
+                    form.max_uses = data_string.parse().unwrap_or(0);
+                } else if name == "starts_at" {
+                    form.starts_at = get_form_date(&data_string);
+                } else if name == "ends_at" {
+                    form.ends_at = get_form_date(&data_string);
+                } else if name == "is_active" {
+                    form.is_active = true;
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CurrencyForm {
+    pub code:      String,
+    pub name:      String,
+    pub name_en:   String,
+    pub symbol:    String,
+    pub rate:      f64,
+    pub position:  i16,
+    pub is_active: bool,
+}
+
+// валюта и ее курс. Курс можно вводить и через запятую
+pub async fn currency_form(payload: &mut Multipart) -> CurrencyForm {
+    let mut form: CurrencyForm = CurrencyForm {
+        code:      "".to_string(),
+        name:      "".to_string(),
+        name_en:   "".to_string(),
+        symbol:    "".to_string(),
+        rate:      0.0,
+        position:  0,
+        is_active: false,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.trim().to_string();
+                if name == "code" {
+                    form.code = data_string.to_uppercase();
+                } else if name == "name" {
+                    form.name = data_string;
+                } else if name == "name_en" {
+                    form.name_en = data_string;
+                } else if name == "symbol" {
+                    form.symbol = data_string;
+                } else if name == "rate" {
+                    form.rate = data_string.replace(",", ".").parse().unwrap_or(0.0);
+                } else if name == "position" {
+                    form.position = data_string.parse().unwrap_or(0);
+                } else if name == "is_active" {
+                    form.is_active = true;
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TimelineSettingsForm {
+    pub workers:       i16,
+    pub hours_per_day: i16,
+    pub start_delay:   i16,
+    pub work_weekends: bool,
+}
+
+// настройки оценки сроков
+pub async fn timeline_settings_form(payload: &mut Multipart) -> TimelineSettingsForm {
+    let mut form: TimelineSettingsForm = TimelineSettingsForm {
+        workers:       0,
+        hours_per_day: 0,
+        start_delay:   0,
+        work_weekends: false,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.trim().to_string();
+                if name == "workers" {
+                    form.workers = data_string.parse().unwrap_or(0);
+                } else if name == "hours_per_day" {
+                    form.hours_per_day = data_string.parse().unwrap_or(0);
+                } else if name == "start_delay" {
+                    form.start_delay = data_string.parse().unwrap_or(0);
+                } else if name == "work_weekends" {
+                    form.work_weekends = true;
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct HolidayForm {
+    pub day:   Option<chrono::NaiveDate>,
+    pub title: String,
+}
+
+// нерабочий день
+pub async fn holiday_form(payload: &mut Multipart) -> HolidayForm {
+    let mut form: HolidayForm = HolidayForm {
+        day:   None,
+        title: "".to_string(),
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.trim().to_string();
+                if name == "day" {
+                    form.day = get_form_date(&data_string).map(|d| d.date());
+                } else if name == "title" {
+                    form.title = data_string;
+                }
+            }
+        }
+    }
+    form
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ServeRelationForm {
+    pub target_id: i32,
+    pub types:     i16,
+}
+
+// связь опции с другой опцией
+pub async fn serve_relation_form(payload: &mut Multipart) -> ServeRelationForm {
+    let mut form: ServeRelationForm = ServeRelationForm {
+        target_id: 0,
+        types:     0,
+    };
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item.expect("split_payload err");
+        let name = field.name().to_string();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.expect("split_payload err chunk");
+            if let Ok(s) = str::from_utf8(&data) {
+                let data_string = s.trim().to_string();
+                if name == "target_id" {
+                    form.target_id = data_string.parse().unwrap_or(0);
+                } else if name == "types" {
+                    form.types = data_string.parse().unwrap_or(0);
+                }
+            }
+        }
+    }
     form
 }
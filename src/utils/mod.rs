@@ -1,11 +1,35 @@
 mod forms;
 mod auth;
 mod stat;
+mod transfer;
+mod trash;
+mod translation;
+mod filter;
+mod wiki;
+mod reading;
+mod api;
+mod slug;
+mod calculator;
+mod timeline;
+mod proposal;
+mod repricing;
 
 pub use self::{
     forms::*,
     auth::*,
     stat::*,
+    transfer::*,
+    trash::*,
+    translation::*,
+    filter::*,
+    wiki::*,
+    reading::*,
+    api::*,
+    slug::*,
+    calculator::*,
+    timeline::*,
+    proposal::*,
+    repricing::*,
 };
 use actix_web::{
     HttpRequest,
@@ -55,22 +79,10 @@ pub struct NewUserForm {
     pub password: String,
 }
 
-pub fn get_price_acc_values(price: &i32) -> Option<i32> {
-    if price > &3_000_000 {
-        let acc = (price * 10) / 100; // 10% скидка
-        return Some(acc);
-    }
-    else if price > &2_000_000 && price < &3_000_000 {
-        let acc = (price * 7) / 100; // 10% скидка
-        return Some(acc);
-    }
-    else if price > &1_000_000 && price < &2_000_000 {
-        let acc = (price * 5) / 100; // 5% скидка
-        return Some(acc);
-    }
-    else {
-        return None;
-    }
+// скидка в рублях по действующим правилам (админка /discounts/),
+// types - тип элементов, правила с types 0 действуют для всех
+pub fn get_price_acc_values(price: &i32, types: i16) -> Option<i32> {
+    crate::models::Discount::get(*price, types, None).amount
 }
 
 pub fn establish_connection() -> PgConnection {
@@ -143,6 +155,55 @@ pub fn set_linguage(types: u8) -> () {
     web_local_storage_api::set_item("linguage", types.to_string().as_str());
 }
 
+// валюта посетителя хранится в куки "currency" (ставит /set_currency/).
+// По умолчанию англоязычным - доллары, остальным - рубли
+pub fn get_default_currency(l: u8) -> String {
+    if l == 2 {
+        return "USD".to_string();
+    }
+    return "RUB".to_string();
+}
+pub fn get_currency_code(req: &HttpRequest, l: u8) -> String {
+    match req.cookie("currency") {
+        Some(cookie) => cookie.value().to_string(),
+        None => get_default_currency(l),
+    }
+}
+
+// валюты для цен страницы: загружаются один раз на запрос
+// и передаются в шаблон полем prices
+pub struct PriceFormat {
+    pub l:        u8,
+    pub base:     Option<crate::models::Currency>,
+    pub currency: Option<crate::models::Currency>,
+}
+impl PriceFormat {
+    pub fn get(req: &HttpRequest, l: u8) -> PriceFormat {
+        let code = get_currency_code(req, l);
+        let mut currencies = crate::models::Currency::get_active();
+        let base = currencies.iter().position(|c| c.is_base).map(|i| currencies.remove(i));
+        let currency = currencies.into_iter().find(|c| c.code == code);
+        PriceFormat {
+            l:        l,
+            base:     base,
+            currency: currency,
+        }
+    }
+    // цена в рублях с разрядами, а если выбрана другая валюта -
+    // еще и ориентировочно в ней: "1 234 567 ₽ (≈ $13,717)"
+    pub fn display(&self, price: i32) -> String {
+        let base = match self.base.as_ref() {
+            Some(base) => base,
+            None => return price.to_string() + " ₽",
+        };
+        let mut display = base.format(price, self.l);
+        if let Some(currency) = self.currency.as_ref() {
+            display = display + " (≈ " + &currency.format(price, self.l) + ")";
+        }
+        display
+    }
+}
+
 
 fn get_content_type<'a>(req: &'a HttpRequest) -> Option<&'a str> {
     return req.headers().get("user-agent")?.to_str().ok();
@@ -221,6 +282,26 @@ pub fn get_page(req: &HttpRequest) -> i32 {
     page
 }
 
+// секретный ключ закрытой страницы: ?key=...
+pub fn get_share_key(req: &HttpRequest) -> Option<String> {
+    #[derive(Debug, Deserialize)]
+    struct Params {
+        pub key: Option<String>,
+    }
+    match web::Query::<Params>::from_query(&req.query_string()) {
+        Ok(params) => params.key.clone(),
+        Err(_) => None,
+    }
+}
+
+
+// кто смотрит страницу (None - гость) - для проверок видимости элементов
+pub fn get_viewer(session: &Session) -> Option<User> {
+    if is_signed_in(session) {
+        return Some(get_request_user_data(session));
+    }
+    None
+}
 
 pub fn get_request_user_data(session: &Session) -> User {
     use crate::models::SessionUser;
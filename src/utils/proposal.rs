@@ -0,0 +1,183 @@
+// коммерческое предложение по заказу: опции заказа, сгруппированные
+// по тех. категориям и категориям опций, трудоемкость, скидка и сроки.
+// HTML рендерит шаблон block/proposal.stpl, PDF делает wkhtmltopdf
+// из того же HTML (путь к программе - WKHTMLTOPDF в .env).
+use serde::Serialize;
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+};
+use crate::models::{
+    Order,
+    OrderItem,
+    ServeCategories,
+    TechCategories,
+};
+use crate::utils::{
+    establish_connection,
+    get_work_days_ru,
+};
+
+
+// реквизиты компании для шапки; по умолчанию - вебсервисы.рф
+#[derive(Serialize, Debug)]
+pub struct ProposalCompany {
+    pub name:  String,
+    pub site:  String,
+    pub email: String,
+    pub phone: String,
+}
+impl ProposalCompany {
+    pub fn get() -> ProposalCompany {
+        dotenv::dotenv().ok();
+        let get_var = |name: &str, default: &str| -> String {
+            std::env::var(name).unwrap_or(default.to_string())
+        };
+        ProposalCompany {
+            name:  get_var("COMPANY_NAME", "вебсервисы.рф"),
+            site:  get_var("COMPANY_SITE", "https://вебсервисы.рф"),
+            email: get_var("COMPANY_EMAIL", ""),
+            phone: get_var("COMPANY_PHONE", ""),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProposalServe {
+    pub name:      String,
+    pub price:     i32,
+    pub man_hours: i32,
+}
+
+// категория опций (например, Rust)
+#[derive(Serialize, Debug)]
+pub struct ProposalCategory {
+    pub name:      String,
+    pub serves:    Vec<ProposalServe>,
+    pub price:     i32,
+    pub man_hours: i32,
+}
+
+// тех. категория (например, создание среднего магазина)
+#[derive(Serialize, Debug)]
+pub struct ProposalTechCategory {
+    pub name:        String,
+    pub description: Option<String>,
+    pub categories:  Vec<ProposalCategory>,
+    pub price:       i32,
+    pub man_hours:   i32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Proposal {
+    pub number:          String,
+    pub date:            chrono::NaiveDate,
+    pub company:         ProposalCompany,
+    pub order:           Order,
+    pub tech_categories: Vec<ProposalTechCategory>,
+    pub items:           Vec<OrderItem>,   // заказ из корзины
+    pub man_hours:       i32,
+    pub price:           i32,
+    pub price_acc:       Option<i32>,
+    pub discount:        Option<String>,
+    pub total:           i32,
+    pub work_days:       Option<String>,
+}
+
+impl Proposal {
+    // в заголовке Content-Disposition - только латиница
+    pub fn get_file_name(&self) -> String {
+        format!("proposal-{}-{}.pdf", self.order.id, self.date.format("%Y%m%d"))
+    }
+}
+
+pub fn get_proposal(order: Order, l: u8) -> Proposal {
+    let _connection = establish_connection();
+    let _serves = order.get_serves();
+    let category_ids: Vec<i32> = _serves.iter().map(|s| s.category_id).collect();
+    let tech_cat_ids: Vec<i32> = _serves.iter().map(|s| s.tech_cat_id).collect();
+    let _categories = schema::serve_categories::table
+        .filter(schema::serve_categories::id.eq_any(&category_ids))
+        .order(schema::serve_categories::position.asc())
+        .load::<ServeCategories>(&_connection)
+        .expect("E");
+    let _tech_categories = schema::tech_categories::table
+        .filter(schema::tech_categories::id.eq_any(&tech_cat_ids))
+        .order(schema::tech_categories::position.asc())
+        .load::<TechCategories>(&_connection)
+        .expect("E");
+
+    let mut tech_categories = Vec::new();
+    for tech in _tech_categories.iter() {
+        let mut categories = Vec::new();
+        for cat in _categories.iter().filter(|c| c.category_id == tech.id) {
+            let serves: Vec<ProposalServe> = _serves
+                .iter()
+                .filter(|s| s.category_id == cat.id && s.tech_cat_id == tech.id)
+                .map(|s| ProposalServe {
+                    name:      s.get_full_name(l),
+                    price:     s.price,
+                    man_hours: s.man_hours as i32,
+                })
+                .collect();
+            if serves.is_empty() {
+                continue;
+            }
+            categories.push(ProposalCategory {
+                name:      if l == 2 { cat.name_en.clone() } else { cat.name.clone() },
+                price:     serves.iter().map(|s| s.price).sum(),
+                man_hours: serves.iter().map(|s| s.man_hours).sum(),
+                serves:    serves,
+            });
+        }
+        tech_categories.push(ProposalTechCategory {
+            name:        if l == 2 { tech.name_en.clone() } else { tech.name.clone() },
+            description: if l == 2 { tech.description_en.clone() } else { tech.description.clone() },
+            price:       categories.iter().map(|c| c.price).sum(),
+            man_hours:   categories.iter().map(|c| c.man_hours).sum(),
+            categories:  categories,
+        });
+    }
+
+    let today = (chrono::Local::now().naive_utc() + chrono::Duration::hours(3)).date();
+    let man_hours = order.man_hours.unwrap_or(tech_categories.iter().map(|t| t.man_hours).sum());
+    Proposal {
+        number:          format!("КП-{}-{}", order.id, today.format("%Y%m%d")),
+        date:            today,
+        company:         ProposalCompany::get(),
+        tech_categories: tech_categories,
+        items:           order.get_items(),
+        man_hours:       man_hours,
+        price:           order.price,
+        price_acc:       order.price_acc,
+        discount:        order.get_discount_title(),
+        total:           order.price - order.price_acc.unwrap_or(0),
+        work_days:       order.work_days.map(get_work_days_ru),
+        order:           order,
+    }
+}
+
+// PDF из готового HTML. None - если wkhtmltopdf не установлен или упал
+pub fn get_proposal_pdf(html: &str) -> Option<Vec<u8>> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    dotenv::dotenv().ok();
+    let program = std::env::var("WKHTMLTOPDF").unwrap_or("wkhtmltopdf".to_string());
+    let mut child = Command::new(program)
+        .args(&["--quiet", "--encoding", "utf-8", "-", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // stdin закрываем до чтения результата, иначе wkhtmltopdf ждет конца HTML
+    child.stdin.take()?.write_all(html.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if output.status.success() && !output.stdout.is_empty() {
+        return Some(output.stdout);
+    }
+    None
+}
@@ -0,0 +1,39 @@
+// объем текста элемента: число слов и примерное время чтения.
+// считается при сохранении содержимого, хранится в items.
+use regex::Regex;
+
+
+// слов в минуту: русский текст читается медленнее английского
+const WORDS_PER_MINUTE_RU: i32 = 180;
+const WORDS_PER_MINUTE_EN: i32 = 230;
+
+// число слов в html без тегов и сущностей
+pub fn get_word_count(content: &Option<String>) -> i32 {
+    let content = match content {
+        Some(content) => content,
+        None => return 0,
+    };
+    let tags = Regex::new(r"(?s)<(script|style)[^>]*>.*?</(script|style)>|<[^>]*>").unwrap();
+    let entities = Regex::new(r"&[#A-Za-z0-9]+;").unwrap();
+    let text = tags.replace_all(content, " ");
+    let text = entities.replace_all(&text, " ");
+    text.split_whitespace()
+        .filter(|w| w.chars().any(|c| c.is_alphanumeric()))
+        .count() as i32
+}
+
+// время чтения в минутах, не меньше минуты для непустого текста
+pub fn get_reading_time(words: i32, l: u8) -> i16 {
+    if words == 0 {
+        return 0;
+    }
+    let per_minute = if l == 2 { WORDS_PER_MINUTE_EN } else { WORDS_PER_MINUTE_RU };
+    ((words + per_minute - 1) / per_minute) as i16
+}
+
+// (слов, минут, слов en, минут en)
+pub fn get_reading_stats(content: &Option<String>, content_en: &Option<String>) -> (i32, i16, i32, i16) {
+    let words = get_word_count(content);
+    let words_en = get_word_count(content_en);
+    (words, get_reading_time(words, 1), words_en, get_reading_time(words_en, 2))
+}
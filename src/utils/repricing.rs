@@ -0,0 +1,262 @@
+// пересчет цен элементов по опциям. Цена элемента - сумма цен
+// привязанных опций, price_acc - скидка по правилам, как в create_item.
+// Вызывается при правке и удалении опции, а целиком - из консоли:
+// reprice_items [id опций...] [--dry-run]
+// refresh_price_acc пересчитывает только скидки - после правки правил.
+// cleanup_order_links [--dry-run] убирает связи, которые create_order
+// раньше писал на заказанный элемент (с типом заказа) вместо заказа.
+use crate::schema;
+use crate::diesel::{
+    Connection,
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+};
+use serde::Serialize;
+use crate::models::{
+    Item,
+    ItemVariant,
+    ServeItems,
+    TechCategoriesItem,
+};
+use crate::utils::{
+    establish_connection,
+    get_price_acc_values,
+};
+
+
+#[derive(Serialize, Debug)]
+pub struct RepriceChange {
+    pub item_id:       i32,
+    pub types:         i16,
+    pub title:         String,
+    pub old_price:     i32,
+    pub new_price:     i32,
+    pub old_price_acc: Option<i32>,
+    pub new_price_acc: Option<i32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RepriceReport {
+    pub dry_run: bool,
+    pub checked: usize,
+    pub changed: Vec<RepriceChange>,
+}
+
+// serve_ids - только элементы с этими опциями, None - все элементы с опциями.
+// заказы (types 7) не трогаем: их цена фиксируется при заказе
+pub fn reprice_items(serve_ids: Option<&Vec<i32>>, dry_run: bool) -> RepriceReport {
+    let _connection = establish_connection();
+    let mut links_query = schema::serve_items::table
+        .filter(schema::serve_items::types.ne(7))
+        .select((schema::serve_items::item_id, schema::serve_items::types))
+        .into_boxed();
+    if let Some(ids) = serve_ids {
+        links_query = links_query.filter(schema::serve_items::serve_id.eq_any(ids));
+    }
+    let links = links_query.load::<(i32, i16)>(&_connection).expect("E");
+    reprice_links(links, dry_run)
+}
+
+// links - пары (item_id, types) из serve_items; пересчитываются только
+// элементы, у которых есть собственные связи (types связи = types элемента)
+pub fn reprice_links(mut links: Vec<(i32, i16)>, dry_run: bool) -> RepriceReport {
+    let _connection = establish_connection();
+    links.sort();
+    links.dedup();
+
+    let item_ids: Vec<i32> = links.iter().map(|(id, _)| *id).collect();
+    let _items = schema::items::table
+        .filter(schema::items::id.eq_any(&item_ids))
+        .order(schema::items::id.asc())
+        .load::<Item>(&_connection)
+        .expect("E");
+
+    let mut report = RepriceReport {
+        dry_run: dry_run,
+        checked: 0,
+        changed: Vec::new(),
+    };
+    for _item in _items.iter().filter(|i| links.contains(&(i.id, i.types))) {
+        report.checked += 1;
+        let _serve_ids = schema::serve_items::table
+            .filter(schema::serve_items::item_id.eq(_item.id))
+            .filter(schema::serve_items::types.eq(_item.types))
+            .select(schema::serve_items::serve_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        // удаленные опции в сумму не попадают
+        let new_price: i32 = schema::serve::table
+            .filter(schema::serve::id.eq_any(_serve_ids))
+            .select(schema::serve::price)
+            .load::<i32>(&_connection)
+            .expect("E")
+            .iter()
+            .sum();
+        let new_price_acc = get_price_acc_values(&new_price, _item.types);
+        if new_price == _item.price && new_price_acc == _item.price_acc {
+            continue;
+        }
+
+        if !dry_run {
+            diesel::update(_item)
+                .set((
+                    schema::items::price.eq(new_price),
+                    schema::items::price_acc.eq(new_price_acc),
+                ))
+                .execute(&_connection)
+                .expect("E");
+        }
+        report.changed.push(RepriceChange {
+            item_id:       _item.id,
+            types:         _item.types,
+            title:         _item.title.clone(),
+            old_price:     _item.price,
+            new_price:     new_price,
+            old_price_acc: _item.price_acc,
+            new_price_acc: new_price_acc,
+        });
+    }
+    report
+}
+
+// скидка по правилам у всех элементов и вариантов товаров; цены не меняются.
+// возвращает число обновленных строк
+pub fn refresh_price_acc() -> usize {
+    let _connection = establish_connection();
+    let mut changed = 0;
+    let _items = schema::items::table
+        .filter(schema::items::deleted_at.is_null())
+        .load::<Item>(&_connection)
+        .expect("E");
+    for _item in _items.iter() {
+        let price_acc = get_price_acc_values(&_item.price, _item.types);
+        if price_acc != _item.price_acc {
+            diesel::update(_item)
+                .set(schema::items::price_acc.eq(price_acc))
+                .execute(&_connection)
+                .expect("E");
+            changed += 1;
+        }
+    }
+    let _variants = schema::item_variants::table
+        .load::<ItemVariant>(&_connection)
+        .expect("E");
+    for _variant in _variants.iter() {
+        let price_acc = get_price_acc_values(&_variant.price, 3);
+        if price_acc != _variant.price_acc {
+            diesel::update(_variant)
+                .set(schema::item_variants::price_acc.eq(price_acc))
+                .execute(&_connection)
+                .expect("E");
+            changed += 1;
+        }
+    }
+    changed
+}
+
+#[derive(Serialize, Debug)]
+pub struct OrderLinksReport {
+    pub dry_run:               bool,
+    pub serve_items:           usize,
+    pub tech_categories_items: usize,
+    pub item_ids:              Vec<i32>,
+    pub reprice:               RepriceReport,
+}
+
+// до исправления create_order каждый заказ писал заказанному элементу
+// (item_id = object_id) копии опций и тех. категорий с types заказа.
+// тип заказа (1 услуга, 2 товар, 3 работа) с типом элемента не совпадает,
+// а собственные связи элемента всегда с его types - поэтому удаляем все
+// связи, кроме заказов (types 7), у которых types не равен types элемента,
+// и пересчитываем цены затронутых элементов.
+pub fn cleanup_order_links(dry_run: bool) -> OrderLinksReport {
+    let _connection = establish_connection();
+    let _serve_items = schema::serve_items::table
+        .filter(schema::serve_items::types.ne(7))
+        .load::<ServeItems>(&_connection)
+        .expect("E");
+    let _tech_items = schema::tech_categories_items::table
+        .filter(schema::tech_categories_items::types.ne(7))
+        .load::<TechCategoriesItem>(&_connection)
+        .expect("E");
+
+    let mut link_item_ids: Vec<i32> = _serve_items.iter().map(|s| s.item_id).collect();
+    link_item_ids.extend(_tech_items.iter().map(|t| t.item_id));
+    link_item_ids.sort();
+    link_item_ids.dedup();
+    let item_types: Vec<(i32, i16)> = schema::items::table
+        .filter(schema::items::id.eq_any(&link_item_ids))
+        .select((schema::items::id, schema::items::types))
+        .load::<(i32, i16)>(&_connection)
+        .expect("E");
+    let is_wrong = |item_id: i32, types: i16| -> bool {
+        item_types.iter().any(|(id, t)| *id == item_id && *t != types)
+    };
+
+    let serve_item_ids: Vec<i32> = _serve_items
+        .iter()
+        .filter(|s| is_wrong(s.item_id, s.types))
+        .map(|s| s.id)
+        .collect();
+    let tech_item_ids: Vec<i32> = _tech_items
+        .iter()
+        .filter(|t| is_wrong(t.item_id, t.types))
+        .map(|t| t.id)
+        .collect();
+    let mut item_ids: Vec<i32> = _serve_items
+        .iter()
+        .filter(|s| is_wrong(s.item_id, s.types))
+        .map(|s| s.item_id)
+        .chain(_tech_items.iter().filter(|t| is_wrong(t.item_id, t.types)).map(|t| t.item_id))
+        .collect();
+    item_ids.sort();
+    item_ids.dedup();
+
+    if !dry_run {
+        _connection.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::delete(schema::serve_items::table.filter(schema::serve_items::id.eq_any(&serve_item_ids)))
+                .execute(&_connection)?;
+            diesel::delete(schema::tech_categories_items::table.filter(schema::tech_categories_items::id.eq_any(&tech_item_ids)))
+                .execute(&_connection)?;
+            Ok(())
+        }).expect("E");
+    }
+
+    // пересчитываем по собственным связям: элемент без своих опций
+    // (например, товар) в пересчет не попадает и цену не теряет
+    let links = _serve_items
+        .iter()
+        .filter(|s| item_ids.contains(&s.item_id) && !is_wrong(s.item_id, s.types))
+        .map(|s| (s.item_id, s.types))
+        .collect();
+    OrderLinksReport {
+        dry_run:               dry_run,
+        serve_items:           serve_item_ids.len(),
+        tech_categories_items: tech_item_ids.len(),
+        item_ids:              item_ids,
+        reprice:               reprice_links(links, dry_run),
+    }
+}
+
+pub fn reprice_command(args: &[String]) -> bool {
+    if !args.is_empty() && args[0] == "cleanup_order_links" {
+        let report = cleanup_order_links(args.iter().any(|a| a == "--dry-run"));
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return true;
+    }
+    if args.is_empty() || args[0] != "reprice_items" {
+        return false;
+    }
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let serve_ids: Vec<i32> = args[1..]
+        .iter()
+        .filter_map(|a| a.parse::<i32>().ok())
+        .collect();
+    let report = match serve_ids.is_empty() {
+        true => reprice_items(None, dry_run),
+        false => reprice_items(Some(&serve_ids), dry_run),
+    };
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    true
+}
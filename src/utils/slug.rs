@@ -0,0 +1,132 @@
+// адреса из названий: транслитерация кириллицы, латиница, цифры и "-"
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+};
+use serde::Serialize;
+use crate::utils::establish_connection;
+
+
+fn translit(c: char) -> &'static str {
+    match c {
+        'а' => "a",  'б' => "b",  'в' => "v",  'г' => "g",  'д' => "d",
+        'е' => "e",  'ё' => "e",  'ж' => "zh", 'з' => "z",  'и' => "i",
+        'й' => "y",  'к' => "k",  'л' => "l",  'м' => "m",  'н' => "n",
+        'о' => "o",  'п' => "p",  'р' => "r",  'с' => "s",  'т' => "t",
+        'у' => "u",  'ф' => "f",  'х' => "h",  'ц' => "ts", 'ч' => "ch",
+        'ш' => "sh", 'щ' => "sch", 'ы' => "y", 'э' => "e",  'ю' => "yu",
+        'я' => "ya",
+        _ => "",
+    }
+}
+
+// "Rust и Actix" -> "rust-i-actix"
+pub fn get_slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        }
+        else if !translit(c).is_empty() {
+            slug += translit(c);
+        }
+        else if ['+', '#'].contains(&c) {
+            // c++, c#
+            slug += if c == '+' { "p" } else { "sharp" };
+        }
+        else if !slug.is_empty() && !slug.ends_with('-') && c != 'ъ' && c != 'ь' {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+#[derive(Serialize, Debug)]
+pub struct TagSlugChange {
+    pub tag_id:   i32,
+    pub name:     String,
+    pub old_slug: String,
+    pub new_slug: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TagSlugReport {
+    pub dry_run: bool,
+    pub checked: usize,
+    pub changed: Vec<TagSlugChange>,
+}
+
+// slug для тегов, созданных до появления адресов. Порядок на старой базе:
+// ALTER TABLE tags ADD COLUMN slug VARCHAR(100) NOT NULL DEFAULT '';
+// meta fill_tag_slugs; затем ALTER TABLE tags ADD UNIQUE(slug);
+// пустые slug заполняются из названия, у повторов свой slug остается
+// только у тега с меньшим id, остальные получают rust-2, rust-3...
+pub fn fill_tag_slugs(dry_run: bool) -> TagSlugReport {
+    let _connection = establish_connection();
+    let _tags = schema::tags::table
+        .order(schema::tags::id.asc())
+        .select((schema::tags::id, schema::tags::name, schema::tags::slug))
+        .load::<(i32, String, String)>(&_connection)
+        .expect("E");
+    let _aliases = schema::tag_aliases::table
+        .select((schema::tag_aliases::tag_id, schema::tag_aliases::slug))
+        .load::<(i32, String)>(&_connection)
+        .expect("E");
+
+    // сначала занимаем уже правильные slug, чтобы новые их не отняли
+    let mut used: Vec<String> = Vec::new();
+    let mut to_fix: Vec<&(i32, String, String)> = Vec::new();
+    for tag in _tags.iter() {
+        if tag.2.is_empty() || used.contains(&tag.2) {
+            to_fix.push(tag);
+        }
+        else {
+            used.push(tag.2.clone());
+        }
+    }
+
+    let mut report = TagSlugReport {
+        dry_run: dry_run,
+        checked: _tags.len(),
+        changed: Vec::new(),
+    };
+    for (tag_id, name, old_slug) in to_fix.into_iter() {
+        let mut base = get_slug(name);
+        if base.is_empty() {
+            base = "tag".to_string();
+        }
+        let mut slug = base.clone();
+        let mut n = 1;
+        while used.contains(&slug) || _aliases.iter().any(|a| a.1 == slug && a.0 != *tag_id) {
+            n += 1;
+            slug = base.clone() + &"-".to_string() + &n.to_string();
+        }
+        used.push(slug.clone());
+
+        if !dry_run {
+            diesel::update(schema::tags::table.filter(schema::tags::id.eq(tag_id)))
+                .set(schema::tags::slug.eq(&slug))
+                .execute(&_connection)
+                .expect("E");
+        }
+        report.changed.push(TagSlugChange {
+            tag_id:   *tag_id,
+            name:     name.clone(),
+            old_slug: old_slug.clone(),
+            new_slug: slug,
+        });
+    }
+    report
+}
+
+// консольный вариант: meta fill_tag_slugs [--dry-run]
+pub fn tag_slugs_command(args: &[String]) -> bool {
+    if args.is_empty() || args[0] != "fill_tag_slugs" {
+        return false;
+    }
+    let report = fill_tag_slugs(args.iter().any(|a| a == "--dry-run"));
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    true
+}
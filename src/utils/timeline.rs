@@ -0,0 +1,123 @@
+// оценка сроков по трудоемкости выбранных опций.
+// опции обычных категорий делаются по очереди одним потоком,
+// каждая категория с is_parallel - своим потоком. Потоки раздаем
+// людям (самые длинные - первыми, каждый - наименее загруженному),
+// срок считаем по самому загруженному, в рабочих днях с учетом
+// выходных и праздников из админки (/timeline/).
+use serde::Serialize;
+use chrono::{NaiveDate, Datelike};
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+};
+use crate::models::{TimelineSettings, Holiday};
+use crate::utils::{
+    establish_connection,
+    get_count_for_ru,
+    CalculateLine,
+};
+
+
+#[derive(Serialize, Debug)]
+pub struct Timeline {
+    pub man_hours: i32,
+    pub workers:   i16,      // сколько человек занято
+    pub work_days: i32,
+    pub starts:    NaiveDate,
+    pub deadline:  NaiveDate,
+}
+
+pub fn get_work_days_ru(work_days: i32) -> String {
+    get_count_for_ru (
+        work_days as i16,
+        " рабочий день".to_string(),
+        " рабочих дня".to_string(),
+        " рабочих дней".to_string(),
+    )
+}
+
+fn is_work_day(day: NaiveDate, settings: &TimelineSettings, holidays: &Vec<NaiveDate>) -> bool {
+    if holidays.contains(&day) {
+        return false;
+    }
+    settings.work_weekends || day.weekday().number_from_monday() < 6
+}
+
+// ближайший рабочий день начиная с day, и от него еще count рабочих дней
+fn add_work_days(day: NaiveDate, count: i32, settings: &TimelineSettings, holidays: &Vec<NaiveDate>) -> NaiveDate {
+    let mut day = day;
+    while !is_work_day(day, settings, holidays) {
+        day = day.succ();
+    }
+    let mut left = count;
+    while left > 0 {
+        day = day.succ();
+        if is_work_day(day, settings, holidays) {
+            left -= 1;
+        }
+    }
+    day
+}
+
+pub fn get_timeline(lines: &Vec<CalculateLine>) -> Timeline {
+    let settings = TimelineSettings::get();
+    let hours_per_day = std::cmp::max(settings.hours_per_day, 1) as i32;
+    let workers = std::cmp::max(settings.workers, 1) as usize;
+
+    let _connection = establish_connection();
+    let category_ids: Vec<i32> = lines.iter().map(|line| line.category_id).collect();
+    let parallel_ids = schema::serve_categories::table
+        .filter(schema::serve_categories::id.eq_any(&category_ids))
+        .filter(schema::serve_categories::is_parallel.eq(true))
+        .select(schema::serve_categories::id)
+        .load::<i32>(&_connection)
+        .expect("E");
+
+    // первый поток - общий последовательный
+    let mut track_ids: Vec<i32> = vec![0];
+    let mut tracks: Vec<i32> = vec![0];
+    for line in lines.iter() {
+        if !parallel_ids.contains(&line.category_id) {
+            tracks[0] += line.man_hours as i32;
+            continue;
+        }
+        match track_ids.iter().position(|id| id == &line.category_id) {
+            Some(i) => tracks[i] += line.man_hours as i32,
+            None => {
+                track_ids.push(line.category_id);
+                tracks.push(line.man_hours as i32);
+            },
+        }
+    }
+    tracks.sort_by(|a, b| b.cmp(a));
+
+    let mut loads = vec![0; workers];
+    for hours in tracks.iter().filter(|hours| **hours > 0) {
+        let i = loads
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, load)| **load)
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        loads[i] += hours;
+    }
+    let max_load = loads.iter().max().copied().unwrap_or(0);
+    let work_days = (max_load + hours_per_day - 1) / hours_per_day;
+
+    let today = (chrono::Local::now().naive_utc() + chrono::Duration::hours(3)).date();
+    let holidays = Holiday::get_days_from(today);
+    let starts = add_work_days(today, settings.start_delay as i32, &settings, &holidays);
+    let deadline = match work_days {
+        0 => starts,
+        _ => add_work_days(starts, work_days - 1, &settings, &holidays),
+    };
+    Timeline {
+        man_hours: lines.iter().map(|line| line.man_hours as i32).sum(),
+        workers:   loads.iter().filter(|load| **load > 0).count() as i16,
+        work_days: work_days,
+        starts:    starts,
+        deadline:  deadline,
+    }
+}
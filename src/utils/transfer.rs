@@ -0,0 +1,764 @@
+// выгрузка и загрузка элементов каталога (json / csv).
+// элемент ищется по slug: если найден - обновляем, иначе создаем.
+// slug занят элементом другого типа - строка отклоняется.
+// связи (категории, теги, опции, файлы) передаются не по id, а по
+// slug категорий, названиям тегов, id опций и src файлов.
+use crate::schema;
+use crate::diesel::{
+    Connection,
+    PgConnection,
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+};
+use serde::{Deserialize, Serialize};
+use crate::models::{
+    Item,
+    File,
+    Serve,
+};
+use crate::utils::{
+    establish_connection,
+    get_price_acc_values,
+    get_reading_stats,
+};
+
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TransferFile {
+    pub types:          i16,
+    pub src:            String,
+    pub description:    Option<String>,
+    pub description_en: Option<String>,
+    pub position:       i16,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TransferItem {
+    pub slug:           String,
+    pub types:          i16,
+    pub title:          String,
+    pub title_en:       String,
+    pub description:    Option<String>,
+    pub description_en: Option<String>,
+    pub content:        Option<String>,
+    pub content_en:     Option<String>,
+    pub link:           Option<String>,
+    pub image:          Option<String>,
+    pub is_active:      bool,
+    pub position:       i16,
+    pub price:          i32,
+    pub categories:     Vec<String>,       // slug категорий
+    pub tags:           Vec<String>,       // названия тегов
+    pub serves:         Vec<i32>,          // id опций
+    pub files:          Vec<TransferFile>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TransferRowError {
+    pub row:    usize,
+    pub slug:   String,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TransferReport {
+    pub dry_run: bool,
+    pub total:   usize,
+    pub created: usize,
+    pub updated: usize,
+    pub errors:  Vec<TransferRowError>,
+}
+
+const CSV_HEADERS: [&str; 17] = [
+    "slug", "types", "title", "title_en", "description", "description_en",
+    "content", "content_en", "link", "image", "is_active", "position", "price",
+    "categories", "tags", "serves", "files",
+];
+
+pub fn export_items(types: Option<i16>) -> Vec<TransferItem> {
+    let _connection = establish_connection();
+    let _items = match types {
+        Some(t) => schema::items::table
+            .filter(schema::items::types.eq(t))
+            .filter(schema::items::deleted_at.is_null())
+            .order(schema::items::id.asc())
+            .load::<Item>(&_connection)
+            .expect("E"),
+        None => schema::items::table
+            .filter(schema::items::deleted_at.is_null())
+            .order(schema::items::id.asc())
+            .load::<Item>(&_connection)
+            .expect("E"),
+    };
+
+    let mut list = Vec::new();
+    for _item in _items.into_iter() {
+        let _files = schema::files::table
+            .filter(schema::files::item_id.eq(_item.id))
+            .filter(schema::files::item_types.eq(_item.types))
+            .filter(schema::files::deleted_at.is_null())
+            .order(schema::files::position.asc())
+            .load::<File>(&_connection)
+            .expect("E")
+            .into_iter()
+            .map(|f| TransferFile {
+                types:          f.types,
+                src:            f.src,
+                description:    f.description,
+                description_en: f.description_en,
+                position:       f.position,
+            })
+            .collect();
+
+        list.push(TransferItem {
+            categories:     _item.get_categories().into_iter().map(|c| c.slug).collect(),
+            tags:           _item.get_tags_obj().into_iter().map(|t| t.name).collect(),
+            serves:         _item.get_serves_ids(),
+            files:          _files,
+            slug:           _item.slug,
+            types:          _item.types,
+            title:          _item.title,
+            title_en:       _item.title_en,
+            description:    _item.description,
+            description_en: _item.description_en,
+            content:        _item.content,
+            content_en:     _item.content_en,
+            link:           _item.link,
+            image:          _item.image,
+            is_active:      _item.is_active,
+            position:       _item.position,
+            price:          _item.price,
+        });
+    }
+    list
+}
+
+// списки в csv пишем через "|", файлы - json-массивом, чтобы описания
+// с любыми символами пережили выгрузку и загрузку
+pub fn items_to_csv(list: &Vec<TransferItem>) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&CSV_HEADERS).expect("E");
+    for i in list.iter() {
+        let files = match i.files.is_empty() {
+            true => "".to_string(),
+            false => serde_json::to_string(&i.files).unwrap_or_default(),
+        };
+        writer.write_record(&[
+            i.slug.clone(),
+            i.types.to_string(),
+            i.title.clone(),
+            i.title_en.clone(),
+            i.description.clone().unwrap_or_default(),
+            i.description_en.clone().unwrap_or_default(),
+            i.content.clone().unwrap_or_default(),
+            i.content_en.clone().unwrap_or_default(),
+            i.link.clone().unwrap_or_default(),
+            i.image.clone().unwrap_or_default(),
+            i.is_active.to_string(),
+            i.position.to_string(),
+            i.price.to_string(),
+            i.categories.join("|"),
+            i.tags.join("|"),
+            i.serves.iter().map(|s| s.to_string()).collect::<Vec<String>>().join("|"),
+            files,
+        ]).expect("E");
+    }
+    String::from_utf8(writer.into_inner().expect("E")).unwrap_or_default()
+}
+
+fn csv_option(value: &str) -> Option<String> {
+    if value.is_empty() {
+        None
+    }
+    else {
+        Some(value.to_string())
+    }
+}
+fn csv_list(value: &str) -> Vec<String> {
+    value.split("|")
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+// файлы: json-массив, как в выгрузке, или старый вид
+// "types;src;position|types;src;position" - без описаний
+fn csv_files(value: &str) -> Result<Vec<TransferFile>, String> {
+    if value.trim_start().starts_with("[") {
+        return serde_json::from_str::<Vec<TransferFile>>(value)
+            .map_err(|e| format!("файлы: {}", e));
+    }
+    let mut files = Vec::new();
+    for f in csv_list(value).iter() {
+        let parts: Vec<&str> = f.split(";").collect();
+        if parts.len() < 2 {
+            return Err(format!("файл {} - ожидается types;src;position", f));
+        }
+        files.push(TransferFile {
+            types:          parts[0].parse().unwrap_or(0),
+            src:            parts[1].to_string(),
+            description:    None,
+            description_en: None,
+            position:       parts.get(2).and_then(|p| p.parse().ok()).unwrap_or(0),
+        });
+    }
+    Ok(files)
+}
+
+// строки с ошибками разбора не теряются - они возвращаются
+// как ошибки с номером строки, остальные идут дальше.
+pub fn items_from_csv(data: &str) -> (Vec<(usize, TransferItem)>, Vec<TransferRowError>) {
+    let mut list = Vec::new();
+    let mut errors = Vec::new();
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+
+    let headers = match reader.headers() {
+        Ok(h) => h.clone(),
+        Err(e) => {
+            errors.push(TransferRowError {
+                row:    0,
+                slug:   "".to_string(),
+                errors: vec!(e.to_string()),
+            });
+            return (list, errors);
+        },
+    };
+    for h in CSV_HEADERS.iter() {
+        if !headers.iter().any(|i| &i == h) {
+            errors.push(TransferRowError {
+                row:    0,
+                slug:   "".to_string(),
+                errors: vec!(format!("нет колонки {}", h)),
+            });
+        }
+    }
+    if !errors.is_empty() {
+        return (list, errors);
+    }
+
+    for (n, record) in reader.records().enumerate() {
+        let row = n + 1;
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                errors.push(TransferRowError {
+                    row:    row,
+                    slug:   "".to_string(),
+                    errors: vec!(e.to_string()),
+                });
+                continue;
+            },
+        };
+        let get = |name: &str| -> String {
+            let index = headers.iter().position(|h| h == name).unwrap();
+            record.get(index).unwrap_or("").to_string()
+        };
+
+        let mut row_errors = Vec::new();
+        let types = get("types").parse::<i16>().unwrap_or_else(|_| {
+            row_errors.push("types - не число".to_string());
+            0
+        });
+        let position = get("position").parse::<i16>().unwrap_or(0);
+        let price = get("price").parse::<i32>().unwrap_or(0);
+        let is_active = get("is_active") == "true" || get("is_active") == "1";
+
+        let mut serves = Vec::new();
+        for s in csv_list(&get("serves")).iter() {
+            match s.parse::<i32>() {
+                Ok(id) => serves.push(id),
+                Err(_) => row_errors.push(format!("опция {} - не число", s)),
+            }
+        }
+        let files = csv_files(&get("files")).unwrap_or_else(|e| {
+            row_errors.push(e);
+            Vec::new()
+        });
+
+        if !row_errors.is_empty() {
+            errors.push(TransferRowError {
+                row:    row,
+                slug:   get("slug"),
+                errors: row_errors,
+            });
+            continue;
+        }
+        list.push((row, TransferItem {
+            slug:           get("slug"),
+            types:          types,
+            title:          get("title"),
+            title_en:       get("title_en"),
+            description:    csv_option(&get("description")),
+            description_en: csv_option(&get("description_en")),
+            content:        csv_option(&get("content")),
+            content_en:     csv_option(&get("content_en")),
+            link:           csv_option(&get("link")),
+            image:          csv_option(&get("image")),
+            is_active:      is_active,
+            position:       position,
+            price:          price,
+            categories:     csv_list(&get("categories")),
+            tags:           csv_list(&get("tags")),
+            serves:         serves,
+            files:          files,
+        }));
+    }
+    (list, errors)
+}
+
+// проверяем строку: тип, slug, существование категорий, тегов и опций.
+// возвращаем id категорий и тегов, чтобы не искать их второй раз.
+fn validate_transfer_item(_connection: &PgConnection, i: &TransferItem) -> Result<(Vec<i32>, Vec<i32>), Vec<String>> {
+    let mut errors = Vec::new();
+    if i.types < 1 || i.types > 6 {
+        errors.push(format!("неизвестный тип {}", i.types));
+    }
+    if i.slug.trim().is_empty() {
+        errors.push("пустой slug".to_string());
+    }
+    // slug уникален для всех типов - элемент другого типа не перезаписываем
+    let old_types = schema::items::table
+        .filter(schema::items::slug.eq(&i.slug))
+        .select(schema::items::types)
+        .first::<i16>(_connection);
+    if let Ok(old_types) = old_types {
+        if old_types != i.types {
+            errors.push(format!("slug занят элементом другого типа {}", old_types));
+        }
+    }
+    if i.title.trim().is_empty() {
+        errors.push("пустой title".to_string());
+    }
+
+    let mut cat_ids = Vec::new();
+    for slug in i.categories.iter() {
+        let _category = schema::categories::table
+            .filter(schema::categories::slug.eq(slug))
+            .filter(schema::categories::types.eq(i.types))
+            .filter(schema::categories::deleted_at.is_null())
+            .select(schema::categories::id)
+            .first::<i32>(_connection);
+        match _category {
+            Ok(id) => cat_ids.push(id),
+            Err(_) => errors.push(format!("категория {} не найдена", slug)),
+        }
+    }
+    let mut tag_ids = Vec::new();
+    for name in i.tags.iter() {
+        let _tag = schema::tags::table
+            .filter(schema::tags::name.eq(name))
+            .filter(schema::tags::deleted_at.is_null())
+            .select(schema::tags::id)
+            .first::<i32>(_connection);
+        match _tag {
+            Ok(id) => tag_ids.push(id),
+            Err(_) => errors.push(format!("тег {} не найден", name)),
+        }
+    }
+    let serve_ids = schema::serve::table
+        .filter(schema::serve::id.eq_any(&i.serves))
+        .select(schema::serve::id)
+        .load::<i32>(_connection)
+        .expect("E");
+    for id in i.serves.iter() {
+        if !serve_ids.iter().any(|s| s == id) {
+            errors.push(format!("опция {} не найдена", id));
+        }
+    }
+    for f in i.files.iter() {
+        if f.types < 1 || f.types > 4 {
+            errors.push(format!("файл {}: неизвестный тип {}", f.src, f.types));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok((cat_ids, tag_ids))
+    }
+    else {
+        Err(errors)
+    }
+}
+
+// меняем счетчики категорий и тегов активного элемента
+fn change_item_counters(_connection: &PgConnection, _item: &Item, step: i16) -> Result<(), diesel::result::Error> {
+    for _category in _item.get_categories_obj().iter() {
+        diesel::update(_category)
+            .set(schema::categories::count.eq(_category.count + step))
+            .execute(_connection)?;
+    }
+    for _tag in _item.get_tags_obj().iter() {
+        diesel::update(_tag)
+            .set(schema::tags::count.eq(_tag.count + step))
+            .execute(_connection)?;
+    }
+    Ok(())
+}
+
+// сохраняем одну проверенную строку. true - создан новый элемент.
+fn save_transfer_item (
+    _connection: &PgConnection,
+    i:           &TransferItem,
+    cat_ids:     Vec<i32>,
+    tag_ids:     Vec<i32>,
+    user_id:     i32
+) -> Result<bool, diesel::result::Error> {
+    use crate::models::{
+        NewItem,
+        NewCategory,
+        NewTagItems,
+        NewServeItems,
+        NewTechCategoriesItem,
+        NewFile,
+    };
+
+    let _serves = schema::serve::table
+        .filter(schema::serve::id.eq_any(&i.serves))
+        .load::<Serve>(_connection)?;
+    let mut item_price = i.price;
+    if !_serves.is_empty() {
+        item_price = _serves.iter().map(|s| s.price).sum();
+    }
+    let price_acc = get_price_acc_values(&item_price, i.types);
+
+    let _old = schema::items::table
+        .filter(schema::items::slug.eq(&i.slug))
+        .filter(schema::items::types.eq(i.types))
+        .first::<Item>(_connection);
+    let created = _old.is_err();
+
+    let _item = if let Ok(_old) = _old {
+        // элемент в корзине или неактивный уже не учтен в счетчиках
+        if _old.is_active && _old.deleted_at.is_none() {
+            change_item_counters(_connection, &_old, -1)?;
+        }
+        diesel::delete(schema::category::table
+            .filter(schema::category::item_id.eq(_old.id))
+            .filter(schema::category::types.eq(_old.types)))
+            .execute(_connection)?;
+        diesel::delete(schema::tags_items::table
+            .filter(schema::tags_items::item_id.eq(_old.id))
+            .filter(schema::tags_items::types.eq(_old.types)))
+            .execute(_connection)?;
+        diesel::delete(schema::serve_items::table
+            .filter(schema::serve_items::item_id.eq(_old.id))
+            .filter(schema::serve_items::types.eq(_old.types)))
+            .execute(_connection)?;
+        diesel::delete(schema::tech_categories_items::table
+            .filter(schema::tech_categories_items::item_id.eq(_old.id))
+            .filter(schema::tech_categories_items::types.eq(_old.types))
+            .filter(schema::tech_categories_items::is_active.eq(1)))
+            .execute(_connection)?;
+
+        diesel::update(&_old)
+            .set((
+                schema::items::title.eq(&i.title),
+                schema::items::title_en.eq(&i.title_en),
+                schema::items::description.eq(&i.description),
+                schema::items::description_en.eq(&i.description_en),
+                schema::items::content.eq(&i.content),
+                schema::items::content_en.eq(&i.content_en),
+                schema::items::link.eq(&i.link),
+                schema::items::image.eq(&i.image),
+                schema::items::is_active.eq(i.is_active),
+                schema::items::position.eq(i.position),
+                schema::items::price.eq(item_price),
+                schema::items::price_acc.eq(price_acc),
+                schema::items::deleted_at.eq(None::<chrono::NaiveDateTime>),
+            ))
+            .get_result::<Item>(_connection)?
+    }
+    else {
+        let mut new_item = NewItem::create (
+            i.title.clone(),
+            i.title_en.clone(),
+            i.description.clone(),
+            i.description_en.clone(),
+            i.link.clone(),
+            i.image.clone(),
+            user_id,
+            i.position,
+            i.types,
+            i.slug.clone(),
+        );
+        new_item.content = i.content.clone();
+        new_item.content_en = i.content_en.clone();
+        new_item.is_active = i.is_active;
+        new_item.price = item_price;
+        new_item.price_acc = price_acc;
+        diesel::insert_into(schema::items::table)
+            .values(&new_item)
+            .get_result::<Item>(_connection)?
+    };
+
+    let (words, minutes, words_en, minutes_en) = get_reading_stats(&i.content, &i.content_en);
+    diesel::update(&_item)
+        .set((
+            schema::items::word_count.eq(words),
+            schema::items::reading_time.eq(minutes),
+            schema::items::word_count_en.eq(words_en),
+            schema::items::reading_time_en.eq(minutes_en),
+        ))
+        .execute(_connection)?;
+
+    for category_id in cat_ids.into_iter() {
+        diesel::insert_into(schema::category::table)
+            .values(&NewCategory {
+                category_id: category_id,
+                item_id:     _item.id,
+                types:       _item.types,
+            })
+            .execute(_connection)?;
+    }
+    for tag_id in tag_ids.into_iter() {
+        diesel::insert_into(schema::tags_items::table)
+            .values(&NewTagItems {
+                tag_id:  tag_id,
+                item_id: _item.id,
+                types:   _item.types,
+                created: chrono::Local::now().naive_utc(),
+            })
+            .execute(_connection)?;
+    }
+    let mut tech_cat_ids = Vec::new();
+    for _serve in _serves.iter() {
+        diesel::insert_into(schema::serve_items::table)
+            .values(&NewServeItems {
+                serve_id: _serve.id,
+                item_id:  _item.id,
+                types:    _item.types,
+            })
+            .execute(_connection)?;
+        if !tech_cat_ids.iter().any(|&i| i == _serve.tech_cat_id) {
+            tech_cat_ids.push(_serve.tech_cat_id);
+        }
+    }
+    for id in tech_cat_ids.into_iter() {
+        diesel::insert_into(schema::tech_categories_items::table)
+            .values(&NewTechCategoriesItem {
+                category_id: id,
+                item_id:     _item.id,
+                types:       _item.types,
+                is_active:   1,
+            })
+            .execute(_connection)?;
+    }
+
+    // файлы элемента, которых нет в загрузке, уходят в корзину
+    let srcs: Vec<String> = i.files.iter().map(|f| f.src.clone()).collect();
+    diesel::update(schema::files::table
+        .filter(schema::files::item_id.eq(_item.id))
+        .filter(schema::files::item_types.eq(_item.types))
+        .filter(schema::files::src.ne_all(srcs))
+        .filter(schema::files::deleted_at.is_null()))
+        .set(schema::files::deleted_at.eq(chrono::Local::now().naive_utc() + chrono::Duration::hours(3)))
+        .execute(_connection)?;
+
+    // файлы уникальны по src - существующий привязываем к элементу
+    // и обновляем его описание и порядок
+    for f in i.files.iter() {
+        let _file = schema::files::table
+            .filter(schema::files::src.eq(&f.src))
+            .first::<File>(_connection);
+        if let Ok(_file) = _file {
+            diesel::update(&_file)
+                .set((
+                    schema::files::item_id.eq(_item.id),
+                    schema::files::item_types.eq(_item.types),
+                    schema::files::types.eq(f.types),
+                    schema::files::description.eq(&f.description),
+                    schema::files::description_en.eq(&f.description_en),
+                    schema::files::position.eq(f.position),
+                    schema::files::deleted_at.eq(None::<chrono::NaiveDateTime>),
+                ))
+                .execute(_connection)?;
+        }
+        else {
+            let mut new_file = NewFile::create (
+                user_id,
+                _item.id,
+                _item.types,
+                f.types,
+                f.src.clone(),
+            );
+            new_file.description = f.description.clone();
+            new_file.description_en = f.description_en.clone();
+            new_file.position = f.position;
+            diesel::insert_into(schema::files::table)
+                .values(&new_file)
+                .execute(_connection)?;
+        }
+    }
+
+    if _item.is_active {
+        change_item_counters(_connection, &_item, 1)?;
+    }
+    Ok(created)
+}
+
+// dry_run - только проверка, в базу ничего не пишется.
+// каждая строка сохраняется в своей транзакции: ошибка одной
+// строки не откатывает остальные.
+// list - пары (номер строки в файле, элемент).
+pub fn import_items(list: Vec<(usize, TransferItem)>, user_id: i32, dry_run: bool) -> TransferReport {
+    let _connection = establish_connection();
+    let mut report = TransferReport {
+        dry_run: dry_run,
+        total:   list.len(),
+        created: 0,
+        updated: 0,
+        errors:  Vec::new(),
+    };
+    let mut slugs: Vec<String> = Vec::new();
+
+    for (row, i) in list.iter() {
+        let row = *row;
+        if slugs.iter().any(|s| s == &i.slug) {
+            report.errors.push(TransferRowError {
+                row:    row,
+                slug:   i.slug.clone(),
+                errors: vec!("slug повторяется в файле".to_string()),
+            });
+            continue;
+        }
+        slugs.push(i.slug.clone());
+
+        let (cat_ids, tag_ids) = match validate_transfer_item(&_connection, i) {
+            Ok(ids) => ids,
+            Err(errors) => {
+                report.errors.push(TransferRowError {
+                    row:    row,
+                    slug:   i.slug.clone(),
+                    errors: errors,
+                });
+                continue;
+            },
+        };
+
+        if dry_run {
+            let exists = schema::items::table
+                .filter(schema::items::slug.eq(&i.slug))
+                .select(schema::items::id)
+                .first::<i32>(&_connection)
+                .is_ok();
+            if exists {
+                report.updated += 1;
+            }
+            else {
+                report.created += 1;
+            }
+            continue;
+        }
+
+        let result = _connection.transaction::<_, diesel::result::Error, _>(|| {
+            save_transfer_item(&_connection, i, cat_ids, tag_ids, user_id)
+        });
+        match result {
+            Ok(true) => report.created += 1,
+            Ok(false) => report.updated += 1,
+            Err(e) => report.errors.push(TransferRowError {
+                row:    row,
+                slug:   i.slug.clone(),
+                errors: vec!(e.to_string()),
+            }),
+        }
+    }
+    report
+}
+
+// разбор загруженных данных по формату ("json" или "csv")
+pub fn parse_transfer_data(data: &str, format: &str) -> (Vec<(usize, TransferItem)>, Vec<TransferRowError>) {
+    if format == "csv" {
+        return items_from_csv(data);
+    }
+    // сначала массив как есть, потом каждый элемент отдельно -
+    // кривая строка не должна валить весь файл
+    let values = match serde_json::from_str::<Vec<serde_json::Value>>(data) {
+        Ok(values) => values,
+        Err(e) => return (Vec::new(), vec!(TransferRowError {
+            row:    e.line(),
+            slug:   "".to_string(),
+            errors: vec!(e.to_string()),
+        })),
+    };
+    let mut list = Vec::new();
+    let mut errors = Vec::new();
+    for (n, value) in values.into_iter().enumerate() {
+        let slug = value.get("slug")
+            .and_then(|s| s.as_str())
+            .unwrap_or("")
+            .to_string();
+        match serde_json::from_value::<TransferItem>(value) {
+            Ok(i) => list.push((n + 1, i)),
+            Err(e) => errors.push(TransferRowError {
+                row:    n + 1,
+                slug:   slug,
+                errors: vec!(e.to_string()),
+            }),
+        }
+    }
+    (list, errors)
+}
+
+// разбор и загрузка; ошибки разбора идут в отчет первыми
+pub fn import_transfer_data(data: &str, format: &str, user_id: i32, dry_run: bool) -> TransferReport {
+    let (list, mut errors) = parse_transfer_data(data, format);
+    let mut report = import_items(list, user_id, dry_run);
+    report.total += errors.len();
+    errors.append(&mut report.errors);
+    report.errors = errors;
+    report
+}
+
+// консольный вариант:
+// meta export json|csv [types]
+// meta import <файл.json|файл.csv> [--dry-run]
+pub fn transfer_command(args: &[String]) -> bool {
+    if args.is_empty() {
+        return false;
+    }
+    if args[0] == "export" {
+        let format = args.get(1).map(|s| s.as_str()).unwrap_or("json");
+        let types = args.get(2).and_then(|t| t.parse::<i16>().ok());
+        let list = export_items(types);
+        if format == "csv" {
+            print!("{}", items_to_csv(&list));
+        }
+        else {
+            println!("{}", serde_json::to_string_pretty(&list).unwrap());
+        }
+        return true;
+    }
+    else if args[0] == "import" {
+        let path = match args.get(1) {
+            Some(p) => p,
+            None => {
+                eprintln!("usage: meta import <file.json|file.csv> [--dry-run]");
+                return true;
+            },
+        };
+        let data = match std::fs::read_to_string(path) {
+            Ok(d) => d,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                return true;
+            },
+        };
+        let format = if path.ends_with(".csv") { "csv" } else { "json" };
+        let dry_run = args.iter().any(|a| a == "--dry-run");
+
+        // в консоли владельцем новых элементов будет первый суперюзер
+        let _connection = establish_connection();
+        let user_id = schema::users::table
+            .filter(schema::users::perm.eq(60))
+            .order(schema::users::id.asc())
+            .select(schema::users::id)
+            .first::<i32>(&_connection)
+            .expect("нет пользователя с правами администратора");
+
+        let report = import_transfer_data(&data, format, user_id, dry_run);
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return true;
+    }
+    false
+}
@@ -0,0 +1,106 @@
+// отчет о непереведенных объектах: элементы, категории и теги,
+// у которых заполнена русская версия, а английская пустая.
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+};
+use serde::Serialize;
+use crate::models::{
+    Item,
+    Categories,
+    Tag,
+};
+use crate::utils::establish_connection;
+
+
+#[derive(Serialize, Debug)]
+pub struct MissingTranslation {
+    pub id:     i32,
+    pub name:   String,
+    pub link:   String,      // где исправить
+    pub fields: Vec<String>, // какие поля не переведены
+}
+
+fn is_empty(value: &Option<String>) -> bool {
+    match value {
+        Some(v) => v.trim().is_empty(),
+        None => true,
+    }
+}
+
+pub fn get_missing_translations() -> (
+    Vec<MissingTranslation>, // элементы
+    Vec<MissingTranslation>, // категории
+    Vec<MissingTranslation>, // теги
+) {
+    let _connection = establish_connection();
+
+    let mut items_list = Vec::new();
+    let _items = schema::items::table
+        .filter(schema::items::deleted_at.is_null())
+        .order(schema::items::types.asc())
+        .load::<Item>(&_connection)
+        .expect("E");
+    for i in _items.into_iter() {
+        let mut fields = Vec::new();
+        if i.title_en.trim().is_empty() {
+            fields.push("title_en".to_string());
+        }
+        if !is_empty(&i.description) && is_empty(&i.description_en) {
+            fields.push("description_en".to_string());
+        }
+        if !is_empty(&i.content) && is_empty(&i.content_en) {
+            fields.push("content_en".to_string());
+        }
+        if !fields.is_empty() {
+            items_list.push(MissingTranslation {
+                id:     i.id,
+                name:   i.title.clone() + &" (".to_string() + &i.get_type() + &")".to_string(),
+                link:   "/edit_item/".to_string() + &i.id.to_string() + &"/".to_string(),
+                fields: fields,
+            });
+        }
+    }
+
+    let mut categories_list = Vec::new();
+    let _categories = schema::categories::table
+        .filter(schema::categories::deleted_at.is_null())
+        .order(schema::categories::types.asc())
+        .load::<Categories>(&_connection)
+        .expect("E");
+    for c in _categories.into_iter() {
+        let mut fields = Vec::new();
+        if c.name_en.trim().is_empty() {
+            fields.push("name_en".to_string());
+        }
+        if !is_empty(&c.description) && is_empty(&c.description_en) {
+            fields.push("description_en".to_string());
+        }
+        if !fields.is_empty() {
+            categories_list.push(MissingTranslation {
+                id:     c.id,
+                name:   c.name.clone() + &" (".to_string() + &c.get_type() + &")".to_string(),
+                link:   "/edit_category/".to_string() + &c.id.to_string() + &"/".to_string(),
+                fields: fields,
+            });
+        }
+    }
+
+    let _tags = schema::tags::table
+        .filter(schema::tags::deleted_at.is_null())
+        .filter(schema::tags::name_en.eq(""))
+        .load::<Tag>(&_connection)
+        .expect("E");
+    let tags_list = _tags.into_iter()
+        .map(|t| MissingTranslation {
+            id:     t.id,
+            name:   t.name.clone(),
+            link:   "/edit_tag/".to_string() + &t.id.to_string() + &"/".to_string(),
+            fields: vec!("name_en".to_string()),
+        })
+        .collect();
+
+    (items_list, categories_list, tags_list)
+}
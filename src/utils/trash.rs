@@ -0,0 +1,256 @@
+// корзина: удаленные элементы, категории, теги и файлы хранятся
+// с отметкой deleted_at и окончательно удаляются после срока хранения.
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+    BoolExpressionMethods,
+    OptionalExtension,
+    Connection,
+    PgConnection,
+};
+use diesel::result::Error;
+use serde::Serialize;
+use crate::models::{
+    Item,
+    Categories,
+    Tag,
+    File,
+};
+use crate::models::get_order_item_types;
+use crate::utils::establish_connection;
+
+
+#[derive(Serialize, Debug)]
+pub struct PurgeReport {
+    pub retention_days: i64,
+    pub items:          usize,
+    pub categories:     usize,
+    pub tags:           usize,
+    pub files:          usize,
+    pub skipped_items:  Vec<i32>, // элементы с заказами - остались в корзине
+}
+
+// срок хранения в корзине, дней. TRASH_RETENTION_DAYS в .env, по умолчанию 30
+pub fn get_trash_retention_days() -> i64 {
+    dotenv::dotenv().ok();
+    std::env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|d| d.parse::<i64>().ok())
+        .unwrap_or(30)
+}
+
+pub fn get_trash_items() -> Vec<Item> {
+    let _connection = establish_connection();
+    return schema::items::table
+        .filter(schema::items::deleted_at.is_not_null())
+        .order(schema::items::deleted_at.desc())
+        .load::<Item>(&_connection)
+        .expect("E");
+}
+pub fn get_trash_categories() -> Vec<Categories> {
+    let _connection = establish_connection();
+    return schema::categories::table
+        .filter(schema::categories::deleted_at.is_not_null())
+        .order(schema::categories::deleted_at.desc())
+        .load::<Categories>(&_connection)
+        .expect("E");
+}
+pub fn get_trash_tags() -> Vec<Tag> {
+    let _connection = establish_connection();
+    return schema::tags::table
+        .filter(schema::tags::deleted_at.is_not_null())
+        .order(schema::tags::deleted_at.desc())
+        .load::<Tag>(&_connection)
+        .expect("E");
+}
+pub fn get_trash_files() -> Vec<File> {
+    let _connection = establish_connection();
+    return schema::files::table
+        .filter(schema::files::deleted_at.is_not_null())
+        .order(schema::files::deleted_at.desc())
+        .load::<File>(&_connection)
+        .expect("E");
+}
+
+// есть ли заказы на элемент или его варианты - такие элементы
+// не удаляем, иначе заказы будут ссылаться на пустоту
+fn is_ordered(_connection: &PgConnection, item: &Item) -> Result<bool, Error> {
+    let order_types: Vec<i16> = schema::orders::table
+        .filter(schema::orders::object_id.eq(item.id))
+        .select(schema::orders::types)
+        .load::<i16>(_connection)?;
+    if order_types.iter().any(|t| get_order_item_types(*t) == item.types) {
+        return Ok(true);
+    }
+    let in_cart_orders = schema::order_items::table
+        .filter(schema::order_items::item_id.eq(item.id))
+        .select(schema::order_items::id)
+        .first::<i32>(_connection)
+        .optional()?;
+    if in_cart_orders.is_some() {
+        return Ok(true);
+    }
+    let variant_ids = schema::item_variants::table
+        .filter(schema::item_variants::item_id.eq(item.id))
+        .select(schema::item_variants::id)
+        .load::<i32>(_connection)?;
+    if variant_ids.is_empty() {
+        return Ok(false);
+    }
+    let variant_orders = schema::orders::table
+        .filter(schema::orders::variant_id.eq_any(&variant_ids))
+        .select(schema::orders::id)
+        .first::<i32>(_connection)
+        .optional()?;
+    let variant_order_items = schema::order_items::table
+        .filter(schema::order_items::variant_id.eq_any(&variant_ids))
+        .select(schema::order_items::id)
+        .first::<i32>(_connection)
+        .optional()?;
+    Ok(variant_orders.is_some() || variant_order_items.is_some())
+}
+
+// окончательное удаление всего, что лежит в корзине дольше срока.
+// все в одной транзакции, файлы с диска удаляем только после нее.
+// элементы, на которые есть заказы, остаются в корзине (skipped_items)
+pub fn purge_trash(retention_days: i64) -> Result<PurgeReport, Error> {
+    use chrono::Duration;
+
+    let _connection = establish_connection();
+    let border = chrono::Local::now().naive_utc() + Duration::hours(3) - Duration::days(retention_days);
+    let mut src_list = Vec::new();
+
+    let report = _connection.transaction::<_, Error, _>(|| {
+        let mut _items = schema::items::table
+            .filter(schema::items::deleted_at.lt(border))
+            .load::<Item>(&_connection)?;
+        let mut skipped_items = Vec::new();
+        for _item in _items.iter() {
+            if is_ordered(&_connection, _item)? {
+                skipped_items.push(_item.id);
+            }
+        }
+        _items.retain(|i| !skipped_items.contains(&i.id));
+        for _item in _items.iter() {
+            src_list.extend(schema::files::table
+                .filter(schema::files::item_id.eq(_item.id))
+                .filter(schema::files::item_types.eq(_item.types))
+                .select(schema::files::src)
+                .load::<String>(&_connection)?);
+            diesel::delete (
+                schema::files::table
+                    .filter(schema::files::item_id.eq(_item.id))
+                    .filter(schema::files::item_types.eq(_item.types))
+                )
+                .execute(&_connection)?;
+            diesel::delete (
+                schema::tags_items::table
+                    .filter(schema::tags_items::item_id.eq(_item.id))
+                    .filter(schema::tags_items::types.eq(_item.types))
+                )
+                .execute(&_connection)?;
+            diesel::delete (
+                schema::category::table
+                    .filter(schema::category::item_id.eq(_item.id))
+                    .filter(schema::category::types.eq(_item.types))
+                )
+                .execute(&_connection)?;
+            diesel::delete (
+                schema::serve_items::table
+                    .filter(schema::serve_items::item_id.eq(_item.id))
+                    .filter(schema::serve_items::types.eq(_item.types))
+                )
+                .execute(&_connection)?;
+            diesel::delete (
+                schema::tech_categories_items::table
+                    .filter(schema::tech_categories_items::item_id.eq(_item.id))
+                    .filter(schema::tech_categories_items::types.eq(_item.types))
+                )
+                .execute(&_connection)?;
+            diesel::delete (
+                schema::wiki_parents::table
+                    .filter(schema::wiki_parents::item_id.eq(_item.id)
+                        .or(schema::wiki_parents::parent_id.eq(_item.id)))
+                )
+                .execute(&_connection)?;
+            diesel::delete(schema::help_votes::table.filter(schema::help_votes::item_id.eq(_item.id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::item_access::table.filter(schema::item_access::item_id.eq(_item.id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::item_group_access::table.filter(schema::item_group_access::item_id.eq(_item.id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::work_case_techs::table.filter(schema::work_case_techs::item_id.eq(_item.id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::work_cases::table.filter(schema::work_cases::item_id.eq(_item.id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::cart_items::table.filter(schema::cart_items::item_id.eq(_item.id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::item_variants::table.filter(schema::item_variants::item_id.eq(_item.id)))
+                .execute(&_connection)?;
+            diesel::delete(_item).execute(&_connection)?;
+        }
+
+        let _categories = schema::categories::table
+            .filter(schema::categories::deleted_at.lt(border))
+            .load::<Categories>(&_connection)?;
+        for _category in _categories.iter() {
+            diesel::delete (
+                schema::category::table
+                    .filter(schema::category::category_id.eq(_category.id))
+                    .filter(schema::category::types.eq(_category.types))
+                )
+                .execute(&_connection)?;
+            diesel::delete(_category).execute(&_connection)?;
+        }
+
+        let _tags = schema::tags::table
+            .filter(schema::tags::deleted_at.lt(border))
+            .load::<Tag>(&_connection)?;
+        for _tag in _tags.iter() {
+            diesel::delete(schema::tags_items::table.filter(schema::tags_items::tag_id.eq(_tag.id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::tag_aliases::table.filter(schema::tag_aliases::tag_id.eq(_tag.id)))
+                .execute(&_connection)?;
+            diesel::delete(_tag).execute(&_connection)?;
+        }
+
+        let _files = schema::files::table
+            .filter(schema::files::deleted_at.lt(border))
+            .load::<File>(&_connection)?;
+        for _file in _files.iter() {
+            src_list.push(_file.src.clone());
+            diesel::delete(_file).execute(&_connection)?;
+        }
+
+        Ok(PurgeReport {
+            retention_days: retention_days,
+            items:          _items.len(),
+            categories:     _categories.len(),
+            tags:           _tags.len(),
+            files:          _files.len(),
+            skipped_items:  skipped_items,
+        })
+    })?;
+
+    for f in src_list.iter() {
+        std::fs::remove_file(f).ok();
+    }
+    Ok(report)
+}
+
+// консольный вариант: meta purge_trash [дней]
+pub fn trash_command(args: &[String]) -> bool {
+    if args.is_empty() || args[0] != "purge_trash" {
+        return false;
+    }
+    let days = args.get(1)
+        .and_then(|d| d.parse::<i64>().ok())
+        .unwrap_or(get_trash_retention_days());
+    match purge_trash(days) {
+        Ok(report) => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+        Err(e) => eprintln!("purge trash: {}", e),
+    };
+    true
+}
@@ -0,0 +1,148 @@
+// база знаний: оглавление по заголовкам и внутренние ссылки вида
+// [[slug]] или [[slug|текст ссылки]], которые разбираются при выводе статьи.
+use regex::{Regex, Captures};
+use serde::Serialize;
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+    TextExpressionMethods,
+    BoolExpressionMethods,
+};
+use crate::models::{Item, User};
+use crate::utils::{establish_connection, get_linguage_storage};
+
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TocItem {
+    pub level: u8,     // 2 или 3 (h2, h3)
+    pub id:    String, // якорь заголовка
+    pub title: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WikiLink {
+    pub title: String,
+    pub link:  String,
+}
+
+fn get_link_regex() -> Regex {
+    Regex::new(r"\[\[([A-Za-z0-9_\-]+)(?:\|([^\]]+))?\]\]").unwrap()
+}
+
+// slug'и статей, на которые ссылается текст
+pub fn get_wiki_link_slugs(content: &str) -> Vec<String> {
+    let mut slugs = Vec::new();
+    for cap in get_link_regex().captures_iter(content) {
+        let slug = cap[1].to_string();
+        if !slugs.contains(&slug) {
+            slugs.push(slug);
+        }
+    }
+    slugs
+}
+
+// название статьи на языке пользователя
+fn get_wiki_title(item: &Item, l: u8) -> String {
+    if l == 2 { item.title_en.clone() } else { item.title.clone() }
+}
+
+// [[slug]] -> ссылка на статью; несуществующие и закрытые
+// для пользователя статьи - красная ссылка
+pub fn render_wiki_links(content: &str, user: Option<&User>) -> String {
+    let slugs = get_wiki_link_slugs(content);
+    if slugs.is_empty() {
+        return content.to_string();
+    }
+    let _connection = establish_connection();
+    let _items = schema::items::table
+        .filter(schema::items::slug.eq_any(&slugs))
+        .filter(schema::items::types.eq(4))
+        .filter(Item::get_visible_filter(user))
+        .filter(schema::items::deleted_at.is_null())
+        .load::<Item>(&_connection)
+        .expect("E");
+    let l = get_linguage_storage();
+
+    get_link_regex().replace_all(content, |cap: &Captures| {
+        let slug = &cap[1];
+        match _items.iter().find(|i| i.slug == slug) {
+            Some(_item) => {
+                let text = match cap.get(2) {
+                    Some(text) => text.as_str().to_string(),
+                    None => get_wiki_title(_item, l),
+                };
+                "<a class=\"ajax underline\" href=\"".to_string() + &_item.get_link() + &"\">".to_string() + &text + &"</a>".to_string()
+            },
+            None => {
+                let text = match cap.get(2) {
+                    Some(text) => text.as_str().to_string(),
+                    None => slug.to_string(),
+                };
+                "<span class=\"wiki_broken_link\" style=\"color: #d9534f; border-bottom: 1px dashed #d9534f;\" title=\"Статья не найдена: ".to_string() + slug + &"\">".to_string() + &text + &"</span>".to_string()
+            },
+        }
+    }).to_string()
+}
+
+// якоря для заголовков h2, h3 и оглавление по ним
+pub fn get_wiki_toc(content: &str) -> (String, Vec<TocItem>) {
+    let heading = Regex::new(r"(?s)<h([23])([^>]*)>(.*?)</h[23]>").unwrap();
+    let id_attr = Regex::new(r#"id\s*=\s*"([^"]*)""#).unwrap();
+    let tags = Regex::new(r"<[^>]*>").unwrap();
+
+    let mut toc = Vec::new();
+    let mut n = 0;
+    let content = heading.replace_all(content, |cap: &Captures| {
+        n += 1;
+        let level: u8 = cap[1].parse().unwrap_or(2);
+        let attrs = cap[2].to_string();
+        let inner = cap[3].to_string();
+        let title = tags.replace_all(&inner, "").trim().to_string();
+
+        let (id, attrs) = match id_attr.captures(&attrs) {
+            Some(id) => (id[1].to_string(), attrs.clone()),
+            None => {
+                let id = "toc-".to_string() + &n.to_string();
+                (id.clone(), " id=\"".to_string() + &id + &"\"".to_string() + &attrs)
+            },
+        };
+        if !title.is_empty() {
+            toc.push(TocItem {
+                level: level,
+                id:    id,
+                title: title,
+            });
+        }
+        "<h".to_string() + &level.to_string() + &attrs + &">".to_string() + &inner + &"</h".to_string() + &level.to_string() + &">".to_string()
+    }).to_string();
+    (content, toc)
+}
+
+// статьи, в тексте которых (русском или английском) есть ссылка [[slug]] на данную
+pub fn get_wiki_backlinks(item: &Item, user: Option<&User>) -> Vec<WikiLink> {
+    let _connection = establish_connection();
+    let pattern = "%[[".to_string() + &item.slug + &"%".to_string();
+    let _items = schema::items::table
+        .filter(schema::items::types.eq(4))
+        .filter(schema::items::id.ne(item.id))
+        .filter(Item::get_visible_filter(user))
+        .filter(schema::items::deleted_at.is_null())
+        .filter(schema::items::content.like(&pattern).or(schema::items::content_en.like(&pattern)))
+        .load::<Item>(&_connection)
+        .expect("E");
+    let l = get_linguage_storage();
+
+    _items
+        .iter()
+        .filter(|i| {
+            get_wiki_link_slugs(i.content.as_deref().unwrap_or("")).contains(&item.slug)
+            || get_wiki_link_slugs(i.content_en.as_deref().unwrap_or("")).contains(&item.slug)
+        })
+        .map(|i| WikiLink {
+            title: get_wiki_title(i, l),
+            link:  i.get_link(),
+        })
+        .collect()
+}
@@ -0,0 +1,141 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    web::block,
+};
+use crate::utils::{
+    ApiParams,
+    get_api_types,
+    get_api_items,
+    get_api_item,
+    get_api_search,
+    get_api_categories,
+    get_api_tags,
+    get_api_tag_set,
+    get_api_tag_cloud,
+    get_api_trending_tags,
+    get_api_serves,
+    get_api_response,
+    CalculateParams,
+    get_calculation,
+};
+
+
+// JSON API каталога для мобильного приложения и партнеров
+pub fn api_routes(config: &mut web::ServiceConfig) {
+    config.route("/api/v1/items/{types}/", web::get().to(api_items));
+    config.route("/api/v1/items/{types}/{slug}/", web::get().to(api_item));
+    config.route("/api/v1/categories/{types}/", web::get().to(api_categories));
+    config.route("/api/v1/tags/", web::get().to(api_tags));
+    // не под /tags/, чтобы не перекрывать теги со slug cloud и trending
+    config.route("/api/v1/tag_cloud/", web::get().to(api_tag_cloud));
+    config.route("/api/v1/tag_trending/", web::get().to(api_trending_tags));
+    config.route("/api/v1/tags/{slugs}/", web::get().to(api_tag_set));
+    config.route("/api/v1/serves/", web::get().to(api_serves));
+    config.route("/api/v1/search/", web::get().to(api_search));
+    config.route("/api/calculate/", web::get().to(api_calculate));
+}
+
+fn api_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json("Не найдено")
+}
+
+pub async fn api_items(req: HttpRequest, types: web::Path<String>) -> actix_web::Result<HttpResponse> {
+    let types = match get_api_types(&types) {
+        Some(types) => types,
+        None => return Ok(api_not_found()),
+    };
+    let params = ApiParams::get(&req);
+    let list = block(move || get_api_items(types, &params)).await?;
+    Ok(get_api_response(&req, &list))
+}
+
+pub async fn api_item(req: HttpRequest, param: web::Path<(String, String)>) -> actix_web::Result<HttpResponse> {
+    let types = match get_api_types(&param.0) {
+        Some(types) => types,
+        None => return Ok(api_not_found()),
+    };
+    let slug = param.1.clone();
+    let l = ApiParams::get(&req).get_linguage();
+    match block(move || get_api_item(types, &slug, l)).await? {
+        Some(item) => Ok(get_api_response(&req, &item)),
+        None => Ok(api_not_found()),
+    }
+}
+
+pub async fn api_categories(req: HttpRequest, types: web::Path<String>) -> actix_web::Result<HttpResponse> {
+    let types = match get_api_types(&types) {
+        Some(types) => types,
+        None => return Ok(api_not_found()),
+    };
+    let l = ApiParams::get(&req).get_linguage();
+    let list = block(move || get_api_categories(types, l)).await?;
+    Ok(get_api_response(&req, &list))
+}
+
+pub async fn api_tags(req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    let params = ApiParams::get(&req);
+    let list = block(move || get_api_tags(&params)).await?;
+    Ok(get_api_response(&req, &list))
+}
+
+pub async fn api_tag_cloud(req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    let params = ApiParams::get(&req);
+    let list = block(move || get_api_tag_cloud(&params)).await?;
+    Ok(get_api_response(&req, &list))
+}
+
+pub async fn api_trending_tags(req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    let params = ApiParams::get(&req);
+    let list = block(move || get_api_trending_tags(&params)).await?;
+    Ok(get_api_response(&req, &list))
+}
+
+pub async fn api_tag_set(req: HttpRequest, slugs: web::Path<String>) -> actix_web::Result<HttpResponse> {
+    let params = ApiParams::get(&req);
+    let slugs = slugs.into_inner();
+    match block(move || get_api_tag_set(&slugs, &params)).await? {
+        Some(set) => Ok(get_api_response(&req, &set)),
+        None => Ok(api_not_found()),
+    }
+}
+
+pub async fn api_serves(req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    let l = ApiParams::get(&req).get_linguage();
+    let list = block(move || get_api_serves(l)).await?;
+    Ok(get_api_response(&req, &list))
+}
+
+pub async fn api_search(req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    let params = ApiParams::get(&req);
+    if params.q.as_deref().unwrap_or("").trim().is_empty() {
+        return Ok(HttpResponse::BadRequest().json("Укажите q"));
+    }
+    let list = block(move || get_api_search(&params)).await?;
+    Ok(get_api_response(&req, &list))
+}
+
+// калькулятор: /api/calculate/?tech_cat=3&serves=1,2,5&promo=CODE
+// (или tech_cats=3,4 - несколько тех. категорий). Вместе с ценой
+// отдает оценку сроков (timeline)
+pub async fn api_calculate(req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    use crate::models::TechCategories;
+
+    let params = CalculateParams::get(&req);
+    let l = ApiParams::get(&req).get_linguage();
+    let tech_cat_ids = match params.get_tech_cat_ids() {
+        Some(ids) if !ids.is_empty() => ids,
+        _ => return Ok(HttpResponse::BadRequest().json("Укажите tech_cat")),
+    };
+    let serve_ids = match params.get_serve_ids() {
+        Some(ids) => ids,
+        None => return Ok(HttpResponse::BadRequest().json("serves - список id через запятую")),
+    };
+    if tech_cat_ids.iter().any(|id| TechCategories::get_with_id(*id).is_none()) {
+        return Ok(api_not_found());
+    }
+    let types = params.get_types();
+    let calculation = block(move || get_calculation(&tech_cat_ids, &serve_ids, types, params.promo.as_deref(), l)).await?;
+    Ok(HttpResponse::Ok().json(calculation))
+}
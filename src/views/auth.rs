@@ -241,6 +241,7 @@ fn handle_sign_in (
     match result {
         Ok(user) => {
             set_current_user(&session, &user);
+            crate::views::merge_cookie_cart(req, user.id);
             if is_json {
                 Ok(HttpResponse::Ok().json(user))
             } else {
@@ -324,7 +325,7 @@ pub async fn signup_form(payload: &mut Multipart) -> NewUserForm {
     }
     form
 }
-pub async fn process_signup(session: Session, mut payload: Multipart) -> impl Responder {
+pub async fn process_signup(session: Session, req: HttpRequest, mut payload: Multipart) -> impl Responder {
     // Если пользователь не аноним, то отправляем его на страницу новостей
     if is_signed_in(&session) {
         HttpResponse::Ok().content_type("text/html; charset=utf-8").body("")
@@ -339,6 +340,7 @@ pub async fn process_signup(session: Session, mut payload: Multipart) -> impl Re
         };
 
         crate::utils::set_current_user(&session, &_session_user);
+        crate::views::merge_cookie_cart(&req, _session_user.id);
         HttpResponse::Ok().content_type("text/html; charset=utf-8").body("")
     }
 }
@@ -0,0 +1,134 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    error::InternalError,
+    http::StatusCode,
+};
+use crate::utils::{
+    is_signed_in,
+    get_request_user_data,
+    get_first_load_page,
+    get_all_storage,
+    get_device_and_ajax,
+    get_page,
+    get_viewer,
+};
+use crate::models::{
+    User,
+    Item,
+};
+use actix_session::Session;
+use sailfish::TemplateOnce;
+
+
+pub fn author_routes(config: &mut web::ServiceConfig) {
+    config.route("/author/{username}/", web::get().to(author_page));
+}
+
+// публичная страница автора: био, картинка и все его опубликованные элементы
+pub async fn author_page(session: Session, req: HttpRequest, username: web::Path<String>) -> actix_web::Result<HttpResponse> {
+    let _author = User::get_user_with_username(&username);
+    if _author.is_err() {
+        return crate::views::not_found(req, session).await;
+    }
+    let _author = _author.expect("E");
+
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+    if is_ajax == 0 {
+        return get_first_load_page (
+            &session,
+            is_desctop,
+            _author.username.clone() + &" | Автор".to_string(),
+            _author.username.clone() + &" | Автор: вебсервисы.рф".to_string(),
+            "/author/".to_string() + &_author.username + &"/".to_string(),
+            _author.get_image(),
+            t,
+            l,
+        ).await;
+    }
+
+    let page = get_page(&req);
+    let _viewer = get_viewer(&session);
+    let (object_list, next_page_number) = _author.get_author_items_list(page, 20, _viewer.as_ref());
+    let items_count = _author.get_author_items_count(_viewer.as_ref());
+
+    if is_signed_in(&session) && is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/author.stpl")]
+        struct Template {
+            request_user:     User,
+            author:           User,
+            object_list:      Vec<Item>,
+            items_count:      i64,
+            next_page_number: i32,
+            is_ajax:          i32,
+            template_types:   u8,
+            linguage:         u8,
+        }
+        let body = Template {
+            request_user:     get_request_user_data(&session),
+            author:           _author,
+            object_list:      object_list,
+            items_count:      items_count,
+            next_page_number: next_page_number,
+            is_ajax:          is_ajax,
+            template_types:   t,
+            linguage:         l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/anon_author.stpl")]
+        struct Template {
+            author:           User,
+            object_list:      Vec<Item>,
+            items_count:      i64,
+            next_page_number: i32,
+            is_ajax:          i32,
+            template_types:   u8,
+            linguage:         u8,
+        }
+        let body = Template {
+            author:           _author,
+            object_list:      object_list,
+            items_count:      items_count,
+            next_page_number: next_page_number,
+            is_ajax:          is_ajax,
+            template_types:   t,
+            linguage:         l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/author.stpl")]
+        struct Template {
+            author:           User,
+            object_list:      Vec<Item>,
+            items_count:      i64,
+            next_page_number: i32,
+            is_ajax:          i32,
+            template_types:   u8,
+            linguage:         u8,
+        }
+        let body = Template {
+            author:           _author,
+            object_list:      object_list,
+            items_count:      items_count,
+            next_page_number: next_page_number,
+            is_ajax:          is_ajax,
+            template_types:   t,
+            linguage:         l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+}
@@ -13,6 +13,8 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    get_list_filter,
+    ListFilter,
 };
 use actix_session::Session;
 use crate::schema;
@@ -49,14 +51,17 @@ pub async fn get_blog_page(session: Session, req: HttpRequest, param: web::Path<
 
     let _item = schema::items::table
         .filter(schema::items::slug.eq(&_item_id))
+        .filter(schema::items::deleted_at.is_null())
         .first::<Item>(&_connection)
         .expect("E");
+    let _key = crate::utils::get_share_key(&req);
+    let title = if _item.is_public() { _item.title.clone() } else { "Закрытая страница".to_string() };
     if is_ajax == 0 {
         get_first_load_page (
             &session,
             is_desctop,
-            _item.title.clone() + &" | Статья ".to_string(),
-            _item.title.clone() + &" | Статья: вебсервисы.рф".to_string(),
+            title.clone() + &" | Статья ".to_string(),
+            title.clone() + &" | Статья: вебсервисы.рф".to_string(),
             "/blog/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
             _item.get_image(),
             t, 
@@ -77,13 +82,13 @@ pub async fn get_blog_page(session: Session, req: HttpRequest, param: web::Path<
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            if !_item.is_active && _request_user.perm < 10 {
+            if !_item.is_visible(Some(&_request_user), _key.as_deref()) {
                 crate::utils::get_private_page (
                     is_ajax,
                     _request_user,
                     is_desctop,
-                    _item.title.clone() + &" | Статья ".to_string(),
-                    _item.title.clone() + &" | Статья: вебсервисы.рф".to_string(),
+                    title.clone() + &" | Статья ".to_string(),
+                    title.clone() + &" | Статья: вебсервисы.рф".to_string(),
                     "/service/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
                     _item.get_image(),
                     t, 
@@ -154,12 +159,12 @@ pub async fn get_blog_page(session: Session, req: HttpRequest, param: web::Path<
             }
         }
         else {
-            if !_item.is_active {
+            if !_item.is_visible(None, _key.as_deref()) {
                 crate::utils::get_anon_private_page (
                     is_ajax,
                     is_desctop,
-                    _item.title.clone() + &" | Статья ".to_string(),
-                    _item.title.clone() + &" | Статья: вебсервисы.рф".to_string(),
+                    title.clone() + &" | Статья ".to_string(),
+                    title.clone() + &" | Статья: вебсервисы.рф".to_string(),
                     "/blog/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
                     _item.get_image(),
                     t, 
@@ -236,6 +241,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
     let _category = schema::categories::table
         .filter(schema::categories::slug.eq(&_cat_id))
         .filter(schema::categories::types.eq(1))
+        .filter(schema::categories::deleted_at.is_null())
         .select((
             schema::categories::name,
             schema::categories::slug,
@@ -273,6 +279,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
         use crate::models::Blog;
 
         let page = crate::utils::get_page(&req);
+        let filter = get_list_filter(&req);
         let _cats = block(move || Categories::get_categories_for_types(1, l)).await?;
         let _tags = block(move || Categories::get_tags(1, l)).await?;
         let object_list: Vec<Blog>;
@@ -280,7 +287,8 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let _res = block(move || Categories::get_blogs_list(_category.id, page, 20, _request_user.perm == 60, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_blogs_list(_category.id, page, 20, _request_user.perm == 60, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -295,6 +303,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Blog>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -306,6 +315,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -323,6 +333,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Blog>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -333,6 +344,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -344,7 +356,8 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
             }
         }
         else {
-            let _res = block(move || Categories::get_blogs_list(_category.id, page, 20, false, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_blogs_list(_category.id, page, 20, false, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -358,6 +371,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Blog>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -368,6 +382,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -385,6 +400,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Blog>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -395,6 +411,7 @@ pub async fn blog_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
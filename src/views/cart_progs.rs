@@ -0,0 +1,296 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    error::InternalError,
+    http::StatusCode,
+};
+use std::borrow::BorrowMut;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+    Connection,
+};
+use crate::utils::{
+    establish_connection,
+    is_signed_in,
+    get_request_user_data,
+    get_first_load_page,
+    get_or_create_cookie_user_id,
+    get_cookie_user_id,
+    get_all_storage,
+    get_device_and_ajax,
+    PriceFormat,
+};
+use crate::schema;
+use crate::models::{
+    User,
+    Order,
+    NewOrder,
+    NewOrderItem,
+    CartItem,
+    CartLine,
+    CartSummary,
+    Discount,
+    PromoCode,
+};
+use actix_session::Session;
+use actix_multipart::Multipart;
+use sailfish::TemplateOnce;
+use actix_web::dev::ConnectionInfo;
+use serde::Serialize;
+
+
+pub fn cart_routes(config: &mut web::ServiceConfig) {
+    config.route("/cart/", web::get().to(cart_page));
+    config.route("/add_to_cart/", web::post().to(add_to_cart));
+    config.route("/change_cart_count/{id}/", web::post().to(change_cart_count));
+    config.route("/remove_from_cart/{id}/", web::get().to(remove_from_cart));
+    config.route("/checkout/", web::post().to(checkout));
+}
+
+// владелец корзины: аккаунт, если пользователь вошел, иначе пользователь куки.
+fn get_cart_owner(session: &Session, cookie_user_id: i32) -> (i32, i16) {
+    if is_signed_in(session) {
+        let _request_user = get_request_user_data(session);
+        return (_request_user.id, 2);
+    }
+    (cookie_user_id, 1)
+}
+// при входе и регистрации корзина пользователя куки переносится в аккаунт
+pub fn merge_cookie_cart(req: &HttpRequest, user_id: i32) -> () {
+    let cookie_user_id = get_request_cookie_user_id(req);
+    if cookie_user_id != 0 {
+        CartItem::merge_owner(cookie_user_id, 1, user_id, 2);
+    }
+}
+fn get_request_cookie_user_id(req: &HttpRequest) -> i32 {
+    if req.headers().get("cookie").is_none() {
+        return 0;
+    }
+    get_cookie_user_id(req)
+}
+
+pub async fn cart_page(session: Session, req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+    if is_ajax == 0 {
+        return get_first_load_page (
+            &session,
+            is_desctop,
+            "Корзина заказа".to_string(),
+            "вебсервисы.рф: Корзина заказа".to_string(),
+            "/cart/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await;
+    }
+
+    let (owner_id, owner_types) = get_cart_owner(&session, get_request_cookie_user_id(&req));
+    let (lines, summary) = CartItem::get_cart(owner_id, owner_types);
+
+    if is_signed_in(&session) && is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/cart.stpl")]
+        struct Template {
+            request_user:   User,
+            object_list:    Vec<CartLine>,
+            summary:        CartSummary,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+            prices:         PriceFormat,
+        }
+        let body = Template {
+            request_user:   get_request_user_data(&session),
+            object_list:    lines,
+            summary:        summary,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+            prices:         PriceFormat::get(&req, l),
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/anon_cart.stpl")]
+        struct Template {
+            object_list:    Vec<CartLine>,
+            summary:        CartSummary,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+            prices:         PriceFormat,
+        }
+        let body = Template {
+            object_list:    lines,
+            summary:        summary,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+            prices:         PriceFormat::get(&req, l),
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/cart.stpl")]
+        struct Template {
+            object_list:    Vec<CartLine>,
+            summary:        CartSummary,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+            prices:         PriceFormat,
+        }
+        let body = Template {
+            object_list:    lines,
+            summary:        summary,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+            prices:         PriceFormat::get(&req, l),
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+}
+
+// поля формы: item_id, variant_id (необязательно), count
+pub async fn add_to_cart(session: Session, conn: ConnectionInfo, req: HttpRequest, mut payload: Multipart) -> actix_web::Result<HttpResponse> {
+    let cookie_user_id = get_or_create_cookie_user_id(conn, &req).await;
+    let (owner_id, owner_types) = get_cart_owner(&session, cookie_user_id);
+    let form = crate::utils::cart_form(payload.borrow_mut()).await;
+    if owner_id == 0 {
+        return Ok(HttpResponse::BadRequest().json("Товар недоступен"));
+    }
+    if let Err(error) = CartItem::add(owner_id, owner_types, form.item_id, form.variant_id, form.count) {
+        return Ok(HttpResponse::BadRequest().json(error));
+    }
+    let (_, summary) = CartItem::get_cart(owner_id, owner_types);
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+fn get_owner_cart_item(owner_id: i32, owner_types: i16, id: i32) -> Option<CartItem> {
+    let _connection = establish_connection();
+    return schema::cart_items::table
+        .filter(schema::cart_items::id.eq(id))
+        .filter(schema::cart_items::owner_id.eq(owner_id))
+        .filter(schema::cart_items::owner_types.eq(owner_types))
+        .first::<CartItem>(&_connection)
+        .ok();
+}
+
+pub async fn change_cart_count(session: Session, req: HttpRequest, mut payload: Multipart, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+    let (owner_id, owner_types) = get_cart_owner(&session, get_request_cookie_user_id(&req));
+    let form = crate::utils::cart_form(payload.borrow_mut()).await;
+    if let Some(_cart_item) = get_owner_cart_item(owner_id, owner_types, *_id) {
+        if let Err(error) = _cart_item.set_count(form.count) {
+            return Ok(HttpResponse::BadRequest().json(error));
+        }
+    }
+    let (_, summary) = CartItem::get_cart(owner_id, owner_types);
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+pub async fn remove_from_cart(session: Session, req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+    let (owner_id, owner_types) = get_cart_owner(&session, get_request_cookie_user_id(&req));
+    if let Some(_cart_item) = get_owner_cart_item(owner_id, owner_types, *_id) {
+        _cart_item.change_count(0);
+    }
+    let (_, summary) = CartItem::get_cart(owner_id, owner_types);
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+#[derive(Serialize)]
+pub struct CheckoutResponse {
+    pub order_id: i32,
+}
+
+// оформление: один заказ, позиции корзины - в order_items.
+// заказ привязан к пользователю куки, как и обычные заказы.
+pub async fn checkout(session: Session, conn: ConnectionInfo, req: HttpRequest, mut payload: Multipart) -> actix_web::Result<HttpResponse> {
+    let cookie_user_id = get_or_create_cookie_user_id(conn, &req).await;
+    let (owner_id, owner_types) = get_cart_owner(&session, cookie_user_id);
+    let form = crate::utils::checkout_form(payload.borrow_mut()).await;
+    if form.username.trim().is_empty() || form.email.trim().is_empty() {
+        return Ok(HttpResponse::BadRequest().json("Укажите имя и почту"));
+    }
+
+    let (lines, summary) = CartItem::get_cart(owner_id, owner_types);
+    if lines.is_empty() {
+        return Ok(HttpResponse::BadRequest().json("Корзина пуста"));
+    }
+    if let Some(line) = lines.iter().find(|l| !l.is_available) {
+        return Ok(HttpResponse::BadRequest().json("Нет в наличии: ".to_string() + &line.title));
+    }
+
+    let discount = Discount::get(summary.price, 3, form.promo.as_deref());
+    let _connection = establish_connection();
+    let mut is_code_used_up = false;
+    let _order = _connection.transaction::<_, diesel::result::Error, _>(|| {
+        let title = "Заказ из корзины (".to_string() + &summary.count.to_string() + &" шт.)".to_string();
+        let new_order = NewOrder::create (
+            title.clone(),
+            title,
+            2,
+            0,
+            form.username.clone(),
+            form.email.clone(),
+            form.description.clone(),
+            form.description.clone(),
+            cookie_user_id,
+            None,
+        );
+        let _order = diesel::insert_into(schema::orders::table)
+            .values(&new_order)
+            .get_result::<Order>(&_connection)?;
+
+        for line in lines.iter() {
+            let new_item = NewOrderItem {
+                order_id:   _order.id,
+                item_id:    line.item_id,
+                variant_id: line.variant_id,
+                title:      line.title.clone(),
+                count:      line.count,
+                price:      line.price,
+            };
+            diesel::insert_into(schema::order_items::table)
+                .values(&new_item)
+                .execute(&_connection)?;
+        }
+        diesel::update(&_order)
+            .set((
+                schema::orders::price.eq(summary.price),
+                schema::orders::price_acc.eq(discount.amount),
+                schema::orders::discount_rule_id.eq(discount.rule_id),
+                schema::orders::promo_code_id.eq(discount.promo_code_id),
+            ))
+            .execute(&_connection)?;
+        // промокод списываем в той же транзакции: лимит уже выбран - заказ не оформляем
+        if let Some(code_id) = discount.promo_code_id {
+            if !PromoCode::use_code(&_connection, code_id)? {
+                is_code_used_up = true;
+                return Err(diesel::result::Error::RollbackTransaction);
+            }
+        }
+        Ok(_order)
+    });
+
+    match _order {
+        Ok(_order) => {
+            CartItem::clear(owner_id, owner_types);
+            Ok(HttpResponse::Ok().json(CheckoutResponse { order_id: _order.id }))
+        },
+        Err(_) if is_code_used_up => Ok(HttpResponse::BadRequest().json("Промокод больше не действует")),
+        Err(_) => Ok(HttpResponse::InternalServerError().json("Не удалось оформить заказ")),
+    }
+}
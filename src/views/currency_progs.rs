@@ -0,0 +1,143 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    error::InternalError,
+    http::StatusCode,
+    Responder,
+};
+use actix_multipart::Multipart;
+use std::borrow::BorrowMut;
+use actix_session::Session;
+use crate::utils::{
+    is_signed_in,
+    get_request_user_data,
+    get_first_load_page,
+    get_all_storage,
+    get_device_and_ajax,
+    currency_form,
+};
+use crate::models::{
+    User,
+    Currency,
+};
+use sailfish::TemplateOnce;
+
+
+pub fn currency_routes(config: &mut web::ServiceConfig) {
+    config.route("/currencies/", web::get().to(currencies_page));
+    config.route("/create_currency/", web::post().to(create_currency));
+    config.route("/edit_currency/{id}/", web::post().to(edit_currency));
+    config.route("/set_currency/{code}/", web::get().to(set_currency_code));
+}
+
+fn is_admin(session: &Session) -> bool {
+    is_signed_in(session) && get_request_user_data(session).perm == 60
+}
+
+// валюты и курсы (только админ)
+pub async fn currencies_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+    if is_ajax == 0 {
+        return get_first_load_page (
+            &session,
+            is_desctop,
+            "Валюты".to_string(),
+            "вебсервисы.рф: Валюты".to_string(),
+            "/currencies/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await;
+    }
+    if !is_admin(&session) {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+
+    let currencies_list = Currency::get_all();
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/currencies.stpl")]
+        struct Template {
+            request_user:    User,
+            currencies_list: Vec<Currency>,
+            is_ajax:         i32,
+            template_types:  u8,
+            linguage:        u8,
+        }
+        let body = Template {
+            request_user:    get_request_user_data(&session),
+            currencies_list: currencies_list,
+            is_ajax:         is_ajax,
+            template_types:  t,
+            linguage:        l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/currencies.stpl")]
+        struct Template {
+            currencies_list: Vec<Currency>,
+            is_ajax:         i32,
+            template_types:  u8,
+            linguage:        u8,
+        }
+        let body = Template {
+            currencies_list: currencies_list,
+            is_ajax:         is_ajax,
+            template_types:  t,
+            linguage:        l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+}
+
+pub async fn create_currency(session: Session, mut payload: Multipart) -> impl Responder {
+    if is_admin(&session) {
+        let form = currency_form(payload.borrow_mut()).await;
+        if form.code.len() != 3 || form.symbol.is_empty() || form.rate <= 0.0 {
+            return HttpResponse::Ok().body("Укажите код из 3 букв, символ и курс больше нуля");
+        }
+        if Currency::create(form).is_none() {
+            return HttpResponse::Ok().body("Такая валюта уже есть");
+        }
+        return HttpResponse::Ok().body("ok");
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn edit_currency(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_admin(&session) {
+        if let Some(_currency) = Currency::get_with_id(*_id) {
+            let form = currency_form(payload.borrow_mut()).await;
+            if (!_currency.is_base && (form.code.len() != 3 || form.rate <= 0.0)) || form.symbol.is_empty() {
+                return HttpResponse::Ok().body("Укажите код из 3 букв, символ и курс больше нуля");
+            }
+            if !_currency.edit(form) {
+                return HttpResponse::Ok().body("Такая валюта уже есть");
+            }
+            return HttpResponse::Ok().body("ok");
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+// выбор валюты посетителем - в его куки на год
+pub async fn set_currency_code(code: web::Path<String>) -> impl Responder {
+    use actix_web::cookie::{Cookie, time::Duration};
+
+    if let Some(_currency) = Currency::get_with_code(&code) {
+        let cookie = Cookie::build("currency", _currency.code)
+            .path("/")
+            .max_age(Duration::days(365))
+            .finish();
+        return HttpResponse::Ok().cookie(cookie).body("ok");
+    }
+    HttpResponse::Ok().body("Валюта не найдена")
+}
@@ -0,0 +1,194 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    error::InternalError,
+    http::StatusCode,
+    Responder,
+};
+use actix_multipart::Multipart;
+use std::borrow::BorrowMut;
+use actix_session::Session;
+use crate::utils::{
+    is_signed_in,
+    get_request_user_data,
+    get_first_load_page,
+    get_all_storage,
+    get_device_and_ajax,
+    discount_rule_form,
+    promo_code_form,
+};
+use crate::models::{
+    User,
+    DiscountRule,
+    PromoCode,
+};
+use sailfish::TemplateOnce;
+
+
+pub fn discount_routes(config: &mut web::ServiceConfig) {
+    config.route("/discounts/", web::get().to(discounts_page));
+    config.route("/create_discount_rule/", web::post().to(create_discount_rule));
+    config.route("/edit_discount_rule/{id}/", web::post().to(edit_discount_rule));
+    config.route("/disable_discount_rule/{id}/", web::get().to(disable_discount_rule));
+    config.route("/create_promo_code/", web::post().to(create_promo_code));
+    config.route("/edit_promo_code/{id}/", web::post().to(edit_promo_code));
+    config.route("/disable_promo_code/{id}/", web::get().to(disable_promo_code));
+}
+
+fn is_admin(session: &Session) -> bool {
+    is_signed_in(session) && get_request_user_data(session).perm == 60
+}
+
+// правила скидок и промокоды (только админ)
+pub async fn discounts_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+    if is_ajax == 0 {
+        return get_first_load_page (
+            &session,
+            is_desctop,
+            "Скидки и промокоды".to_string(),
+            "вебсервисы.рф: Скидки и промокоды".to_string(),
+            "/discounts/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await;
+    }
+    if !is_admin(&session) {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+
+    let rules_list = DiscountRule::get_all();
+    let codes_list = PromoCode::get_all();
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/discounts.stpl")]
+        struct Template {
+            request_user:   User,
+            rules_list:     Vec<DiscountRule>,
+            codes_list:     Vec<PromoCode>,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+        }
+        let body = Template {
+            request_user:   get_request_user_data(&session),
+            rules_list:     rules_list,
+            codes_list:     codes_list,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/discounts.stpl")]
+        struct Template {
+            rules_list:     Vec<DiscountRule>,
+            codes_list:     Vec<PromoCode>,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+        }
+        let body = Template {
+            rules_list:     rules_list,
+            codes_list:     codes_list,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+}
+
+// price_acc элементов хранится, поэтому после правки правил пересчитываем
+fn refresh_discounts() -> () {
+    let changed = crate::utils::refresh_price_acc();
+    info!("refresh price_acc: {} rows", changed);
+}
+
+pub async fn create_discount_rule(session: Session, mut payload: Multipart) -> impl Responder {
+    if is_admin(&session) {
+        let form = discount_rule_form(payload.borrow_mut()).await;
+        if form.percent < 1 || form.percent > 100 {
+            return HttpResponse::Ok().body("Скидка - от 1 до 100%");
+        }
+        DiscountRule::create(form);
+        refresh_discounts();
+        return HttpResponse::Ok().body("ok");
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn edit_discount_rule(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_admin(&session) {
+        if let Some(_rule) = DiscountRule::get_with_id(*_id) {
+            let form = discount_rule_form(payload.borrow_mut()).await;
+            if form.percent < 1 || form.percent > 100 {
+                return HttpResponse::Ok().body("Скидка - от 1 до 100%");
+            }
+            _rule.edit(form);
+            refresh_discounts();
+            return HttpResponse::Ok().body("ok");
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn disable_discount_rule(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_admin(&session) {
+        if let Some(_rule) = DiscountRule::get_with_id(*_id) {
+            _rule.disable();
+            refresh_discounts();
+            return HttpResponse::Ok().body("ok");
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn create_promo_code(session: Session, mut payload: Multipart) -> impl Responder {
+    if is_admin(&session) {
+        let form = promo_code_form(payload.borrow_mut()).await;
+        if form.code.is_empty() || form.percent < 1 || form.percent > 100 {
+            return HttpResponse::Ok().body("Укажите код и скидку от 1 до 100%");
+        }
+        if PromoCode::create(form).is_none() {
+            return HttpResponse::Ok().body("Такой промокод уже есть");
+        }
+        return HttpResponse::Ok().body("ok");
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn edit_promo_code(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_admin(&session) {
+        if let Some(_code) = PromoCode::get_with_id(*_id) {
+            let form = promo_code_form(payload.borrow_mut()).await;
+            if form.code.is_empty() || form.percent < 1 || form.percent > 100 {
+                return HttpResponse::Ok().body("Укажите код и скидку от 1 до 100%");
+            }
+            if !_code.edit(form) {
+                return HttpResponse::Ok().body("Такой промокод уже есть");
+            }
+            return HttpResponse::Ok().body("ok");
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn disable_promo_code(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_admin(&session) {
+        if let Some(_code) = PromoCode::get_with_id(*_id) {
+            _code.disable();
+            return HttpResponse::Ok().body("ok");
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
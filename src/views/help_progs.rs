@@ -13,8 +13,13 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    get_device_and_ajax,
+    get_or_create_cookie_user_id,
 };
 use actix_session::Session;
+use actix_web::dev::ConnectionInfo;
+use std::borrow::BorrowMut;
+use actix_multipart::Multipart;
 use crate::schema;
 use crate::diesel::{
     RunQueryDsl,
@@ -27,6 +32,8 @@ use crate::models::{
     Cat,
     SmallTag,
     CatDetail,
+    HelpVote,
+    HelpReport,
 };
 use sailfish::TemplateOnce;
 
@@ -36,18 +43,19 @@ pub fn help_routes(config: &mut web::ServiceConfig) {
         web::scope("/helps")
             .service(web::resource("{slug}/").route(web::get().to(help_category_page)))
     ); 
+    config.route("/help_vote/{id}/", web::post().to(help_vote));
+    config.route("/help_report/", web::get().to(help_report_page));
 }
 
 
 pub async fn help_category_page(session: Session, req: HttpRequest, _id: web::Path<String>) -> actix_web::Result<HttpResponse> {
-    use crate::utils::get_device_and_ajax;
-
     let _connection = establish_connection();
     let (t, l) = get_all_storage();
 
     let _category = schema::categories::table
         .filter(schema::categories::slug.eq(_id.clone()))
         .filter(schema::categories::types.eq(6))
+        .filter(schema::categories::deleted_at.is_null())
         .select((
             schema::categories::name,
             schema::categories::slug,
@@ -87,6 +95,7 @@ pub async fn help_category_page(session: Session, req: HttpRequest, _id: web::Pa
         use crate::models::Help;
 
         let page = get_page(&req);
+        let _key = crate::utils::get_share_key(&req);
         let object_list: Vec<Help>;
         let next_page_number: i32;
         let _cats = block(move || Categories::get_categories_for_types(6, l)).await?;
@@ -94,7 +103,8 @@ pub async fn help_category_page(session: Session, req: HttpRequest, _id: web::Pa
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let _res = block(move || Categories::get_helps_list(_category.id, page, 20, _request_user.perm == 60, l)).await?;
+            let _user = get_request_user_data(&session);
+            let _res = block(move || Categories::get_helps_list(_category.id, page, 20, Some(_user), _key, l)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -159,7 +169,7 @@ pub async fn help_category_page(session: Session, req: HttpRequest, _id: web::Pa
             }
         }
         else {
-            let _res = block(move || Categories::get_helps_list(_category.id, page, 20, false, l)).await?;
+            let _res = block(move || Categories::get_helps_list(_category.id, page, 20, None, _key, l)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -222,3 +232,80 @@ pub async fn help_category_page(session: Session, req: HttpRequest, _id: web::Pa
         }
     }
 }
+
+// "Было полезно?": id - статья помощи, оценка от пользователя куки
+pub async fn help_vote(conn: ConnectionInfo, req: HttpRequest, mut payload: Multipart, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+    let user_id = get_or_create_cookie_user_id(conn, &req).await;
+    let form = crate::utils::help_vote_form(payload.borrow_mut()).await;
+    if user_id == 0 || !HelpVote::vote(*_id, user_id, form) {
+        return Ok(HttpResponse::BadRequest().json("Статья не найдена"));
+    }
+    Ok(HttpResponse::Ok().json(HelpVote::get_score(*_id)))
+}
+
+pub async fn help_report_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+    if is_ajax == 0 {
+        get_first_load_page (
+            &session,
+            is_desctop,
+            "Полезность статей помощи".to_string(),
+            "вебсервисы.рф: Полезность статей помощи".to_string(),
+            "/help_report/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await
+    }
+    else if !is_signed_in(&session) {
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
+    }
+    else {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm < 60 {
+            return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+        }
+        let object_list = block(move || HelpVote::get_report()).await?;
+        if is_desctop {
+            #[derive(TemplateOnce)]
+            #[template(path = "desctop/pages/help_report.stpl")]
+            struct Template {
+                request_user:   User,
+                is_ajax:        i32,
+                object_list:    Vec<HelpReport>,
+                template_types: u8,
+                linguage:       u8,
+            }
+            let body = Template {
+                request_user:   _request_user,
+                is_ajax:        is_ajax,
+                object_list:    object_list,
+                template_types: t,
+                linguage:       l,
+            }
+            .render_once()
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+        else {
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/pages/help_report.stpl")]
+            struct Template {
+                is_ajax:        i32,
+                object_list:    Vec<HelpReport>,
+                template_types: u8,
+                linguage:       u8,
+            }
+            let body = Template {
+                is_ajax:        is_ajax,
+                object_list:    object_list,
+                template_types: t,
+                linguage:       l,
+            }
+            .render_once()
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+    }
+}
@@ -11,6 +11,15 @@ pub mod auth;
 pub mod tag_progs;
 pub mod search_progs;
 pub mod help_progs;
+pub mod transfer_progs;
+pub mod trash_progs;
+pub mod cart_progs;
+pub mod author_progs;
+pub mod api_progs;
+pub mod discount_progs;
+pub mod currency_progs;
+pub mod user_group_progs;
+pub mod timeline_progs;
 
 pub use self::{
     work_progs::*,
@@ -25,5 +34,14 @@ pub use self::{
     tag_progs::*,
     search_progs::*,
     help_progs::*,
+    transfer_progs::*,
+    trash_progs::*,
+    cart_progs::*,
+    author_progs::*,
+    api_progs::*,
+    discount_progs::*,
+    currency_progs::*,
+    user_group_progs::*,
+    timeline_progs::*,
     auth::*,
 };
@@ -2,7 +2,7 @@ use actix_web::{
     HttpRequest,
     HttpResponse,
     web,
-    //web::block,
+    web::block,
     error::InternalError,
     http::StatusCode,
     Responder,
@@ -12,6 +12,7 @@ use crate::diesel::{
     RunQueryDsl,
     ExpressionMethods,
     QueryDsl,
+    Connection,
 };
 use crate::utils::{
     establish_connection,
@@ -21,6 +22,7 @@ use crate::utils::{
     get_or_create_cookie_user_id,
     get_cookie_user_id,
     get_all_storage,
+    PriceFormat,
 };
 use crate::schema;
 use crate::models::{
@@ -40,6 +42,8 @@ pub fn order_routes(config: &mut web::ServiceConfig) {
     config.route("/orders/", web::get().to(get_orders_page));
     config.route("/user_orders/", web::get().to(get_user_orders_page));
     config.route("/order/{id}/", web::get().to(get_order_page));
+    config.route("/order/{id}/proposal/", web::get().to(get_order_proposal));
+    config.route("/order/{id}/proposal.pdf", web::get().to(get_order_proposal_pdf));
     config.service(web::resource("/create_order/")
         .route(web::get().to(create_order_page))
         .route(web::post().to(create_order))
@@ -49,6 +53,7 @@ pub fn order_routes(config: &mut web::ServiceConfig) {
     //    .route(web::post().to(edit_order))
     //);
     config.route("/delete_order/{id}/", web::get().to(delete_order));
+    config.route("/accept_order/{id}/", web::get().to(accept_order));
 }
 
 pub async fn get_orders_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
@@ -294,6 +299,7 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     request_user:   _request_user,
@@ -302,6 +308,7 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -316,6 +323,7 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     object:         _order,
@@ -323,6 +331,7 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -339,6 +348,7 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     object:         _order,
@@ -346,6 +356,7 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -360,6 +371,7 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     object:         _order,
@@ -367,6 +379,7 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -376,6 +389,63 @@ pub async fn get_order_page(session: Session, req: HttpRequest, _id: web::Path<i
     }
 }
 
+// коммерческое предложение видят заказчик и админ
+fn get_proposal_html(session: &Session, req: &HttpRequest, id: i32) -> Option<(String, String)> {
+    use crate::utils::{get_proposal, Proposal};
+
+    let _connection = establish_connection();
+    let _order = schema::orders::table
+        .filter(schema::orders::id.eq(id))
+        .first::<Order>(&_connection)
+        .ok()?;
+    let is_admin = is_signed_in(session) && get_request_user_data(session).perm == 60;
+    if !is_admin && get_cookie_user_id(req) != _order.user_id {
+        return None;
+    }
+
+    #[derive(TemplateOnce)]
+    #[template(path = "block/proposal.stpl")]
+    struct Template {
+        proposal: Proposal,
+        linguage: u8,
+        prices:   PriceFormat,
+    }
+    let (_, l) = get_all_storage();
+    let proposal = get_proposal(_order, l);
+    let file_name = proposal.get_file_name();
+    let body = Template {
+        proposal: proposal,
+        linguage: l,
+        prices:   PriceFormat::get(&req, l),
+    }
+    .render_once()
+    .ok()?;
+    Some((file_name, body))
+}
+
+pub async fn get_order_proposal(session: Session, req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+    match get_proposal_html(&session, &req, *_id) {
+        Some((_, body)) => Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body)),
+        None => Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Информация о заказчике не найдена")),
+    }
+}
+
+pub async fn get_order_proposal_pdf(session: Session, req: HttpRequest, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+    use crate::utils::get_proposal_pdf;
+
+    let (file_name, body) = match get_proposal_html(&session, &req, *_id) {
+        Some(res) => res,
+        None => return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Информация о заказчике не найдена")),
+    };
+    match block(move || get_proposal_pdf(&body)).await? {
+        Some(pdf) => Ok(HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", file_name)))
+            .body(pdf)),
+        None => Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("PDF сейчас недоступен, откройте HTML-версию предложения")),
+    }
+}
+
 pub async fn create_order_page() -> actix_web::Result<HttpResponse> {
     let (t, l) = get_all_storage();
     #[derive(TemplateOnce)]
@@ -397,104 +467,174 @@ pub async fn create_order(conn: ConnectionInfo, req: HttpRequest, mut payload: M
     use crate::schema::serve::dsl::serve;
     use crate::models::{
         NewTechCategoriesItem,
-        Serve,
         NewServeItems,
+        NewOrderServe,
+        Serve,
+        ItemVariant,
+        get_order_item_types,
+        Discount,
+        PromoCode,
     };
     use crate::utils::{
         order_form,
-        get_price_acc_values,
+        get_calculation,
+        get_linguage_storage,
     };
 
     let _connection = establish_connection();
     let user_id = get_or_create_cookie_user_id(conn, &req).await;
+    // что калькулятор поменял в выбранных опциях - покажем клиенту
+    let mut notes = Vec::new();
 
     if user_id != 0 {
         let form = order_form(payload.borrow_mut(), user_id).await;
-        let new_order = NewOrder::create (
-            form.title.clone(),
-            form.title.clone(),
-            form.types,
-            form.object_id,
-            form.username.clone(),
-            form.email.clone(),
-            form.description.clone(),
-            form.description,
-            user_id,
-        );
-
-        let _order = diesel::insert_into(schema::orders::table)
-            .values(&new_order)
-            .get_result::<Order>(&_connection)
-            .expect("E.");
-
-        for file in form.files.iter() {
-            let new_file = NewOrderFile::create (
-                _order.id,
-                file.to_string()
-            );
-            diesel::insert_into(schema::order_files::table)
-                .values(&new_file)
-                .execute(&_connection)
-                .expect("E.");
-        };
+        // form.types - тип заказа, калькулятору и скидкам нужен тип элемента
+        let item_types = get_order_item_types(form.types);
 
-        // создаем опции услуги и записываем id опций в вектор.
-        let mut serve_ids = Vec::new();
-        for serve_id in form.serve_list.into_iter() {
-            let new_serve_form = NewServeItems {
-                serve_id: serve_id,
-                item_id:  form.object_id,
-                types:    form.types,
-            };
-            diesel::insert_into(schema::serve_items::table)
-                .values(&new_serve_form)
-                .execute(&_connection)
-                .expect("Error.");
-            serve_ids.push(serve_id);
+        // вариант товара должен относиться к заказываемому товару и быть в наличии
+        let mut variant_price = 0;
+        if form.variant_id.is_some() {
+            let _variant = schema::item_variants::table
+                .filter(schema::item_variants::id.eq(form.variant_id.unwrap()))
+                .filter(schema::item_variants::item_id.eq(form.object_id))
+                .first::<ItemVariant>(&_connection);
+            match _variant {
+                Ok(_variant) if _variant.is_available() => variant_price = _variant.price,
+                _ => return HttpResponse::Ok().finish(),
+            }
         }
 
-        // получаем опции, чтобы создать связи с их тех. категорией.
-        // это надо отрисовки тех категорий услуги, которые активны
+        // опции считает тот же калькулятор, что и /api/calculate/:
+        // обязательные добавятся, из вариантов одной опции останется один.
+        let tech_cat_ids = serve
+            .filter(schema::serve::id.eq_any(&form.serve_list))
+            .select(schema::serve::tech_cat_id)
+            .load::<i32>(&_connection)
+            .expect("E");
+        let calculation = get_calculation(&tech_cat_ids, &form.serve_list, item_types, None, get_linguage_storage());
+        if let Some(conflict) = calculation.notes.iter().find(|n| n.action == "conflict") {
+            return HttpResponse::BadRequest().body(conflict.text.clone());
+        }
+        for note in calculation.notes.iter().filter(|n| n.action != "recommended") {
+            notes.push(note.text.clone());
+        }
         let _serves = serve
-            .filter(schema::serve::id.eq_any(serve_ids))
+            .filter(schema::serve::id.eq_any(calculation.get_serve_ids()))
             .load::<Serve>(&_connection)
             .expect("E");
+        // родители вариантов - их названия тоже попадут в снимок заказа
+        let parent_ids: Vec<i32> = _serves.iter().filter_map(|s| s.serve_id).collect();
+        let _parents = serve
+            .filter(schema::serve::id.eq_any(&parent_ids))
+            .load::<Serve>(&_connection)
+            .expect("E");
+        let order_price = variant_price + calculation.price;
+        let discount = Discount::get(order_price, item_types, form.promo.as_deref());
 
-        let mut tech_cat_ids = Vec::new();
-        let mut order_price = 0;
-        for _serve in _serves.iter() {
-            if !tech_cat_ids.iter().any(|&i| i==_serve.tech_cat_id) {
-                tech_cat_ids.push(_serve.tech_cat_id);
-            }
-            order_price += _serve.price;
-        }
+        // заказ, его опции и списание промокода - одной транзакцией:
+        // если лимит промокода уже выбран, заказ не создается
+        let mut is_code_used_up = false;
+        let _order = _connection.transaction::<_, diesel::result::Error, _>(|| {
+            let new_order = NewOrder::create (
+                form.title.clone(),
+                form.title.clone(),
+                form.types,
+                form.object_id,
+                form.username.clone(),
+                form.email.clone(),
+                form.description.clone(),
+                form.description.clone(),
+                user_id,
+                form.variant_id,
+            );
+            let _order = diesel::insert_into(schema::orders::table)
+                .values(&new_order)
+                .get_result::<Order>(&_connection)?;
 
-        for id in tech_cat_ids.iter() {
-            let new_cat = NewTechCategoriesItem {
-                category_id: *id,
-                item_id:     form.object_id,
-                types:       form.types,
-                is_active:   1,
+            for file in form.files.iter() {
+                let new_file = NewOrderFile::create (
+                    _order.id,
+                    file.to_string()
+                );
+                diesel::insert_into(schema::order_files::table)
+                    .values(&new_file)
+                    .execute(&_connection)?;
             };
-            diesel::insert_into(schema::tech_categories_items::table)
-                .values(&new_cat)
-                .execute(&_connection)
-                .expect("Error.");
-        }
 
-        // фух. Связи созданы все, но надо еще посчитать цену
-        // услуги для калькулятора. Как? А это будет сумма всех
-        // цен выбранных опций.
-        let price_acc = get_price_acc_values(&order_price);
-        diesel::update(&_order)
-            .set((
-                schema::orders::price.eq(order_price),
-                schema::orders::price_acc.eq(price_acc),
-            ))
-            .execute(&_connection)
-            .expect("Error.");
+            // опции привязываются к самому заказу (item_id - id заказа, types 7),
+            // а не к заказанному элементу: иначе они попадают в его опции и цену.
+            // старые ошибочные строки убирает консольная cleanup_order_links.
+            // кроме связи с опцией запоминаем ее название, цену, часы и категории -
+            // правка опции потом не изменит уже сделанный заказ
+            for _serve in _serves.iter() {
+                let new_serve_form = NewServeItems {
+                    serve_id: _serve.id,
+                    item_id:  _order.id,
+                    types:    7,
+                };
+                diesel::insert_into(schema::serve_items::table)
+                    .values(&new_serve_form)
+                    .execute(&_connection)?;
+                let parent = _parents.iter().find(|p| Some(p.id) == _serve.serve_id);
+                let new_snapshot = NewOrderServe {
+                    order_id:       _order.id,
+                    serve_id:       _serve.id,
+                    name:           _serve.name.clone(),
+                    name_en:        _serve.name_en.clone(),
+                    price:          _serve.price,
+                    man_hours:      _serve.man_hours,
+                    category_id:    _serve.category_id,
+                    tech_cat_id:    _serve.tech_cat_id,
+                    parent_id:      _serve.serve_id,
+                    parent_name:    parent.map(|p| p.name.clone()),
+                    parent_name_en: parent.map(|p| p.name_en.clone()),
+                };
+                diesel::insert_into(schema::order_serves::table)
+                    .values(&new_snapshot)
+                    .execute(&_connection)?;
+            }
+
+            // связи с тех. категориями - для отрисовки тех категорий
+            // услуги, которые активны; тоже на заказ, types 7
+            for id in calculation.get_tech_cat_ids().iter() {
+                let new_cat = NewTechCategoriesItem {
+                    category_id: *id,
+                    item_id:     _order.id,
+                    types:       7,
+                    is_active:   1,
+                };
+                diesel::insert_into(schema::tech_categories_items::table)
+                    .values(&new_cat)
+                    .execute(&_connection)?;
+            }
+
+            diesel::update(&_order)
+                .set((
+                    schema::orders::price.eq(order_price),
+                    schema::orders::price_acc.eq(discount.amount),
+                    schema::orders::discount_rule_id.eq(discount.rule_id),
+                    schema::orders::promo_code_id.eq(discount.promo_code_id),
+                    schema::orders::man_hours.eq(calculation.timeline.man_hours),
+                    schema::orders::work_days.eq(calculation.timeline.work_days),
+                    schema::orders::deadline.eq(calculation.timeline.deadline),
+                ))
+                .execute(&_connection)?;
+            if let Some(code_id) = discount.promo_code_id {
+                if !PromoCode::use_code(&_connection, code_id)? {
+                    is_code_used_up = true;
+                    return Err(diesel::result::Error::RollbackTransaction);
+                }
+            }
+            Ok(_order)
+        });
+        if is_code_used_up {
+            return HttpResponse::BadRequest().body("Промокод больше не действует - уберите его и отправьте заказ еще раз");
+        }
+        if _order.is_err() {
+            return HttpResponse::InternalServerError().body("Не удалось оформить заказ");
+        }
     }
-    HttpResponse::Ok()
+    HttpResponse::Ok().body(notes.join("\n"))
 }
 
 pub async fn delete_order(req: HttpRequest, _id: web::Path<i32>) -> impl Responder {
@@ -512,21 +652,59 @@ pub async fn delete_order(req: HttpRequest, _id: web::Path<i32>) -> impl Respond
             tech_categories_items::dsl::tech_categories_items,
         };
 
-        diesel::delete (
-            serve_items
-                .filter(schema::serve_items::item_id.eq(*_id))
-                .filter(schema::serve_items::types.eq(7))
-            )
-            .execute(&_connection)
-            .expect("E");
-        diesel::delete(
-            tech_categories_items
-                .filter(schema::tech_categories_items::item_id.eq(*_id))
-                .filter(schema::tech_categories_items::types.eq(7))
-            )
-            .execute(&_connection)
+        let _src_list = schema::order_files::table
+            .filter(schema::order_files::order_id.eq(*_id))
+            .select(schema::order_files::src)
+            .load::<String>(&_connection)
             .expect("E");
-        diesel::delete(&_order).execute(&_connection).expect("E");
+        _connection.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::delete (
+                serve_items
+                    .filter(schema::serve_items::item_id.eq(*_id))
+                    .filter(schema::serve_items::types.eq(7))
+                )
+                .execute(&_connection)?;
+            diesel::delete(
+                tech_categories_items
+                    .filter(schema::tech_categories_items::item_id.eq(*_id))
+                    .filter(schema::tech_categories_items::types.eq(7))
+                )
+                .execute(&_connection)?;
+            diesel::delete(schema::order_items::table.filter(schema::order_items::order_id.eq(*_id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::order_serves::table.filter(schema::order_serves::order_id.eq(*_id)))
+                .execute(&_connection)?;
+            diesel::delete(schema::order_files::table.filter(schema::order_files::order_id.eq(*_id)))
+                .execute(&_connection)?;
+            diesel::delete(&_order).execute(&_connection)?;
+            Ok(())
+        }).expect("E");
+        // файлы с диска - только после удаления заказа из базы
+        for f in _src_list.iter() {
+            std::fs::remove_file(f).ok();
+        }
     }
     HttpResponse::Ok()
 }
+
+// принятие заказа администратором; для вариантов товара списывается остаток
+pub async fn accept_order(session: Session, _id: web::Path<i32>) -> actix_web::Result<HttpResponse> {
+    if !is_signed_in(&session) {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+    let _request_user = get_request_user_data(&session);
+    if _request_user.perm != 60 {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+    let _connection = establish_connection();
+    let _order = schema::orders::table
+        .filter(schema::orders::id.eq(*_id))
+        .first::<Order>(&_connection)
+        .expect("E");
+    if _order.accept() {
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("ok"))
+    }
+    else {
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Заказ уже обработан или товара нет в наличии"))
+    }
+}
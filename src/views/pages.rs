@@ -25,6 +25,7 @@ use crate::utils::{
     get_first_load_page,
     get_all_storage,
     IndexResponse, AppState,
+    PriceFormat,
 };
 use crate::diesel::{
     RunQueryDsl,
@@ -46,6 +47,7 @@ pub fn pages_routes(config: &mut web::ServiceConfig) {
     config.route("/feedback_list/", web::get().to(feedback_list_page));
     config.route("/serve_list/", web::get().to(serve_list_page));
     config.route("/cookie_users_list/", web::get().to(cookie_users_list_page));
+    config.route("/missing_translations/", web::get().to(missing_translations_page));
 
     config.route("/load_tech_category/{id}/", web::get().to(get_tech_category_page));
     config.route("/load_serve_category/{id}/", web::get().to(get_serve_category_page));
@@ -225,6 +227,7 @@ pub async fn index_page (
                     stat:           StatPage,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     request_user:   _request_user,
@@ -237,6 +240,7 @@ pub async fn index_page (
                     stat:           _stat,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -256,6 +260,7 @@ pub async fn index_page (
                     stat:           StatPage,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     request_user:   _request_user,
@@ -268,6 +273,7 @@ pub async fn index_page (
                     stat:           _stat,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -294,6 +300,7 @@ pub async fn index_page (
                     stat:           StatPage,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     last_works:     _last_works,
@@ -305,6 +312,7 @@ pub async fn index_page (
                     stat:           _stat,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -323,6 +331,7 @@ pub async fn index_page (
                     stat:           StatPage,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     last_works:     _last_works,
@@ -334,6 +343,7 @@ pub async fn index_page (
                     stat:           _stat,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -1120,6 +1130,7 @@ pub async fn create_category_page(session: Session, req: HttpRequest) -> actix_w
         if _request_user.perm == 60 {
             let _connection = establish_connection();
             let _cats = schema::categories::table
+                .filter(schema::categories::deleted_at.is_null())
                 .load::<Categories>(&_connection)
                 .expect("Error");
 
@@ -1200,6 +1211,7 @@ pub async fn edit_category_page(session: Session, req: HttpRequest, _id: web::Pa
         let _request_user = get_request_user_data(&session);
         if _request_user.perm == 60 {
             let _cats = schema::categories::table
+                .filter(schema::categories::deleted_at.is_null())
                 .load::<Categories>(&_connection)
                 .expect("Error");
 
@@ -1278,10 +1290,6 @@ pub async fn create_item_page(session: Session, req: HttpRequest) -> actix_web::
             use crate::models::TechCategories;
 
             let _connection = establish_connection();
-            let all_tags = schema::tags::table
-                .load::<Tag>(&_connection)
-                .expect("Error.");
-
             let _tech_categories = schema::tech_categories::table
                 .load::<TechCategories>(&_connection)
                 .expect("E");
@@ -1291,14 +1299,12 @@ pub async fn create_item_page(session: Session, req: HttpRequest) -> actix_web::
                 #[template(path = "desctop/pages/create_item.stpl")]
                 struct Template {
                     request_user:   User,
-                    all_tags:       Vec<Tag>,
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
                 }
                 let body = Template {
                     request_user:   _request_user,
-                    all_tags:       all_tags,
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
@@ -1311,13 +1317,11 @@ pub async fn create_item_page(session: Session, req: HttpRequest) -> actix_web::
                 #[derive(TemplateOnce)]
                 #[template(path = "mobile/pages/create_item.stpl")]
                 struct Template {
-                    all_tags:       Vec<Tag>,
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
                 }
                 let body = Template {
-                    all_tags:       all_tags,
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
@@ -1364,12 +1368,9 @@ pub async fn edit_item_page(session: Session, req: HttpRequest, _id: web::Path<i
             let item_cats = _item.get_categories_obj();
             let item_tags = _item.get_tags_obj();
 
-            let _all_tags = schema::tags::table
-                .load::<Tag>(&_connection)
-                .expect("Error.");
-
             let _cats = schema::categories::table
                 .filter(schema::categories::types.eq(_item.types))
+                .filter(schema::categories::deleted_at.is_null())
                 .load::<Categories>(&_connection)
                 .expect("Error");
 
@@ -1398,7 +1399,6 @@ pub async fn edit_item_page(session: Session, req: HttpRequest, _id: web::Path<i
                     object:         Item,
                     cats:           Vec<Categories>,
                     is_ajax:        i32,
-                    all_tags:       Vec<Tag>,
                     item_tags:      Vec<Tag>,
                     item_cats:      Vec<Categories>,
                     tech_cats:      Vec<TechCategories>,
@@ -1411,7 +1411,6 @@ pub async fn edit_item_page(session: Session, req: HttpRequest, _id: web::Path<i
                     object:         _item,
                     cats:           _cats,
                     is_ajax:        is_ajax,
-                    all_tags:       _all_tags,
                     item_tags:      item_tags,
                     item_cats:      item_cats,
                     tech_cats:      _tech_categories,
@@ -1430,7 +1429,6 @@ pub async fn edit_item_page(session: Session, req: HttpRequest, _id: web::Path<i
                     object:         Item,
                     cats:           Vec<Categories>,
                     is_ajax:        i32,
-                    all_tags:       Vec<Tag>,
                     item_tags:      Vec<Tag>,
                     item_cats:      Vec<Categories>,
                     tech_cats:      Vec<TechCategories>,
@@ -1442,7 +1440,6 @@ pub async fn edit_item_page(session: Session, req: HttpRequest, _id: web::Path<i
                     object:         _item,
                     cats:           _cats,
                     is_ajax:        is_ajax,
-                    all_tags:       _all_tags,
                     item_tags:      item_tags,
                     item_cats:      item_cats,
                     tech_cats:      _tech_categories,
@@ -1679,3 +1676,80 @@ pub async fn image_page(_id: web::Path<i32>) -> actix_web::Result<HttpResponse>
     .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
     Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
 }
+
+pub async fn missing_translations_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+    use crate::utils::{get_missing_translations, MissingTranslation};
+
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+    if is_ajax == 0 {
+        get_first_load_page (
+            &session,
+            is_desctop,
+            "Непереведенные объекты".to_string(),
+            "вебсервисы.рф: Непереведенные объекты".to_string(),
+            "/missing_translations/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await
+    }
+    else if !is_signed_in(&session) {
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
+    }
+    else {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm < 60 {
+            return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+        }
+        let (items_list, categories_list, tags_list) = get_missing_translations();
+        if is_desctop {
+            #[derive(TemplateOnce)]
+            #[template(path = "desctop/pages/missing_translations.stpl")]
+            struct Template {
+                request_user:    User,
+                is_ajax:         i32,
+                items_list:      Vec<MissingTranslation>,
+                categories_list: Vec<MissingTranslation>,
+                tags_list:       Vec<MissingTranslation>,
+                template_types:  u8,
+                linguage:        u8,
+            }
+            let body = Template {
+                request_user:    _request_user,
+                is_ajax:         is_ajax,
+                items_list:      items_list,
+                categories_list: categories_list,
+                tags_list:       tags_list,
+                template_types:  t,
+                linguage:        l,
+            }
+            .render_once()
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+        else {
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/pages/missing_translations.stpl")]
+            struct Template {
+                is_ajax:         i32,
+                items_list:      Vec<MissingTranslation>,
+                categories_list: Vec<MissingTranslation>,
+                tags_list:       Vec<MissingTranslation>,
+                template_types:  u8,
+                linguage:        u8,
+            }
+            let body = Template {
+                is_ajax:         is_ajax,
+                items_list:      items_list,
+                categories_list: categories_list,
+                tags_list:       tags_list,
+                template_types:  t,
+                linguage:        l,
+            }
+            .render_once()
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+    }
+}
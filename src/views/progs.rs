@@ -51,6 +51,7 @@ pub fn progs_routes(config: &mut web::ServiceConfig) {
     config.route("/publish_item/{id}/", web::post().to(publish_item));
     config.route("/hide_item/{id}/", web::post().to(hide_item));
     config.route("/edit_content_item/{id}/", web::post().to(edit_content_item));
+    config.route("/edit_item_access/{id}/", web::post().to(edit_item_access));
 
     config.route("/create_category/", web::post().to(create_category));
     config.route("/edit_category/{id}/", web::post().to(edit_category));
@@ -322,9 +323,9 @@ pub async fn create_item(session: Session, mut payload: Multipart) -> impl Respo
             let types = form.types;
             let new_item = NewItem::create (
                 form.title.clone(),
-                form.title.clone(),
-                form.description.clone(),
+                form.title_en.clone(),
                 form.description.clone(),
+                form.description_en.clone(),
                 form.link.clone(),
                 form.main_image.clone(),
                 _request_user.id,
@@ -349,7 +350,8 @@ pub async fn create_item(session: Session, mut payload: Multipart) -> impl Respo
                     .execute(&_connection)
                     .expect("E.");
             };
-            for tag_id in form.tags_list.into_iter() {
+            let tags_list = Tag::get_ids_with_names(form.tags_list, &form.new_tags, _request_user.id);
+            for tag_id in tags_list.into_iter() {
                 let new_tag = NewTagItems {
                     tag_id:  tag_id,
                     item_id: _item.id,
@@ -424,7 +426,7 @@ pub async fn create_item(session: Session, mut payload: Multipart) -> impl Respo
             // фух. Связи созданы все, но надо еще посчитать цену
             // услуги для калькулятора. Как? А  это будет сумма всех
             // цен выбранных опций.
-            let price_acc = crate::utils::get_price_acc_values(&item_price);
+            let price_acc = crate::utils::get_price_acc_values(&item_price, _item.types);
             diesel::update(&_item)
                 .set((
                     schema::items::price.eq(item_price),
@@ -521,9 +523,9 @@ pub async fn edit_item(session: Session, mut payload: Multipart, _id: web::Path<
             let form = crate::utils::item_form(payload.borrow_mut(), _request_user.id).await;
             let _new_item = EditItem {
                 title:          form.title.clone(),
-                title_en:       form.title.clone(),
+                title_en:       form.title_en.clone(),
                 description:    form.description.clone(),
-                description_en: form.description.clone(),
+                description_en: form.description_en.clone(),
                 link:           form.link.clone(),
                 image:          form.main_image.clone(),
                 position:       form.position,
@@ -558,7 +560,8 @@ pub async fn edit_item(session: Session, mut payload: Multipart, _id: web::Path<
                         .expect("Error.");
                 }
             };
-            for tag_id in form.tags_list.into_iter() {
+            let tags_list = Tag::get_ids_with_names(form.tags_list, &form.new_tags, _request_user.id);
+            for tag_id in tags_list.into_iter() {
                 let new_tag = NewTagItems {
                     tag_id:  tag_id,
                     item_id: _item.id,
@@ -645,7 +648,7 @@ pub async fn edit_item(session: Session, mut payload: Multipart, _id: web::Path<
             // фух. Связи созданы все, но надо еще посчитать цену
             // услуги для калькулятора. Как? А  это будет сумма всех
             // цен выбранных опций.
-            let price_acc = crate::utils::get_price_acc_values(&item_price);
+            let price_acc = crate::utils::get_price_acc_values(&item_price, _item.types);
             diesel::update(&_item)
                 .set((
                     schema::items::price.eq(item_price),
@@ -689,10 +692,15 @@ pub async fn edit_content_item(session: Session, mut payload: Multipart, _id: we
         let _request_user = get_request_user_data(&session);
         if _request_user.perm == 60 || _request_user.id == _item.user_id {
             let form = crate::utils::content_form(payload.borrow_mut()).await;
+            let (words, minutes, words_en, minutes_en) = crate::utils::get_reading_stats(&form.content, &form.content_en);
             diesel::update(&_item)
                 .set((
                     schema::items::content.eq(form.content.clone()),
                     schema::items::content_en.eq(form.content_en.clone()),
+                    schema::items::word_count.eq(words),
+                    schema::items::reading_time.eq(minutes),
+                    schema::items::word_count_en.eq(words_en),
+                    schema::items::reading_time_en.eq(minutes_en),
                 ))
                 .execute(&_connection)
                 .expect("E");
@@ -701,73 +709,37 @@ pub async fn edit_content_item(session: Session, mut payload: Multipart, _id: we
     HttpResponse::Ok().body("")
 }
 
-pub async fn delete_item(session: Session, _id: web::Path<i32>) -> impl Responder {
-    use crate::schema::{
-        items::dsl::items,
-        tags_items::dsl::tags_items,
-        category::dsl::category,
-        files::dsl::files,
-    };
-
+// видимость элемента: всем, авторизованным, выбранным пользователям и группам + секретная ссылка
+pub async fn edit_item_access(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
     if is_signed_in(&session) {
         let _request_user = get_request_user_data(&session);
         if _request_user.perm == 60 {
             let _connection = establish_connection();
-            let _item = items
+            let _item = schema::items::table
                 .filter(schema::items::id.eq(*_id))
-                .first::<Item>(&_connection)
-                .expect("E");
-
-            let _src_list = files
-                .filter(schema::files::item_id.eq(*_id))
-                .filter(schema::files::item_types.eq(_item.types))
-                .select(schema::files::src)
-                .load::<String>(&_connection)
-                .expect("E");
-
-            for f in _src_list.iter() {
-                std::fs::remove_file(f);
+                .first::<Item>(&_connection);
+            if let Ok(_item) = _item {
+                let form = crate::utils::item_access_form(payload.borrow_mut()).await;
+                if crate::models::ItemAccess::save(&_item, form) {
+                    return HttpResponse::Ok().body("ok");
+                }
+                return HttpResponse::Ok().body("Есть неизвестные пользователи или группы");
             }
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
 
-            diesel::delete (
-                files
-                    .filter(schema::files::item_id.eq(*_id))
-                    .filter(schema::files::item_types.eq(_item.types))
-                )
-                .execute(&_connection)
-                .expect("E");
-            diesel::delete (
-                tags_items
-                    .filter(schema::tags_items::item_id.eq(*_id))
-                    .filter(schema::tags_items::types.eq(_item.types))
-                )
-                .execute(&_connection)
-                .expect("E");
-            diesel::delete (
-                category
-                    .filter(schema::category::item_id.eq(*_id))
-                    .filter(schema::category::types.eq(_item.types))
-                ) 
-                .execute(&_connection)
+pub async fn delete_item(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _item = schema::items::table
+                .filter(schema::items::id.eq(*_id))
+                .first::<Item>(&_connection)
                 .expect("E");
-            diesel::delete(&_item).execute(&_connection).expect("E");
-
-            let _categories = _item.get_categories_obj();
-            let _tags = _item.get_tags_obj();
-
-            for _category in _categories.iter() {
-                diesel::update(_category)
-                    .set(schema::categories::count.eq(_category.count - 1))
-                    .execute(&_connection)
-                    .expect("Error.");
-            };
-            for _tag in _tags.iter() {
-                diesel::update(_tag)
-                    .set(schema::tags::count.eq(_tag.count - 1))
-                    .execute(&_connection)
-                    .expect("Error.");
-            };
-
+            _item.soft_delete();
         }
     }
     HttpResponse::Ok()
@@ -778,9 +750,11 @@ pub async fn delete_category(session: Session, _id: web::Path<i32>) -> impl Resp
         let _request_user = get_request_user_data(&session);
         if _request_user.perm == 60 {
             let _connection = establish_connection();
-            diesel::delete(schema::categories::table.filter(schema::categories::id.eq(*_id)))
-                .execute(&_connection)
+            let _category = schema::categories::table
+                .filter(schema::categories::id.eq(*_id))
+                .first::<Categories>(&_connection)
                 .expect("E");
+            _category.soft_delete();
         }
     }
     HttpResponse::Ok()
@@ -841,11 +815,7 @@ pub async fn delete_file(session: Session, _id: web::Path<i32>) -> impl Responde
                 .filter(schema::files::id.eq(*_id))
                 .first::<crate::models::File>(&_connection)
                 .expect("E");
-            std::fs::remove_file(_file.src).expect("E");
-
-            diesel::delete(schema::files::table.filter(schema::files::id.eq(*_id)))
-                .execute(&_connection)
-                .expect("E");
+            _file.soft_delete();
         }
     }
     HttpResponse::Ok()
@@ -13,6 +13,7 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    PriceFormat,
 };
 
 use sailfish::TemplateOnce;
@@ -184,6 +185,7 @@ pub async fn search_page(session: Session, req: HttpRequest, q: web::Path<String
                     q:              String,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     request_user:   _request_user,
@@ -202,6 +204,7 @@ pub async fn search_page(session: Session, req: HttpRequest, q: web::Path<String
                     q:              _q,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -281,6 +284,7 @@ pub async fn search_page(session: Session, req: HttpRequest, q: web::Path<String
                     q:              String,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     works_list:     work_list,
@@ -298,6 +302,7 @@ pub async fn search_page(session: Session, req: HttpRequest, q: web::Path<String
                     q:              _q,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -735,6 +740,7 @@ pub async fn search_stores_page(session: Session, req: HttpRequest, q: web::Path
                     next_page_number: i32,
                     template_types:   u8,
                     linguage:         u8,
+                    prices:           PriceFormat,
                 }
                 let body = Template {
                     request_user:     _request_user,
@@ -745,6 +751,7 @@ pub async fn search_stores_page(session: Session, req: HttpRequest, q: web::Path
                     next_page_number: next_page_number,
                     template_types:   t,
                     linguage:         l,
+                    prices:           PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -796,6 +803,7 @@ pub async fn search_stores_page(session: Session, req: HttpRequest, q: web::Path
                     next_page_number: i32,
                     template_types:   u8,
                     linguage:         u8,
+                    prices:           PriceFormat,
                 }
 
                 let body = Template {
@@ -806,6 +814,7 @@ pub async fn search_stores_page(session: Session, req: HttpRequest, q: web::Path
                     next_page_number: next_page_number,
                     template_types:   t,
                     linguage:         l,
+                    prices:           PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
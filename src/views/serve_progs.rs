@@ -12,6 +12,7 @@ use crate::diesel::{
     RunQueryDsl,
     ExpressionMethods,
     QueryDsl,
+    Connection,
 };
 use crate::utils::{
     establish_connection,
@@ -19,6 +20,8 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    reprice_items,
+    reprice_links,
 };
 use crate::schema;
 use crate::models::{
@@ -26,6 +29,8 @@ use crate::models::{
     Serve,
     NewServe,
     TechCategories,
+    ServeRelation,
+    ServePrice,
 };
 use actix_session::Session;
 use actix_multipart::{Field, Multipart};
@@ -69,6 +74,8 @@ pub fn serve_routes(config: &mut web::ServiceConfig) {
     config.route("/delete_serve/{id}/", web::get().to(delete_serve));
     config.route("/delete_serve_category/{id}/", web::get().to(delete_serve_category));
     config.route("/delete_tech_category/{id}/", web::get().to(delete_tech_category));
+    config.route("/create_serve_relation/{id}/", web::post().to(create_serve_relation));
+    config.route("/delete_serve_relation/{id}/", web::get().to(delete_serve_relation));
 }
 
 pub async fn serve_categories_page(session: Session, req: HttpRequest) -> actix_web::Result<HttpResponse> {
@@ -836,26 +843,30 @@ pub async fn edit_serve_category(session: Session, mut payload: Multipart, _id:
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct ServeForm {
-    pub name:        String,
-    pub description: String,
-    pub position:    i16,
-    pub category_id: i32,
-    pub price:       i32,
-    pub man_hours:   i16,
-    pub is_default:  bool,
-    pub serve_id:    Option<i32>,
+    pub name:           String,
+    pub name_en:        String,
+    pub description:    String,
+    pub description_en: String,
+    pub position:       i16,
+    pub category_id:    i32,
+    pub price:          i32,
+    pub man_hours:      i16,
+    pub is_default:     bool,
+    pub serve_id:       Option<i32>,
 }
 
 pub async fn serve_split_payload(payload: &mut Multipart) -> ServeForm {
     let mut form: ServeForm = ServeForm {
-        name:        "".to_string(),
-        description: "".to_string(),
-        position:    0,
-        category_id: 0,
-        price:       0,
-        man_hours:   0,
-        is_default:  true,
-        serve_id:    None,
+        name:           "".to_string(),
+        name_en:        "".to_string(),
+        description:    "".to_string(),
+        description_en: "".to_string(),
+        position:       0,
+        category_id:    0,
+        price:          0,
+        man_hours:      0,
+        is_default:     true,
+        serve_id:       None,
     };
 
     let mut is_default = false;
@@ -925,8 +936,12 @@ pub async fn serve_split_payload(payload: &mut Multipart) -> ServeForm {
                     let data_string = s.to_string();
                     if field.name() == "name" {
                         form.name = data_string
+                    } else if field.name() == "name_en" {
+                        form.name_en = data_string
                     } else if field.name() == "description" {
                         form.description = data_string
+                    } else if field.name() == "description_en" {
+                        form.description_en = data_string
                     } ;
                 }
             }
@@ -952,9 +967,9 @@ pub async fn create_serve(session: Session, mut payload: Multipart) -> impl Resp
 
             let _new_serve = NewServe {
                 name:           form.name.clone(),
-                name_en:        form.name.clone(),
+                name_en:        form.name_en.clone(),
                 description:    Some(form.description.clone()),
-                description_en: Some(form.description.clone()),
+                description_en: Some(form.description_en.clone()),
                 position:       form.position,
                 category_id:    _cat_id,
                 price:          form.price,
@@ -972,6 +987,7 @@ pub async fn create_serve(session: Session, mut payload: Multipart) -> impl Resp
                 .values(&_new_serve)
                 .get_result::<Serve>(&_connection)
                 .expect("E.");
+            ServePrice::create(_serve.id, _serve.price, _serve.man_hours, _request_user.id);
 
             if _new_serve.is_default {
                 diesel::update(&_category)
@@ -1037,9 +1053,9 @@ pub async fn edit_serve(session: Session, mut payload: Multipart, _id: web::Path
 
             let _new_serve = NewServe {
                 name:           form.name.clone(),
-                name_en:        form.name.clone(),
+                name_en:        form.name_en.clone(),
                 description:    Some(form.description.clone()),
-                description_en: Some(form.description.clone()),
+                description_en: Some(form.description_en.clone()),
                 position:       form.position,
                 category_id:    _serve.category_id,
                 price:          form.price,
@@ -1053,10 +1069,22 @@ pub async fn edit_serve(session: Session, mut payload: Multipart, _id: web::Path
                 view:           0,
             };
 
+            // старые заказы хранят свои цены, а здесь - история изменений
+            if _new_serve.price != _serve.price || _new_serve.man_hours != _serve.man_hours {
+                ServePrice::create(_serve.id, _new_serve.price, _new_serve.man_hours, _request_user.id);
+            }
+
+            let price_changed = _new_serve.price != _serve.price;
             diesel::update(&_serve)
                 .set(_new_serve)
                 .execute(&_connection)
                 .expect("E");
+
+            // цены элементов с этой опцией - сумма цен их опций
+            if price_changed {
+                let report = reprice_items(Some(&vec![_serve.id]), false);
+                info!("reprice items: {:?}", report);
+            }
         }
     }
     return HttpResponse::Ok();
@@ -1080,12 +1108,25 @@ pub async fn delete_serve(session: Session, _id: web::Path<i32>) -> impl Respond
                 .filter(schema::serve_categories::id.eq(_serve.category_id))
                 .first::<ServeCategories>(&_connection)
                 .expect("E");
-            diesel::update(&_category)
-                .set(schema::serve_categories::count.eq(_category.count - 1))
-                .execute(&_connection)
-                .expect("Error.");
-
-            diesel::delete(&_serve).execute(&_connection).expect("E");
+            // элементы с этой опцией запоминаем до удаления связей -
+            // потом пересчитываем их цены уже без нее
+            let links = schema::serve_items::table
+                .filter(schema::serve_items::serve_id.eq(_serve.id))
+                .filter(schema::serve_items::types.ne(7))
+                .select((schema::serve_items::item_id, schema::serve_items::types))
+                .load::<(i32, i16)>(&_connection)
+                .expect("E");
+            _connection.transaction::<_, diesel::result::Error, _>(|| {
+                diesel::update(&_category)
+                    .set(schema::serve_categories::count.eq(_category.count - 1))
+                    .execute(&_connection)?;
+                diesel::delete(schema::serve_items::table.filter(schema::serve_items::serve_id.eq(_serve.id)))
+                    .execute(&_connection)?;
+                diesel::delete(&_serve).execute(&_connection)?;
+                Ok(())
+            }).expect("E");
+            let report = reprice_links(links, false);
+            info!("reprice items: {:?}", report);
         }
     }
     HttpResponse::Ok()
@@ -1127,17 +1168,90 @@ pub async fn delete_serve_category(session: Session, _id: web::Path<i32>) -> imp
     if is_signed_in(&session) {
         let _request_user = get_request_user_data(&session);
         if _request_user.perm == 60 || s_category.user_id == _request_user.id {
-            diesel::delete(serve_categories.filter(schema::serve_categories::id.eq(*_id))).execute(&_connection).expect("E");
+            let has_serves = schema::serve::table
+                .filter(schema::serve::category_id.eq(*_id))
+                .select(schema::serve::id)
+                .first::<i32>(&_connection)
+                .is_ok();
+            if has_serves {
+                return HttpResponse::Ok().body("Сначала удалите опции этой категории");
+            }
+            // технологии кейсов ссылаются на категорию - убираем их вместе с ней
+            let result = _connection.transaction::<_, diesel::result::Error, _>(|| {
+                diesel::delete(schema::work_case_techs::table.filter(schema::work_case_techs::category_id.eq(*_id)))
+                    .execute(&_connection)?;
+                diesel::delete(serve_categories.filter(schema::serve_categories::id.eq(*_id)))
+                    .execute(&_connection)?;
+
+                let _category = tech_categories
+                    .filter(schema::tech_categories::id.eq(s_category.category_id))
+                    .first::<TechCategories>(&_connection)?;
+                diesel::update(&_category)
+                    .set(schema::tech_categories::count.eq(_category.count - 1))
+                    .execute(&_connection)?;
+                Ok(())
+            });
+            if result.is_err() {
+                return HttpResponse::Ok().body("Категорию удалить не удалось");
+            }
+        }
+    }
+    HttpResponse::Ok().body("ok")
+}
 
-            let _category = tech_categories
-                .filter(schema::tech_categories::id.eq(*_id))
-                .first::<TechCategories>(&_connection)
-                .expect("E");
-            diesel::update(&_category)
-                .set(schema::tech_categories::count.eq(_category.count - 1))
-                .execute(&_connection)
-                .expect("E");
+// связи опции: требует, исключает, рекомендует
+pub async fn create_serve_relation(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    use crate::utils::serve_relation_form;
+
+    if is_signed_in(&session) {
+        let _connection = establish_connection();
+        let _serve = match schema::serve::table
+            .filter(schema::serve::id.eq(*_id))
+            .first::<Serve>(&_connection) {
+                Ok(_serve) => _serve,
+                Err(_) => return HttpResponse::Ok().body("Permission Denied"),
+            };
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 || _serve.user_id == _request_user.id {
+            let form = serve_relation_form(payload.borrow_mut()).await;
+            if form.types < 1 || form.types > 3 {
+                return HttpResponse::Ok().body("Выберите тип связи");
+            }
+            if form.target_id == _serve.id {
+                return HttpResponse::Ok().body("Опция не может быть связана сама с собой");
+            }
+            let target_exists = schema::serve::table
+                .filter(schema::serve::id.eq(form.target_id))
+                .select(schema::serve::id)
+                .first::<i32>(&_connection)
+                .is_ok();
+            if !target_exists {
+                return HttpResponse::Ok().body("Опция не найдена");
+            }
+            if ServeRelation::create(_serve.id, form.target_id, form.types).is_none() {
+                return HttpResponse::Ok().body("Связь с этой опцией уже есть");
+            }
+            return HttpResponse::Ok().body("ok");
         }
     }
-    HttpResponse::Ok()
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn delete_serve_relation(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        if let Some(_relation) = ServeRelation::get_with_id(*_id) {
+            let _connection = establish_connection();
+            let owner_id = schema::serve::table
+                .filter(schema::serve::id.eq(_relation.serve_id))
+                .select(schema::serve::user_id)
+                .first::<i32>(&_connection)
+                .unwrap_or(0);
+            let _request_user = get_request_user_data(&session);
+            if _request_user.perm == 60 || owner_id == _request_user.id {
+                _relation.delete();
+                return HttpResponse::Ok().body("ok");
+            }
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
 }
@@ -13,6 +13,8 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    get_list_filter,
+    ListFilter,
 };
 use actix_session::Session;
 use crate::schema;
@@ -48,14 +50,17 @@ pub async fn get_service_page(session: Session, req: HttpRequest, param: web::Pa
 
     let _item = schema::items::table
         .filter(schema::items::slug.eq(&_item_id))
+        .filter(schema::items::deleted_at.is_null())
         .first::<Item>(&_connection)
         .expect("E");
+    let _key = crate::utils::get_share_key(&req);
+    let title = if _item.is_public() { _item.title.clone() } else { "Закрытая страница".to_string() };
     if is_ajax == 0 {
         get_first_load_page (
             &session,
             is_desctop,
-            _item.title.clone() + &" | Услуга".to_string(),
-            _item.title.clone() + &" | Услуга: вебсервисы.рф".to_string(),
+            title.clone() + &" | Услуга".to_string(),
+            title.clone() + &" | Услуга: вебсервисы.рф".to_string(),
             "/service/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
             _item.get_image(),
             t, 
@@ -85,13 +90,13 @@ pub async fn get_service_page(session: Session, req: HttpRequest, param: web::Pa
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            if !_item.is_active && _request_user.perm < 10 {
+            if !_item.is_visible(Some(&_request_user), _key.as_deref()) {
                 crate::utils::get_private_page (
                     is_ajax,
                     _request_user,
                     is_desctop,
-                    _item.title.clone() + &" | Услуга".to_string(),
-                    _item.title.clone() + &" | Услуга: вебсервисы.рф".to_string(),
+                    title.clone() + &" | Услуга".to_string(),
+                    title.clone() + &" | Услуга: вебсервисы.рф".to_string(),
                     "/service/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
                     _item.get_image(),
                     t, 
@@ -162,12 +167,12 @@ pub async fn get_service_page(session: Session, req: HttpRequest, param: web::Pa
             }
         }
         else {
-            if _item.is_active == false {
+            if !_item.is_visible(None, _key.as_deref()) {
                 crate::utils::get_anon_private_page (
                     is_ajax,
                     is_desctop,
-                    _item.title.clone() + &" | Услуга".to_string(),
-                    _item.title.clone() + &" | Услуга: вебсервисы.рф".to_string(),
+                    title.clone() + &" | Услуга".to_string(),
+                    title.clone() + &" | Услуга: вебсервисы.рф".to_string(),
                     "/service/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
                     _item.get_image(),
                     t, 
@@ -247,6 +252,7 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
     let _category = categories
         .filter(schema::categories::slug.eq(&_cat_id))
         .filter(schema::categories::types.eq(2)) 
+        .filter(schema::categories::deleted_at.is_null())
         .select((
             schema::categories::name,
             schema::categories::slug,
@@ -286,6 +292,7 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
         use crate::models::Service;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
         let object_list: Vec<Service>;
         let next_page_number: i32;
         let _cats = block(move || Categories::get_categories_for_types(2, l)).await?;
@@ -293,7 +300,8 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let _res = block(move || Categories::get_services_list(_category.id, page, 20, _request_user.perm == 60, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_services_list(_category.id, page, 20, _request_user.perm == 60, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -304,7 +312,9 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
                 struct Template {
                     request_user:     User,
                     category:         CatDetail,
+                    all_tags:         Vec<SmallTag>,
                     object_list:      Vec<Service>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -313,7 +323,9 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
                 let body = Template {
                     request_user:     _request_user,
                     category:         _category,
+                    all_tags:         _tags,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -331,6 +343,7 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Service>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -341,6 +354,7 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -352,7 +366,8 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
             }
         }
         else {
-            let _res = block(move || Categories::get_services_list(_category.id, page, 20, false, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_services_list(_category.id, page, 20, false, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -363,7 +378,9 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
                 #[template(path = "desctop/services/anon_category.stpl")]
                 struct Template {
                     category:         CatDetail,
+                    all_tags:         Vec<SmallTag>,
                     object_list:      Vec<Service>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -371,7 +388,9 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
                 }
                 let body = Template {
                     category:         _category,
+                    all_tags:         _tags,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -389,6 +408,7 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Service>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -399,6 +419,7 @@ pub async fn service_category_page(session: Session, req: HttpRequest, _id: web:
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
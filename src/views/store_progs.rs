@@ -5,7 +5,10 @@ use actix_web::{
     HttpResponse,
     error::InternalError,
     http::StatusCode,
+    Responder,
 };
+use std::borrow::BorrowMut;
+use actix_multipart::Multipart;
 
 use crate::utils::{
     establish_connection,
@@ -13,6 +16,9 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    get_list_filter,
+    ListFilter,
+    PriceFormat,
 };
 use actix_session::Session;
 use crate::schema;
@@ -28,6 +34,7 @@ use crate::models::{
     Cat,
     SmallTag,
     CatDetail,
+    ItemVariant,
 };
 use sailfish::TemplateOnce;
 
@@ -36,6 +43,9 @@ pub fn store_routes(config: &mut web::ServiceConfig) {
     config.route("/store_categories/", web::get().to(store_categories_page));
     config.service(web::resource("/store/{cat_slug}/{store_slug}/").route(web::get().to(get_store_page)));
     config.service(web::resource("/stores/{slug}/").route(web::get().to(store_category_page)));
+    config.route("/create_variant/{id}/", web::post().to(create_variant));
+    config.route("/edit_variant/{id}/", web::post().to(edit_variant));
+    config.route("/delete_variant/{id}/", web::get().to(delete_variant));
 }
 
 
@@ -51,14 +61,17 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
 
     let _item = items
         .filter(schema::items::slug.eq(&_item_id))
+        .filter(schema::items::deleted_at.is_null())
         .first::<Item>(&_connection)
         .expect("E");
+    let _key = crate::utils::get_share_key(&req);
+    let title = if _item.is_public() { _item.title.clone() } else { "Закрытая страница".to_string() };
     if is_ajax == 0 {
         get_first_load_page (
             &session,
             is_desctop,
-            _item.title.clone() + &" | Товар".to_string(),
-            _item.title.clone() + &" | Товар: вебсервисы.рф".to_string(),
+            title.clone() + &" | Товар".to_string(),
+            title.clone() + &" | Товар: вебсервисы.рф".to_string(),
             "/store/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
             _item.get_image(),
             t, 
@@ -83,13 +96,13 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            if !_item.is_active && _request_user.perm < 10 {
+            if !_item.is_visible(Some(&_request_user), _key.as_deref()) {
                 crate::utils::get_private_page (
                     is_ajax,
                     _request_user,
                     is_desctop,
-                    _item.title.clone() + &" | Товар".to_string(),
-                    _item.title.clone() + &" | Товар: вебсервисы.рф".to_string(),
+                    title.clone() + &" | Товар".to_string(),
+                    title.clone() + &" | Товар: вебсервисы.рф".to_string(),
                     "/store/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
                     _item.get_image(),
                     t, 
@@ -108,6 +121,7 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     request_user:   _request_user,
@@ -118,6 +132,7 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -137,6 +152,7 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     request_user:   _request_user,
@@ -149,6 +165,7 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -156,13 +173,13 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
             }
         }
         else {
-            if _item.is_active == false {
+            if !_item.is_visible(None, _key.as_deref()) {
                 use crate::utils::get_anon_private_page;
                 get_anon_private_page (
                     is_ajax,
                     is_desctop,
-                    _item.title.clone() + &" | Товар".to_string(),
-                    _item.title.clone() + &" | Товар: вебсервисы.рф".to_string(),
+                    title.clone() + &" | Товар".to_string(),
+                    title.clone() + &" | Товар: вебсервисы.рф".to_string(),
                     "/store/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
                     _item.get_image(),
                     t, 
@@ -180,6 +197,7 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     object:         _item,
@@ -189,6 +207,7 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -207,6 +226,7 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     object:         _item,
@@ -218,6 +238,7 @@ pub async fn get_store_page(session: Session, req: HttpRequest, param: web::Path
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -238,6 +259,7 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
     let _category = categories
         .filter(schema::categories::slug.eq(&_cat_id))
         .filter(schema::categories::types.eq(3))
+        .filter(schema::categories::deleted_at.is_null())
         .select((
             schema::categories::name,
             schema::categories::slug,
@@ -277,6 +299,7 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
         use crate::models::Store;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
         let object_list: Vec<Store>;
         let next_page_number: i32;
         let _cats = block(move || Categories::get_categories_for_types(3, l)).await?;
@@ -284,7 +307,8 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let _res = block(move || Categories::get_stores_list(_category.id, page, 20, _request_user.perm == 60, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_stores_list(_category.id, page, 20, _request_user.perm == 60, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -298,10 +322,12 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Store>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
                     linguage:         u8,
+                    prices:           PriceFormat,
                 }
                 let body = Template {
                     request_user:     _request_user,
@@ -309,10 +335,12 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
                     linguage:         l,
+                    prices:           PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -326,6 +354,7 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Store>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -337,6 +366,7 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -348,7 +378,8 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
             }
         }
         else {
-            let _res = block(move || Categories::get_stores_list(_category.id, page, 20, false, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_stores_list(_category.id, page, 20, false, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -362,20 +393,24 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Store>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
                     linguage:         u8,
+                    prices:           PriceFormat,
                 }
                 let body = Template {
                     all_tags:         _tags,
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
                     linguage:         l,
+                    prices:           PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -389,6 +424,7 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Store>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -399,6 +435,7 @@ pub async fn store_category_page(session: Session, req: HttpRequest, _id: web::P
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -447,6 +484,7 @@ pub async fn store_categories_page(session: Session, req: HttpRequest) -> actix_
                     stat:           StatPage,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     request_user:   _request_user,
@@ -456,6 +494,7 @@ pub async fn store_categories_page(session: Session, req: HttpRequest) -> actix_
                     stat:           _stat,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -498,6 +537,7 @@ pub async fn store_categories_page(session: Session, req: HttpRequest) -> actix_
                     stat:           StatPage,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     is_ajax:        is_ajax,
@@ -506,6 +546,7 @@ pub async fn store_categories_page(session: Session, req: HttpRequest) -> actix_
                     stat:           _stat,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -537,3 +578,75 @@ pub async fn store_categories_page(session: Session, req: HttpRequest) -> actix_
         }
     }
 }
+
+// варианты товара: id - товар.
+// артикул обязателен и уникален - иначе текст ошибки вместо "ok"
+fn check_variant_sku(form: &crate::utils::VariantForm, except_id: Option<i32>) -> Option<&'static str> {
+    if form.sku.is_empty() {
+        return Some("Укажите артикул");
+    }
+    if ItemVariant::is_sku_taken(&form.sku, except_id) {
+        return Some("Такой артикул уже есть");
+    }
+    None
+}
+
+pub async fn create_variant(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _item = schema::items::table
+                .filter(schema::items::id.eq(*_id))
+                .filter(schema::items::types.eq(3))
+                .first::<Item>(&_connection);
+            if _item.is_ok() {
+                let form = crate::utils::variant_form(payload.borrow_mut()).await;
+                if let Some(error) = check_variant_sku(&form, None) {
+                    return HttpResponse::Ok().body(error);
+                }
+                if ItemVariant::create(*_id, form).is_none() {
+                    return HttpResponse::Ok().body("Не удалось сохранить вариант");
+                }
+                return HttpResponse::Ok().body("ok");
+            }
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn edit_variant(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            if let Some(_variant) = ItemVariant::get_with_id(*_id) {
+                let form = crate::utils::variant_form(payload.borrow_mut()).await;
+                if let Some(error) = check_variant_sku(&form, Some(_variant.id)) {
+                    return HttpResponse::Ok().body(error);
+                }
+                if !_variant.update_variant(form) {
+                    return HttpResponse::Ok().body("Не удалось сохранить вариант");
+                }
+                return HttpResponse::Ok().body("ok");
+            }
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+// заказанный вариант не удаляем - его можно снять с продажи нулевым остатком
+pub async fn delete_variant(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            if let Some(_variant) = ItemVariant::get_with_id(*_id) {
+                if _variant.is_ordered() {
+                    return HttpResponse::Ok().body("Вариант есть в заказах - поставьте остаток 0");
+                }
+                _variant.delete();
+                return HttpResponse::Ok().body("ok");
+            }
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
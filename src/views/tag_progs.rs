@@ -22,6 +22,10 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    get_list_filter,
+    get_viewer,
+    ListFilter,
+    PriceFormat,
 };
 use crate::schema;
 use crate::models::{
@@ -34,6 +38,7 @@ use sailfish::TemplateOnce;
 
 pub fn tag_routes(config: &mut web::ServiceConfig) {
     config.route("/tags/", web::get().to(tags_page));
+    config.route("/tags/{slugs}/", web::get().to(multi_tag_page));
     config.route("/tag/{slug}/", web::get().to(tag_page));
     config.route("/tag_blogs/{slug}/", web::get().to(tag_blogs_page));
     config.route("/tag_services/{slug}/", web::get().to(tag_services_page));
@@ -50,6 +55,26 @@ pub fn tag_routes(config: &mut web::ServiceConfig) {
         .route(web::post().to(edit_tag))
     );
     config.route("/delete_tag/{id}/", web::get().to(delete_tag));
+    config.route("/edit_tag_aliases/{id}/", web::post().to(edit_tag_aliases));
+    config.route("/merge_tag/{id}/", web::post().to(merge_tag));
+    config.route("/tags_autocomplete/", web::get().to(tags_autocomplete));
+}
+
+// старые адреса тегов (по названию, слитые и переименованные теги)
+// ведут на актуальный; остальное - страница 404
+async fn tag_redirect(req: HttpRequest, session: Session, prefix: &str, slug: &String) -> actix_web::Result<HttpResponse> {
+    match Tag::get_redirect_slug(slug) {
+        Some(new_slug) => {
+            let mut location = prefix.to_string() + &new_slug + &"/".to_string();
+            if !req.query_string().is_empty() {
+                location += &("?".to_string() + req.query_string());
+            }
+            Ok(HttpResponse::MovedPermanently()
+                .insert_header((actix_web::http::header::LOCATION, location))
+                .finish())
+        },
+        None => crate::views::not_found(req, session).await,
+    }
 }
 
 pub async fn create_tag_page(session: Session, req: HttpRequest) -> actix_web::Result<HttpResponse> {
@@ -70,6 +95,7 @@ pub async fn create_tag_page(session: Session, req: HttpRequest) -> actix_web::R
     else {
         let _connection = establish_connection();
         let all_tags = schema::tags::table
+            .filter(schema::tags::deleted_at.is_null())
             .load::<Tag>(&_connection)
             .expect("Error.");
 
@@ -133,18 +159,248 @@ pub async fn create_tag(session: Session, mut payload: Multipart) -> impl Respon
     return HttpResponse::Ok();
 }
 
+// несколько тегов сразу: /tags/rust+actix/ - все теги, /tags/rust,actix/ - любой
+pub async fn multi_tag_page(req: HttpRequest, session: Session, _slugs: web::Path<String>) -> actix_web::Result<HttpResponse> {
+    use crate::utils::get_device_and_ajax;
+    use crate::models::{Item, Blog, Service, Store, Wiki, Work, Help};
+
+    let (slugs, is_and) = Tag::parse_slugs(&_slugs);
+    let _tags = Tag::get_tags_with_slugs(&slugs);
+    if _tags.is_empty() || _tags.len() != slugs.len() || _tags.len() > 5 {
+        return crate::views::not_found(req, session).await;
+    }
+    if _tags.len() == 1 {
+        return Ok(HttpResponse::MovedPermanently()
+            .insert_header((actix_web::http::header::LOCATION, "/tag/".to_string() + &_tags[0].slug + &"/".to_string()))
+            .finish());
+    }
+
+    let (t, l) = get_all_storage();
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let names = _tags.iter().map(|t| t.name.clone()).collect::<Vec<String>>().join(if is_and { " + " } else { " / " });
+    let and_path = slugs.join("+");
+    let or_path = slugs.join(",");
+    if is_ajax == 0 {
+        let path = if is_and { &and_path } else { &or_path };
+        return get_first_load_page (
+            &session,
+            is_desctop,
+            names.clone() + &" | Теги".to_string(),
+            names.clone() + &" | вебсервисы.рф: Теги".to_string(),
+            "/tags/".to_string() + path + &"/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await;
+    }
+
+    let _viewer = get_viewer(&session);
+    let tag_ids: Vec<i32> = _tags.iter().map(|t| t.id).collect();
+    let _items = Tag::get_items_for_tags(&tag_ids, is_and, _viewer.as_ref());
+    let item_ids: Vec<i32> = _items.iter().map(|i| i.0).collect();
+    let stack = |types: i16| _items.iter().filter(|i| i.1 == types).map(|i| i.0).collect::<Vec<i32>>();
+    let (blog_stack, service_stack, store_stack) = (stack(1), stack(2), stack(3));
+    let (wiki_stack, work_stack, help_stack) = (stack(4), stack(5), stack(6));
+
+    let blogs_count = blog_stack.len();
+    let services_count = service_stack.len();
+    let stores_count = store_stack.len();
+    let wikis_count = wiki_stack.len();
+    let works_count = work_stack.len();
+    let helps_count = help_stack.len();
+
+    let _blogs = Item::get_blogs_for_ids(20, 0, &blog_stack, _viewer.as_ref());
+    let _services = Item::get_services_for_ids(20, 0, &service_stack, _viewer.as_ref());
+    let _stores = Item::get_stores_for_ids(20, 0, &store_stack, _viewer.as_ref());
+    let _wikis = Item::get_wikis_for_ids(20, 0, &wiki_stack, _viewer.as_ref());
+    let _works = Item::get_works_for_ids(20, 0, &work_stack, _viewer.as_ref());
+    let _helps = Item::get_helps_for_ids(20, 0, &help_stack, _viewer.as_ref());
+    let _related = Tag::get_related_tags(&tag_ids, &item_ids, 15);
+
+    if is_signed_in(&session) && is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/tags/multi_tag.stpl")]
+        struct Template {
+            request_user:   User,
+            tags:           Vec<Tag>,
+            names:          String,
+            is_and:         bool,
+            and_path:       String,
+            or_path:        String,
+            related_tags:   Vec<SmallTag>,
+            works_list:     Vec<Work>,
+            services_list:  Vec<Service>,
+            wikis_list:     Vec<Wiki>,
+            blogs_list:     Vec<Blog>,
+            stores_list:    Vec<Store>,
+            helps_list:     Vec<Help>,
+
+            works_count:    usize,
+            services_count: usize,
+            wikis_count:    usize,
+            blogs_count:    usize,
+            stores_count:   usize,
+            helps_count:    usize,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+            prices:         PriceFormat,
+        }
+        let body = Template {
+            request_user:   get_request_user_data(&session),
+            tags:           _tags,
+            names:          names,
+            is_and:         is_and,
+            and_path:       and_path,
+            or_path:        or_path,
+            related_tags:   _related,
+            works_list:     _works,
+            services_list:  _services,
+            wikis_list:     _wikis,
+            blogs_list:     _blogs,
+            stores_list:    _stores,
+            helps_list:     _helps,
+
+            works_count:    works_count,
+            services_count: services_count,
+            wikis_count:    wikis_count,
+            blogs_count:    blogs_count,
+            stores_count:   stores_count,
+            helps_count:    helps_count,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+            prices:         PriceFormat::get(&req, l),
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/tags/anon_multi_tag.stpl")]
+        struct Template {
+            tags:           Vec<Tag>,
+            names:          String,
+            is_and:         bool,
+            and_path:       String,
+            or_path:        String,
+            related_tags:   Vec<SmallTag>,
+            works_list:     Vec<Work>,
+            services_list:  Vec<Service>,
+            wikis_list:     Vec<Wiki>,
+            blogs_list:     Vec<Blog>,
+            stores_list:    Vec<Store>,
+            helps_list:     Vec<Help>,
+
+            works_count:    usize,
+            services_count: usize,
+            wikis_count:    usize,
+            blogs_count:    usize,
+            stores_count:   usize,
+            helps_count:    usize,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+            prices:         PriceFormat,
+        }
+        let body = Template {
+            tags:           _tags,
+            names:          names,
+            is_and:         is_and,
+            and_path:       and_path,
+            or_path:        or_path,
+            related_tags:   _related,
+            works_list:     _works,
+            services_list:  _services,
+            wikis_list:     _wikis,
+            blogs_list:     _blogs,
+            stores_list:    _stores,
+            helps_list:     _helps,
+
+            works_count:    works_count,
+            services_count: services_count,
+            wikis_count:    wikis_count,
+            blogs_count:    blogs_count,
+            stores_count:   stores_count,
+            helps_count:    helps_count,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+            prices:         PriceFormat::get(&req, l),
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/tags/multi_tag.stpl")]
+        struct Template {
+            tags:           Vec<Tag>,
+            names:          String,
+            is_and:         bool,
+            and_path:       String,
+            or_path:        String,
+            related_tags:   Vec<SmallTag>,
+            works_list:     Vec<Work>,
+            services_list:  Vec<Service>,
+            wikis_list:     Vec<Wiki>,
+            blogs_list:     Vec<Blog>,
+            stores_list:    Vec<Store>,
+            helps_list:     Vec<Help>,
+
+            works_count:    usize,
+            services_count: usize,
+            wikis_count:    usize,
+            blogs_count:    usize,
+            stores_count:   usize,
+            helps_count:    usize,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+        }
+        let body = Template {
+            tags:           _tags,
+            names:          names,
+            is_and:         is_and,
+            and_path:       and_path,
+            or_path:        or_path,
+            related_tags:   _related,
+            works_list:     _works,
+            services_list:  _services,
+            wikis_list:     _wikis,
+            blogs_list:     _blogs,
+            stores_list:    _stores,
+            helps_list:     _helps,
+
+            works_count:    works_count,
+            services_count: services_count,
+            wikis_count:    wikis_count,
+            blogs_count:    blogs_count,
+            stores_count:   stores_count,
+            helps_count:    helps_count,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+}
+
 pub async fn tag_page(req: HttpRequest, session: Session, _id: web::Path<String>) -> actix_web::Result<HttpResponse> {
     use crate::utils::get_device_and_ajax;
-    use schema::tags::dsl::tags;
 
     let _connection = establish_connection();
     let (t, l) = get_all_storage();
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
     let slug = _id.to_string();
-    let _tag = tags
-        .filter(schema::tags::name.eq(&slug))
-        .first::<Tag>(&_connection)
-        .expect("E");
+    let _tag = match Tag::get_tag_with_slug(&slug) {
+        Some(_tag) => _tag,
+        None => return tag_redirect(req, session, "/tag/", &slug).await,
+    };
 
     if is_ajax == 0 {
         get_first_load_page (
@@ -186,14 +442,13 @@ pub async fn tag_page(req: HttpRequest, session: Session, _id: web::Path<String>
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let is_admin = _request_user.is_superuser();
 
-            let _blogs = Item::get_blogs_for_ids(3, 0, &blog_stack, is_admin);
-            let _services = Item::get_services_for_ids(3, 0, &service_stack, is_admin);
-            let _stores = Item::get_stores_for_ids(3, 0, &store_stack, is_admin);
-            let _wikis = Item::get_wikis_for_ids(3, 0, &wiki_stack, is_admin);
-            let _works = Item::get_works_for_ids(3, 0, &work_stack, is_admin);
-            let _helps = Item::get_helps_for_ids(3, 0, &help_stack, is_admin);
+            let _blogs = Item::get_blogs_for_ids(3, 0, &blog_stack, Some(&_request_user));
+            let _services = Item::get_services_for_ids(3, 0, &service_stack, Some(&_request_user));
+            let _stores = Item::get_stores_for_ids(3, 0, &store_stack, Some(&_request_user));
+            let _wikis = Item::get_wikis_for_ids(3, 0, &wiki_stack, Some(&_request_user));
+            let _works = Item::get_works_for_ids(3, 0, &work_stack, Some(&_request_user));
+            let _helps = Item::get_helps_for_ids(3, 0, &help_stack, Some(&_request_user));
 
             let blogs_count = _blogs.len();
             let services_count = _services.len();
@@ -224,6 +479,7 @@ pub async fn tag_page(req: HttpRequest, session: Session, _id: web::Path<String>
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     tag:            _tag,
@@ -244,6 +500,7 @@ pub async fn tag_page(req: HttpRequest, session: Session, _id: web::Path<String>
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -296,12 +553,12 @@ pub async fn tag_page(req: HttpRequest, session: Session, _id: web::Path<String>
             }
         }
         else {
-            let _blogs = Item::get_blogs_for_ids(3, 0, &blog_stack, false);
-            let _services = Item::get_services_for_ids(3, 0, &service_stack, false);
-            let _stores = Item::get_stores_for_ids(3, 0, &store_stack, false);
-            let _wikis = Item::get_wikis_for_ids(3, 0, &wiki_stack, false);
-            let _works = Item::get_works_for_ids(3, 0, &work_stack, false);
-            let _helps = Item::get_helps_for_ids(3, 0, &help_stack, false);
+            let _blogs = Item::get_blogs_for_ids(3, 0, &blog_stack, None);
+            let _services = Item::get_services_for_ids(3, 0, &service_stack, None);
+            let _stores = Item::get_stores_for_ids(3, 0, &store_stack, None);
+            let _wikis = Item::get_wikis_for_ids(3, 0, &wiki_stack, None);
+            let _works = Item::get_works_for_ids(3, 0, &work_stack, None);
+            let _helps = Item::get_helps_for_ids(3, 0, &help_stack, None);
 
             let blogs_count = _blogs.len();
             let services_count = _services.len();
@@ -331,6 +588,7 @@ pub async fn tag_page(req: HttpRequest, session: Session, _id: web::Path<String>
                     is_ajax:        i32,
                     template_types: u8,
                     linguage:       u8,
+                    prices:         PriceFormat,
                 }
                 let body = Template {
                     tag:            _tag,
@@ -350,6 +608,7 @@ pub async fn tag_page(req: HttpRequest, session: Session, _id: web::Path<String>
                     is_ajax:        is_ajax,
                     template_types: t,
                     linguage:       l,
+                    prices:         PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -409,7 +668,10 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
     let _connection = establish_connection();
     let (t, l) = get_all_storage();
     let slug = _id.to_string();
-    let _tag = Tag::get_tag_with_slug(&slug); 
+    let _tag = match Tag::get_tag_with_slug(&slug) {
+        Some(_tag) => _tag,
+        None => return tag_redirect(req, session, "/tag_blogs/", &slug).await,
+    };
 
     if is_ajax == 0 {
         get_first_load_page (
@@ -429,6 +691,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
         use crate::utils::get_page;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
 
         let _tag_items = tags_items
             .filter(schema::tags_items::tag_id.eq(&_tag.id))
@@ -440,7 +703,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
 
-            let (_blogs, next_page_number) = Item::get_blogs_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser());
+            let (_blogs, next_page_number) = Item::get_blogs_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser(), &filter);
             let blog_count = _blogs.len();
 
             if is_desctop {
@@ -450,6 +713,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
                     request_user:     User,
                     tag:              Tag,
                     blogs_list:       Vec<Blog>,
+                    filter:           ListFilter,
                     blogs_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -460,6 +724,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
                     request_user:     _request_user,
                     tag:              _tag,
                     blogs_list:       _blogs,
+                    filter:           filter,
                     blogs_count:      blog_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -476,6 +741,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     blogs_list:       Vec<Blog>,
+                    filter:           ListFilter,
                     blogs_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -485,6 +751,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     blogs_list:       _blogs,
+                    filter:           filter,
                     blogs_count:      blog_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -497,7 +764,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
             }
         }
         else {
-            let (_blogs, next_page_number) = Item::get_blogs_list_for_ids(page, 20, &_tag_items, false);
+            let (_blogs, next_page_number) = Item::get_blogs_list_for_ids(page, 20, &_tag_items, false, &filter);
             let blog_count = _blogs.len();
 
             if is_desctop {
@@ -506,6 +773,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     blogs_list:       Vec<Blog>,
+                    filter:           ListFilter,
                     blogs_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -515,6 +783,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     blogs_list:       _blogs,
+                    filter:           filter,
                     blogs_count:      blog_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -531,6 +800,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     blogs_list:       Vec<Blog>,
+                    filter:           ListFilter,
                     blogs_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -540,6 +810,7 @@ pub async fn tag_blogs_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     blogs_list:       _blogs,
+                    filter:           filter,
                     blogs_count:      blog_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -560,7 +831,10 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
     let _connection = establish_connection();
     let (t, l) = get_all_storage();
     let slug = _id.to_string();
-    let _tag = Tag::get_tag_with_slug(&slug); 
+    let _tag = match Tag::get_tag_with_slug(&slug) {
+        Some(_tag) => _tag,
+        None => return tag_redirect(req, session, "/tag_services/", &slug).await,
+    };
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
 
     if is_ajax == 0 {
@@ -581,6 +855,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
         use crate::utils::get_page;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
         let _tag_items = tags_items
             .filter(schema::tags_items::tag_id.eq(&_tag.id))
             .filter(schema::tags_items::types.eq(2))
@@ -590,7 +865,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let (_services, next_page_number) = Item::get_services_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser());
+            let (_services, next_page_number) = Item::get_services_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser(), &filter);
             let service_count = _services.len();
 
             if is_desctop {
@@ -600,6 +875,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
                     request_user:     User,
                     tag:              Tag,
                     services_list:    Vec<Service>,
+                    filter:           ListFilter,
                     services_count:   usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -610,6 +886,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
                     request_user:     _request_user,
                     tag:              _tag,
                     services_list:    _services,
+                    filter:           filter,
                     services_count:   service_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -626,6 +903,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
                 struct Template {
                     tag:              Tag,
                     services_list:    Vec<Service>,
+                    filter:           ListFilter,
                     services_count:   usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -635,6 +913,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
                 let body = Template {
                     tag:              _tag,
                     services_list:    _services,
+                    filter:           filter,
                     services_count:   service_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -647,7 +926,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
             }
         }
         else {
-            let (_services, next_page_number) = Item::get_services_list_for_ids(page, 20, &_tag_items, false);
+            let (_services, next_page_number) = Item::get_services_list_for_ids(page, 20, &_tag_items, false, &filter);
             let service_count = _services.len();
 
             if is_desctop {
@@ -656,6 +935,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
                 struct Template {
                     tag:              Tag,
                     services_list:    Vec<Service>,
+                    filter:           ListFilter,
                     services_count:   usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -665,6 +945,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
                 let body = Template {
                     tag:              _tag,
                     services_list:    _services,
+                    filter:           filter,
                     services_count:   service_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -681,6 +962,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
                 struct Template {
                     tag:              Tag,
                     services_list:    Vec<Service>,
+                    filter:           ListFilter,
                     services_count:   usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -690,6 +972,7 @@ pub async fn tag_services_page(session: Session, req: HttpRequest, _id: web::Pat
                 let body = Template {
                     tag:              _tag,
                     services_list:    _services,
+                    filter:           filter,
                     services_count:   service_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -710,7 +993,10 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
     let _connection = establish_connection();
     let (t, l) = get_all_storage();
     let slug = _id.to_string();
-    let _tag = Tag::get_tag_with_slug(&slug); 
+    let _tag = match Tag::get_tag_with_slug(&slug) {
+        Some(_tag) => _tag,
+        None => return tag_redirect(req, session, "/tag_stores/", &slug).await,
+    };
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
 
     if is_ajax == 0 {
@@ -731,6 +1017,7 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
         use crate::utils::get_page;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
 
         let _tag_items = tags_items
             .filter(schema::tags_items::tag_id.eq(&_tag.id))
@@ -741,7 +1028,7 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let (_stores, next_page_number) = Item::get_stores_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser());
+            let (_stores, next_page_number) = Item::get_stores_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser(), &filter);
             let stores_count = _stores.len();
 
             if is_desctop {
@@ -751,21 +1038,25 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
                     request_user:     User,
                     tag:              Tag,
                     stores_list:      Vec<Store>,
+                    filter:           ListFilter,
                     stores_count:     usize,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
                     linguage:         u8,
+                    prices:           PriceFormat,
                 }
                 let body = Template {
                     request_user:     _request_user,
                     tag:              _tag,
                     stores_list:      _stores,
+                    filter:           filter,
                     stores_count:     stores_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
                     linguage:         l,
+                    prices:           PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -777,6 +1068,7 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
                 struct Template {
                     tag:              Tag,
                     stores_list:      Vec<Store>,
+                    filter:           ListFilter,
                     stores_count:     usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -786,6 +1078,7 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
                 let body = Template {
                     tag:              _tag,
                     stores_list:      _stores,
+                    filter:           filter,
                     stores_count:     stores_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -798,7 +1091,7 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
             }
         }
         else {
-            let (_stores, next_page_number) = Item::get_stores_list_for_ids(page, 20, &_tag_items, false);
+            let (_stores, next_page_number) = Item::get_stores_list_for_ids(page, 20, &_tag_items, false, &filter);
             let stores_count = _stores.len();
 
             if is_desctop {
@@ -807,20 +1100,24 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
                 struct Template {
                     tag:              Tag,
                     stores_list:      Vec<Store>,
+                    filter:           ListFilter,
                     stores_count:     usize,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
                     linguage:         u8,
+                    prices:           PriceFormat,
                 }
                 let body = Template {
                     tag:              _tag,
                     stores_list:      _stores,
+                    filter:           filter,
                     stores_count:     stores_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
                     linguage:         l,                
+                    prices:           PriceFormat::get(&req, l),
                 }
                 .render_once()
                 .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
@@ -832,6 +1129,7 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
                 struct Template {
                     tag:              Tag,
                     stores_list:      Vec<Store>,
+                    filter:           ListFilter,
                     stores_count:     usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -841,6 +1139,7 @@ pub async fn tag_stores_page(session: Session, req: HttpRequest, _id: web::Path<
                 let body = Template {
                     tag:              _tag,
                     stores_list:      _stores,
+                    filter:           filter,
                     stores_count:     stores_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -861,7 +1160,10 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
     let _connection = establish_connection();
     let (t, l) = get_all_storage();
     let slug = _id.to_string();
-    let _tag = Tag::get_tag_with_slug(&slug); 
+    let _tag = match Tag::get_tag_with_slug(&slug) {
+        Some(_tag) => _tag,
+        None => return tag_redirect(req, session, "/tag_wikis/", &slug).await,
+    };
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
 
     if is_ajax == 0 {
@@ -882,6 +1184,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
         use crate::utils::get_page;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
 
         let _tag_items = tags_items
             .filter(schema::tags_items::tag_id.eq(&_tag.id))
@@ -892,7 +1195,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let (_wikis, next_page_number) = Item::get_wikis_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser());
+            let (_wikis, next_page_number) = Item::get_wikis_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser(), &filter);
             let wikis_count = _wikis.len();
 
             if is_desctop {
@@ -902,6 +1205,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
                     request_user:     User,
                     tag:              Tag,
                     wikis_list:       Vec<Wiki>,
+                    filter:           ListFilter,
                     wikis_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -912,6 +1216,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
                     request_user:     _request_user,
                     tag:              _tag,
                     wikis_list:       _wikis,
+                    filter:           filter,
                     wikis_count:      wikis_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -928,6 +1233,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     wikis_list:       Vec<Wiki>,
+                    filter:           ListFilter,
                     wikis_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -937,6 +1243,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     wikis_list:       _wikis,
+                    filter:           filter,
                     wikis_count:      wikis_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -949,7 +1256,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
             }
         }
         else {
-            let (_wikis, next_page_number) = Item::get_wikis_list_for_ids(page, 20, &_tag_items, false);
+            let (_wikis, next_page_number) = Item::get_wikis_list_for_ids(page, 20, &_tag_items, false, &filter);
             let wikis_count = _wikis.len();
 
             if is_desctop {
@@ -958,6 +1265,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     wikis_list:       Vec<Wiki>,
+                    filter:           ListFilter,
                     wikis_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -967,6 +1275,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     wikis_list:       _wikis,
+                    filter:           filter,
                     wikis_count:      wikis_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -983,6 +1292,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     wikis_list:       Vec<Wiki>,
+                    filter:           ListFilter,
                     wikis_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -992,6 +1302,7 @@ pub async fn tag_wikis_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     wikis_list:       _wikis,
+                    filter:           filter,
                     wikis_count:      wikis_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -1012,7 +1323,10 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
     let _connection = establish_connection();
     let (t, l) = get_all_storage();
     let slug = _id.to_string();
-    let _tag = Tag::get_tag_with_slug(&slug); 
+    let _tag = match Tag::get_tag_with_slug(&slug) {
+        Some(_tag) => _tag,
+        None => return tag_redirect(req, session, "/tag_works/", &slug).await,
+    };
     let (is_desctop, is_ajax) = get_device_and_ajax(&req);
 
     if is_ajax == 0 {
@@ -1033,6 +1347,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
         use crate::utils::get_page;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
 
         let _tag_items = tags_items
             .filter(schema::tags_items::tag_id.eq(&_tag.id))
@@ -1043,7 +1358,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let (_works, next_page_number) = Item::get_works_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser());
+            let (_works, next_page_number) = Item::get_works_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser(), &filter);
             let works_count = _works.len();
 
             if is_desctop {
@@ -1053,6 +1368,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
                     request_user:     User,
                     tag:              Tag,
                     works_list:       Vec<Work>,
+                    filter:           ListFilter,
                     works_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -1063,6 +1379,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
                     request_user:     _request_user,
                     tag:              _tag,
                     works_list:       _works,
+                    filter:           filter,
                     works_count:      works_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -1079,6 +1396,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     works_list:       Vec<Work>,
+                    filter:           ListFilter,
                     works_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -1088,6 +1406,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     works_list:       _works,
+                    filter:           filter,
                     works_count:      works_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -1101,7 +1420,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
             }
         }
         else {
-            let (_works, next_page_number) = Item::get_works_list_for_ids(page, 20, &_tag_items, false);
+            let (_works, next_page_number) = Item::get_works_list_for_ids(page, 20, &_tag_items, false, &filter);
             let works_count = _works.len();
 
             if is_desctop {
@@ -1110,6 +1429,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     works_list:       Vec<Work>,
+                    filter:           ListFilter,
                     works_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -1119,6 +1439,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     works_list:       _works,
+                    filter:           filter,
                     works_count:      works_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -1135,6 +1456,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
                 struct Template {
                     tag:              Tag,
                     works_list:       Vec<Work>,
+                    filter:           ListFilter,
                     works_count:      usize,
                     next_page_number: i32,
                     is_ajax:          i32,
@@ -1144,6 +1466,7 @@ pub async fn tag_works_page(session: Session, req: HttpRequest, _id: web::Path<S
                 let body = Template {
                     tag:              _tag,
                     works_list:       _works,
+                    filter:           filter,
                     works_count:      works_count,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
@@ -1162,7 +1485,10 @@ pub async fn tag_helps_page(session: Session, req: HttpRequest, _id: web::Path<S
     let _connection = establish_connection();
     let (t, l) = get_all_storage();
     let slug = _id.to_string();
-    let _tag = Tag::get_tag_with_slug(&slug); 
+    let _tag = match Tag::get_tag_with_slug(&slug) {
+        Some(_tag) => _tag,
+        None => return tag_redirect(req, session, "/tag_helps/", &slug).await,
+    };
     let (is_desctop, is_ajax) = crate::utils::get_device_and_ajax(&req);
 
     if is_ajax == 0 {
@@ -1193,7 +1519,7 @@ pub async fn tag_helps_page(session: Session, req: HttpRequest, _id: web::Path<S
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let (_helps, next_page_number) = Item::get_helps_list_for_ids(page, 20, &_tag_items, _request_user.is_superuser());
+            let (_helps, next_page_number) = Item::get_helps_list_for_ids(page, 20, &_tag_items, Some(&_request_user));
             let helps_count = _helps.len();
 
             if is_desctop {
@@ -1250,7 +1576,7 @@ pub async fn tag_helps_page(session: Session, req: HttpRequest, _id: web::Path<S
             }
         }
         else {
-            let (_helps, next_page_number) = Item::get_helps_list_for_ids(page, 20, &_tag_items, false);
+            let (_helps, next_page_number) = Item::get_helps_list_for_ids(page, 20, &_tag_items, None);
             let helps_count = _helps.len();
 
             if is_desctop {
@@ -1538,9 +1864,81 @@ pub async fn delete_tag(session: Session, _id: web::Path<i32>) -> impl Responder
         let _request_user = get_request_user_data(&session);
         if _request_user.perm == 60 {
             let _connection = establish_connection();
-            diesel::delete(schema::tags_items::table.filter(schema::tags_items::tag_id.eq(*_id))).execute(&_connection).expect("E");
-            diesel::delete(schema::tags::table.filter(schema::tags::id.eq(*_id))).execute(&_connection).expect("E");
+            let _tag = schema::tags::table
+                .filter(schema::tags::id.eq(*_id))
+                .first::<Tag>(&_connection)
+                .expect("E");
+            _tag.soft_delete();
         }
     }
     HttpResponse::Ok()
 }
+
+pub async fn edit_tag_aliases(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _tag = schema::tags::table
+                .filter(schema::tags::id.eq(*_id))
+                .first::<Tag>(&_connection);
+            if let Ok(_tag) = _tag {
+                let form = crate::utils::tag_aliases_form(payload.borrow_mut()).await;
+                if _tag.set_aliases(&form.aliases) {
+                    return HttpResponse::Ok().body("ok");
+                }
+                return HttpResponse::Ok().body("Название уже занято другим тегом");
+            }
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+// слияние дублей: связи с элементами переходят к target, адрес тега - редирект
+pub async fn merge_tag(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _tag = schema::tags::table
+                .filter(schema::tags::id.eq(*_id))
+                .first::<Tag>(&_connection);
+            if let Ok(_tag) = _tag {
+                let form = crate::utils::tag_merge_form(payload.borrow_mut()).await;
+                match Tag::get_tag_with_slug(&form.target) {
+                    Some(target) => {
+                        if _tag.merge_into(&target) {
+                            return HttpResponse::Ok().body("ok");
+                        }
+                        return HttpResponse::Ok().body("Не удалось слить теги");
+                    },
+                    None => return HttpResponse::Ok().body("Тег не найден"),
+                }
+            }
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+// подсказки тегов для форм объектов: /tags_autocomplete/?q=ru
+pub async fn tags_autocomplete(session: Session, req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    #[derive(Debug, serde::Deserialize)]
+    struct Params {
+        pub q: Option<String>,
+    }
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let q = match web::Query::<Params>::from_query(&req.query_string()) {
+                Ok(params) => params.q.clone().unwrap_or_default(),
+                Err(_) => "".to_string(),
+            };
+            if q.trim().is_empty() {
+                return Ok(HttpResponse::Ok().json(Vec::<crate::models::TagHint>::new()));
+            }
+            let list = web::block(move || Tag::search(&q, 10)).await?;
+            return Ok(HttpResponse::Ok().json(list));
+        }
+    }
+    Ok(HttpResponse::Forbidden().json("Permission Denied"))
+}
@@ -0,0 +1,142 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    error::InternalError,
+    http::StatusCode,
+    Responder,
+};
+use actix_multipart::Multipart;
+use std::borrow::BorrowMut;
+use actix_session::Session;
+use crate::utils::{
+    is_signed_in,
+    get_request_user_data,
+    get_first_load_page,
+    get_all_storage,
+    get_device_and_ajax,
+    timeline_settings_form,
+    holiday_form,
+};
+use crate::models::{
+    User,
+    TimelineSettings,
+    Holiday,
+};
+use sailfish::TemplateOnce;
+
+
+pub fn timeline_routes(config: &mut web::ServiceConfig) {
+    config.route("/timeline/", web::get().to(timeline_page));
+    config.route("/edit_timeline_settings/", web::post().to(edit_timeline_settings));
+    config.route("/create_holiday/", web::post().to(create_holiday));
+    config.route("/delete_holiday/{id}/", web::get().to(delete_holiday));
+}
+
+fn is_admin(session: &Session) -> bool {
+    is_signed_in(session) && get_request_user_data(session).perm == 60
+}
+
+// настройки оценки сроков и нерабочие дни (только админ)
+pub async fn timeline_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+    if is_ajax == 0 {
+        return get_first_load_page (
+            &session,
+            is_desctop,
+            "Сроки работ".to_string(),
+            "вебсервисы.рф: Сроки работ".to_string(),
+            "/timeline/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await;
+    }
+    if !is_admin(&session) {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+
+    let settings = TimelineSettings::get();
+    let holidays_list = Holiday::get_all();
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/timeline.stpl")]
+        struct Template {
+            request_user:   User,
+            settings:       TimelineSettings,
+            holidays_list:  Vec<Holiday>,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+        }
+        let body = Template {
+            request_user:   get_request_user_data(&session),
+            settings:       settings,
+            holidays_list:  holidays_list,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/timeline.stpl")]
+        struct Template {
+            settings:       TimelineSettings,
+            holidays_list:  Vec<Holiday>,
+            is_ajax:        i32,
+            template_types: u8,
+            linguage:       u8,
+        }
+        let body = Template {
+            settings:       settings,
+            holidays_list:  holidays_list,
+            is_ajax:        is_ajax,
+            template_types: t,
+            linguage:       l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+}
+
+pub async fn edit_timeline_settings(session: Session, mut payload: Multipart) -> impl Responder {
+    if is_admin(&session) {
+        let form = timeline_settings_form(payload.borrow_mut()).await;
+        if form.workers < 1 || form.hours_per_day < 1 || form.hours_per_day > 24 || form.start_delay < 0 {
+            return HttpResponse::Ok().body("Людей - от 1, часов в день - от 1 до 24");
+        }
+        TimelineSettings::get().edit(form);
+        return HttpResponse::Ok().body("ok");
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn create_holiday(session: Session, mut payload: Multipart) -> impl Responder {
+    if is_admin(&session) {
+        let form = holiday_form(payload.borrow_mut()).await;
+        if form.day.is_none() {
+            return HttpResponse::Ok().body("Укажите дату");
+        }
+        if Holiday::create(form).is_none() {
+            return HttpResponse::Ok().body("Этот день уже нерабочий");
+        }
+        return HttpResponse::Ok().body("ok");
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn delete_holiday(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_admin(&session) {
+        if let Some(_holiday) = Holiday::get_with_id(*_id) {
+            _holiday.delete();
+            return HttpResponse::Ok().body("ok");
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
@@ -0,0 +1,78 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    web::block,
+};
+use std::borrow::BorrowMut;
+use crate::utils::{
+    is_signed_in,
+    get_request_user_data,
+    export_items,
+    items_to_csv,
+    import_transfer_data,
+};
+use actix_session::Session;
+use actix_multipart::Multipart;
+use serde::Deserialize;
+
+
+pub fn transfer_routes(config: &mut web::ServiceConfig) {
+    config.route("/export_items/", web::get().to(export_items_page));
+    config.route("/import_items/", web::post().to(import_items_page));
+}
+
+// выгрузка каталога: /export_items/?format=csv&types=2
+// без format - json, без types - все элементы.
+pub async fn export_items_page(session: Session, req: HttpRequest) -> actix_web::Result<HttpResponse> {
+    #[derive(Debug, Deserialize)]
+    struct Params {
+        pub format: Option<String>,
+        pub types:  Option<i16>,
+    }
+    if !is_signed_in(&session) {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+    let _request_user = get_request_user_data(&session);
+    if _request_user.perm != 60 {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+
+    let params_some = web::Query::<Params>::from_query(&req.query_string());
+    let (format, types) = match params_some {
+        Ok(params) => (params.format.clone().unwrap_or("json".to_string()), params.types),
+        Err(_) => ("json".to_string(), None),
+    };
+
+    let list = block(move || export_items(types)).await?;
+    if format == "csv" {
+        Ok(HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header(("Content-Disposition", "attachment; filename=\"items.csv\""))
+            .body(items_to_csv(&list)))
+    }
+    else {
+        Ok(HttpResponse::Ok()
+            .insert_header(("Content-Disposition", "attachment; filename=\"items.json\""))
+            .json(list))
+    }
+}
+
+// загрузка каталога. поля формы: file, format (json / csv), dry_run.
+// в ответ - отчет: сколько создано, обновлено и ошибки по строкам.
+pub async fn import_items_page(session: Session, mut payload: Multipart) -> actix_web::Result<HttpResponse> {
+    if !is_signed_in(&session) {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+    let _request_user = get_request_user_data(&session);
+    if _request_user.perm != 60 {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+
+    let form = crate::utils::import_form(payload.borrow_mut()).await;
+    let user_id = _request_user.id;
+    let report = block(move || {
+        import_transfer_data(&form.data, &form.format, user_id, form.dry_run)
+    }).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
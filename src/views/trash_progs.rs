@@ -0,0 +1,206 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    web::block,
+    error::InternalError,
+    http::StatusCode,
+    Responder,
+};
+use crate::schema;
+use crate::diesel::{
+    RunQueryDsl,
+    ExpressionMethods,
+    QueryDsl,
+};
+use crate::utils::{
+    establish_connection,
+    is_signed_in,
+    get_request_user_data,
+    get_first_load_page,
+    get_all_storage,
+    get_device_and_ajax,
+    get_trash_retention_days,
+    get_trash_items,
+    get_trash_categories,
+    get_trash_tags,
+    get_trash_files,
+    purge_trash,
+};
+use actix_session::Session;
+use sailfish::TemplateOnce;
+use crate::models::{
+    User,
+    Item,
+    Categories,
+    Tag,
+    File,
+};
+
+
+pub fn trash_routes(config: &mut web::ServiceConfig) {
+    config.route("/trash/", web::get().to(trash_page));
+    config.route("/restore_item/{id}/", web::get().to(restore_item));
+    config.route("/restore_category/{id}/", web::get().to(restore_category));
+    config.route("/restore_tag/{id}/", web::get().to(restore_tag));
+    config.route("/restore_file/{id}/", web::get().to(restore_file));
+    config.route("/purge_trash/", web::post().to(purge_trash_now));
+}
+
+pub async fn trash_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+
+    if is_ajax == 0 {
+        get_first_load_page (
+            &session,
+            is_desctop,
+            "Корзина".to_string(),
+            "вебсервисы.рф: Корзина".to_string(),
+            "/trash/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await
+    }
+    else if !is_signed_in(&session) {
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
+    }
+    else {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm < 60 {
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"))
+        }
+        else if is_desctop {
+            #[derive(TemplateOnce)]
+            #[template(path = "desctop/pages/trash.stpl")]
+            struct Template {
+                request_user:    User,
+                is_ajax:         i32,
+                retention_days:  i64,
+                items_list:      Vec<Item>,
+                categories_list: Vec<Categories>,
+                tags_list:       Vec<Tag>,
+                files_list:      Vec<File>,
+                template_types:  u8,
+                linguage:        u8,
+            }
+            let body = Template {
+                request_user:    _request_user,
+                is_ajax:         is_ajax,
+                retention_days:  get_trash_retention_days(),
+                items_list:      get_trash_items(),
+                categories_list: get_trash_categories(),
+                tags_list:       get_trash_tags(),
+                files_list:      get_trash_files(),
+                template_types:  t,
+                linguage:        l,
+            }
+            .render_once()
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+        else {
+            #[derive(TemplateOnce)]
+            #[template(path = "mobile/pages/trash.stpl")]
+            struct Template {
+                is_ajax:         i32,
+                retention_days:  i64,
+                items_list:      Vec<Item>,
+                categories_list: Vec<Categories>,
+                tags_list:       Vec<Tag>,
+                files_list:      Vec<File>,
+                template_types:  u8,
+                linguage:        u8,
+            }
+            let body = Template {
+                is_ajax:         is_ajax,
+                retention_days:  get_trash_retention_days(),
+                items_list:      get_trash_items(),
+                categories_list: get_trash_categories(),
+                tags_list:       get_trash_tags(),
+                files_list:      get_trash_files(),
+                template_types:  t,
+                linguage:        l,
+            }
+            .render_once()
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+        }
+    }
+}
+
+pub async fn restore_item(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _item = schema::items::table
+                .filter(schema::items::id.eq(*_id))
+                .first::<Item>(&_connection)
+                .expect("E");
+            _item.restore();
+        }
+    }
+    HttpResponse::Ok()
+}
+
+pub async fn restore_category(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _category = schema::categories::table
+                .filter(schema::categories::id.eq(*_id))
+                .first::<Categories>(&_connection)
+                .expect("E");
+            _category.restore();
+        }
+    }
+    HttpResponse::Ok()
+}
+
+pub async fn restore_tag(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _tag = schema::tags::table
+                .filter(schema::tags::id.eq(*_id))
+                .first::<Tag>(&_connection)
+                .expect("E");
+            _tag.restore();
+        }
+    }
+    HttpResponse::Ok()
+}
+
+pub async fn restore_file(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _file = schema::files::table
+                .filter(schema::files::id.eq(*_id))
+                .first::<File>(&_connection)
+                .expect("E");
+            _file.restore();
+        }
+    }
+    HttpResponse::Ok()
+}
+
+// ручной запуск очистки корзины (сама очистка идет раз в сутки)
+pub async fn purge_trash_now(session: Session) -> actix_web::Result<HttpResponse> {
+    if !is_signed_in(&session) {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+    let _request_user = get_request_user_data(&session);
+    if _request_user.perm != 60 {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+    match block(move || purge_trash(get_trash_retention_days())).await? {
+        Ok(report) => Ok(HttpResponse::Ok().json(report)),
+        Err(e) => Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Ошибка очистки корзины: ".to_string() + &e.to_string())),
+    }
+}
@@ -0,0 +1,140 @@
+use actix_web::{
+    HttpRequest,
+    HttpResponse,
+    web,
+    error::InternalError,
+    http::StatusCode,
+    Responder,
+};
+use actix_multipart::Multipart;
+use std::borrow::BorrowMut;
+use actix_session::Session;
+use crate::utils::{
+    is_signed_in,
+    get_request_user_data,
+    get_first_load_page,
+    get_all_storage,
+    get_device_and_ajax,
+    user_group_form,
+};
+use crate::models::{
+    User,
+    UserGroup,
+};
+use sailfish::TemplateOnce;
+
+
+pub fn user_group_routes(config: &mut web::ServiceConfig) {
+    config.route("/user_groups/", web::get().to(user_groups_page));
+    config.route("/create_user_group/", web::post().to(create_user_group));
+    config.route("/edit_user_group/{id}/", web::post().to(edit_user_group));
+    config.route("/delete_user_group/{id}/", web::get().to(delete_user_group));
+}
+
+fn is_admin(session: &Session) -> bool {
+    is_signed_in(session) && get_request_user_data(session).perm == 60
+}
+
+// группы пользователей и их участники (только админ)
+pub async fn user_groups_page(req: HttpRequest, session: Session) -> actix_web::Result<HttpResponse> {
+    let (is_desctop, is_ajax) = get_device_and_ajax(&req);
+    let (t, l) = get_all_storage();
+    if is_ajax == 0 {
+        return get_first_load_page (
+            &session,
+            is_desctop,
+            "Группы пользователей".to_string(),
+            "вебсервисы.рф: Группы пользователей".to_string(),
+            "/user_groups/".to_string(),
+            "/static/images/dark/store.jpg".to_string(),
+            t,
+            l,
+        ).await;
+    }
+    if !is_admin(&session) {
+        return Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body("Permission Denied"));
+    }
+
+    let groups_list = UserGroup::get_all();
+    if is_desctop {
+        #[derive(TemplateOnce)]
+        #[template(path = "desctop/pages/user_groups.stpl")]
+        struct Template {
+            request_user:    User,
+            groups_list:     Vec<UserGroup>,
+            is_ajax:         i32,
+            template_types:  u8,
+            linguage:        u8,
+        }
+        let body = Template {
+            request_user:    get_request_user_data(&session),
+            groups_list:     groups_list,
+            is_ajax:         is_ajax,
+            template_types:  t,
+            linguage:        l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+    else {
+        #[derive(TemplateOnce)]
+        #[template(path = "mobile/pages/user_groups.stpl")]
+        struct Template {
+            groups_list:     Vec<UserGroup>,
+            is_ajax:         i32,
+            template_types:  u8,
+            linguage:        u8,
+        }
+        let body = Template {
+            groups_list:     groups_list,
+            is_ajax:         is_ajax,
+            template_types:  t,
+            linguage:        l,
+        }
+        .render_once()
+        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body))
+    }
+}
+
+pub async fn create_user_group(session: Session, mut payload: Multipart) -> impl Responder {
+    if is_admin(&session) {
+        let form = user_group_form(payload.borrow_mut()).await;
+        if form.name.trim().is_empty() {
+            return HttpResponse::Ok().body("Укажите название группы");
+        }
+        return match UserGroup::create(form) {
+            Ok(()) => HttpResponse::Ok().body("ok"),
+            Err(error) => HttpResponse::Ok().body(error),
+        };
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+pub async fn edit_user_group(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_admin(&session) {
+        if let Some(_group) = UserGroup::get_with_id(*_id) {
+            let form = user_group_form(payload.borrow_mut()).await;
+            if form.name.trim().is_empty() {
+                return HttpResponse::Ok().body("Укажите название группы");
+            }
+            return match _group.edit(form) {
+                Ok(()) => HttpResponse::Ok().body("ok"),
+                Err(error) => HttpResponse::Ok().body(error),
+            };
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
+
+// вместе с группой убираются ее участники и открытый ей доступ
+pub async fn delete_user_group(session: Session, _id: web::Path<i32>) -> impl Responder {
+    if is_admin(&session) {
+        if let Some(_group) = UserGroup::get_with_id(*_id) {
+            _group.delete();
+            return HttpResponse::Ok().body("ok");
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
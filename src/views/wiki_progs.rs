@@ -3,9 +3,12 @@ use actix_web::{
     web::block,
     HttpRequest,
     HttpResponse,
+    Responder,
     error::InternalError,
     http::StatusCode,
 };
+use std::borrow::BorrowMut;
+use actix_multipart::Multipart;
 
 use crate::utils::{
     establish_connection,
@@ -13,6 +16,8 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    get_list_filter,
+    ListFilter,
 };
 use actix_session::Session;
 use crate::schema;
@@ -28,6 +33,7 @@ use crate::models::{
     Cat,
     SmallTag,
     CatDetail,
+    WikiParent,
 };
 use sailfish::TemplateOnce;
 
@@ -36,6 +42,7 @@ pub fn wiki_routes(config: &mut web::ServiceConfig) {
     config.route("/wiki_categories/", web::get().to(wiki_categories_page));
     config.service(web::resource("/wiki/{cat_slug}/{wiki_slug}/").route(web::get().to(get_wiki_page)));
     config.service(web::resource("/wikis/{slug}/").route(web::get().to(wiki_category_page)));
+    config.route("/edit_wiki_parent/{id}/", web::post().to(edit_wiki_parent));
 }
 
 
@@ -51,14 +58,17 @@ pub async fn get_wiki_page(session: Session, req: HttpRequest, param: web::Path<
 
     let _item = items
         .filter(schema::items::slug.eq(&_item_id))
+        .filter(schema::items::deleted_at.is_null())
         .first::<Item>(&_connection)
         .expect("E");
+    let _key = crate::utils::get_share_key(&req);
+    let title = if _item.is_public() { _item.title.clone() } else { "Закрытая страница".to_string() };
     if is_ajax == 0 {
         get_first_load_page (
             &session,
             is_desctop,
-            _item.title.clone() + &" | Обучающая статья".to_string(),
-            _item.title.clone() + &" | Обучающая статья: вебсервисы.рф".to_string(),
+            title.clone() + &" | Обучающая статья".to_string(),
+            title.clone() + &" | Обучающая статья: вебсервисы.рф".to_string(),
             "/wiki/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
             _item.get_image(),
             t, 
@@ -83,13 +93,13 @@ pub async fn get_wiki_page(session: Session, req: HttpRequest, param: web::Path<
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            if !_item.is_active && _request_user.perm < 10 {
+            if !_item.is_visible(Some(&_request_user), _key.as_deref()) {
                 crate::utils::get_private_page (
                     is_ajax,
                     _request_user,
                     is_desctop,
-                    _item.title.clone() + &" | Обучающая статья".to_string(),
-                    _item.title.clone() + &" | Обучающая статья: вебсервисы.рф".to_string(),
+                    title.clone() + &" | Обучающая статья".to_string(),
+                    title.clone() + &" | Обучающая статья: вебсервисы.рф".to_string(),
                     "/wiki/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
                     _item.get_image(),
                     t, 
@@ -160,13 +170,13 @@ pub async fn get_wiki_page(session: Session, req: HttpRequest, param: web::Path<
             }
         }
         else {
-            if _item.is_active == false {
+            if !_item.is_visible(None, _key.as_deref()) {
                 use crate::utils::get_anon_private_page;
                 get_anon_private_page ( 
                     is_ajax,
                     is_desctop,
-                    _item.title.clone() + &" | Обучающая статья".to_string(),
-                    _item.title.clone() + &" | Обучающая статья: вебсервисы.рф".to_string(),
+                    title.clone() + &" | Обучающая статья".to_string(),
+                    title.clone() + &" | Обучающая статья: вебсервисы.рф".to_string(),
                     "/wiki/".to_string() + &_cat_id.to_string() + &"/".to_string() + &_item_id.to_string() + &"/".to_string(),
                     _item.get_image(),
                     t, 
@@ -247,6 +257,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
     let _category = categories
         .filter(schema::categories::slug.eq(&_cat_id))
         .filter(schema::categories::types.eq(4))
+        .filter(schema::categories::deleted_at.is_null())
         .select((
             schema::categories::name,
             schema::categories::slug,
@@ -286,6 +297,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
         use crate::models::Wiki;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
         let object_list: Vec<Wiki>;
         let next_page_number: i32;
         let _cats = block(move || Categories::get_categories_for_types(4, l)).await?;
@@ -293,7 +305,8 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let _res = block(move || Categories::get_wikis_list(_category.id, page, 20, _request_user.perm == 60, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_wikis_list(_category.id, page, 20, _request_user.perm == 60, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -307,6 +320,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Wiki>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -318,6 +332,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -335,6 +350,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Wiki>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -345,6 +361,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -356,7 +373,8 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
             }
         }
         else {
-            let _res = block(move || Categories::get_wikis_list(_category.id, page, 20, false, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_wikis_list(_category.id, page, 20, false, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -370,6 +388,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Wiki>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -380,6 +399,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -397,6 +417,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Wiki>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -407,6 +428,7 @@ pub async fn wiki_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -539,3 +561,25 @@ pub async fn wiki_categories_page(session: Session, req: HttpRequest) -> actix_w
         }
     }
 }
+
+// родитель статьи: id - статья, в форме slug родителя
+pub async fn edit_wiki_parent(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _item = schema::items::table
+                .filter(schema::items::id.eq(*_id))
+                .filter(schema::items::types.eq(4))
+                .first::<Item>(&_connection);
+            if _item.is_ok() {
+                let form = crate::utils::wiki_parent_form(payload.borrow_mut()).await;
+                if WikiParent::set_parent_slug(*_id, &form.parent) {
+                    return HttpResponse::Ok().body("ok");
+                }
+                return HttpResponse::Ok().body("Статья не найдена или получится цикл");
+            }
+        }
+    }
+    HttpResponse::Ok().body("Permission Denied")
+}
@@ -3,9 +3,12 @@ use actix_web::{
     web::block,
     HttpRequest,
     HttpResponse,
+    Responder,
     error::InternalError,
     http::StatusCode,
 };
+use std::borrow::BorrowMut;
+use actix_multipart::Multipart;
 
 use crate::utils::{
     establish_connection,
@@ -13,6 +16,8 @@ use crate::utils::{
     get_request_user_data,
     get_first_load_page,
     get_all_storage,
+    get_list_filter,
+    ListFilter,
 };
 use actix_session::Session;
 use crate::schema;
@@ -28,6 +33,7 @@ use crate::models::{
     Cat,
     SmallTag,
     CatDetail,
+    WorkCase,
 };
 use sailfish::TemplateOnce;
 
@@ -36,6 +42,7 @@ pub fn work_routes(config: &mut web::ServiceConfig) {
     config.route("/work_categories/", web::get().to(work_categories_page));
     config.service(web::resource("/work/{cat_slug}/{work_slug}/").route(web::get().to(get_work_page)));
     config.service(web::resource("/works/{slug}/").route(web::get().to(work_category_page)));
+    config.route("/edit_work_case/{id}/", web::post().to(edit_work_case));
 }
 
 
@@ -51,9 +58,11 @@ pub async fn get_work_page(session: Session, req: HttpRequest, param: web::Path<
 
     let _item = items
         .filter(schema::items::slug.eq(&_item_id))
+        .filter(schema::items::deleted_at.is_null())
         .first::<Item>(&_connection)
         .expect("E");
-    let title = _item.title.clone();
+    let _key = crate::utils::get_share_key(&req);
+    let title = if _item.is_public() { _item.title.clone() } else { "Закрытая страница".to_string() };
     if is_ajax == 0 {
         get_first_load_page (
             &session,
@@ -84,7 +93,7 @@ pub async fn get_work_page(session: Session, req: HttpRequest, param: web::Path<
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            if !_item.is_active && _request_user.perm < 10 {
+            if !_item.is_visible(Some(&_request_user), _key.as_deref()) {
                 crate::utils::get_private_page (
                     is_ajax,
                     _request_user,
@@ -157,7 +166,7 @@ pub async fn get_work_page(session: Session, req: HttpRequest, param: web::Path<
             }
         }
         else {
-            if !_item.is_active {
+            if !_item.is_visible(None, _key.as_deref()) {
                 use crate::utils::get_anon_private_page;
                 get_anon_private_page (
                     is_ajax,
@@ -239,6 +248,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
     let _category = categories
         .filter(schema::categories::slug.eq(&_cat_id))
         .filter(schema::categories::types.eq(5))
+        .filter(schema::categories::deleted_at.is_null())
         .select((
             schema::categories::name,
             schema::categories::slug,
@@ -278,6 +288,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
         use crate::models::Work;
 
         let page = get_page(&req);
+        let filter = get_list_filter(&req);
         let object_list: Vec<Work>;
         let next_page_number: i32;
         let _cats = block(move || Categories::get_categories_for_types(5, l)).await?;
@@ -285,7 +296,8 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
 
         if is_signed_in(&session) {
             let _request_user = get_request_user_data(&session);
-            let _res = block(move || Categories::get_works_list(_category.id, page, 20, _request_user.perm == 60, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_works_list(_category.id, page, 20, _request_user.perm == 60, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -298,6 +310,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     all_tags:         Vec<SmallTag>,
                     category:         CatDetail,
                     object_list:      Vec<Work>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -308,6 +321,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     all_tags:         _tags,
                     category:         _category,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -325,6 +339,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Work>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -335,6 +350,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -346,7 +362,8 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
             }
         }
         else {
-            let _res = block(move || Categories::get_works_list(_category.id, page, 20, false, l)).await?;
+            let _filter = filter.clone();
+            let _res = block(move || Categories::get_works_list(_category.id, page, 20, false, l, &_filter)).await?;
             let _dict = match _res {
                 Ok(_ok) => {object_list = _ok.0; next_page_number = _ok.1},
                 Err(_error) => {object_list = Vec::new(); next_page_number = 0},
@@ -359,6 +376,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     all_tags:         Vec<SmallTag>,
                     category:         CatDetail,
                     object_list:      Vec<Work>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -368,6 +386,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     all_tags:         _tags,
                     category:         _category,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -385,6 +404,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         CatDetail,
                     cats:             Vec<Cat>,
                     object_list:      Vec<Work>,
+                    filter:           ListFilter,
                     next_page_number: i32,
                     is_ajax:          i32,
                     template_types:   u8,
@@ -395,6 +415,7 @@ pub async fn work_category_page(session: Session, req: HttpRequest, _id: web::Pa
                     category:         _category,
                     cats:             _cats,
                     object_list:      object_list,
+                    filter:           filter,
                     next_page_number: next_page_number,
                     is_ajax:          is_ajax,
                     template_types:   t,
@@ -527,3 +548,22 @@ pub async fn work_categories_page(session: Session, req: HttpRequest) -> actix_w
         }
     }
 }
+
+// кейс работы: id - работа
+pub async fn edit_work_case(session: Session, mut payload: Multipart, _id: web::Path<i32>) -> impl Responder {
+    if is_signed_in(&session) {
+        let _request_user = get_request_user_data(&session);
+        if _request_user.perm == 60 {
+            let _connection = establish_connection();
+            let _item = schema::items::table
+                .filter(schema::items::id.eq(*_id))
+                .filter(schema::items::types.eq(5))
+                .first::<Item>(&_connection);
+            if _item.is_ok() {
+                let form = crate::utils::work_case_form(payload.borrow_mut()).await;
+                WorkCase::save(*_id, form);
+            }
+        }
+    }
+    HttpResponse::Ok()
+}
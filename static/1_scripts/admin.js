@@ -138,6 +138,17 @@ function send_category_data(form, url) {
   $input.classList.add("input_text");
   $input.value = _text1;
   form.append($input);
+
+  text_val2 = form.querySelector(".content_2");
+  if (text_val2) {
+    _val2 = format_text(text_val2);
+    $input_en = document.createElement("input");
+    $input_en.setAttribute("name", "description_en");
+    $input_en.setAttribute("type", "hidden");
+    $input_en.classList.add("input_text");
+    $input_en.value = _val2.innerHTML;
+    form.append($input_en);
+  }
   form_data = new FormData(form);
 
   link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
@@ -182,6 +193,10 @@ function send_serve_data(form, url) {
   _val1 = format_text(text_field);
   form_data = new FormData(form);
   form_data.append("description", _val1.innerHTML);
+  text_field_en = form.querySelector(".content_2");
+  if (text_field_en) {
+    form_data.append("description_en", format_text(text_field_en).innerHTML);
+  }
 
   link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
   link.open( 'POST', url, true );
@@ -246,8 +261,21 @@ on('body', 'click', '.remove_serve', function() {
   this.parentElement.remove();
 });
 on('body', 'click', '.remove_serve_category', function() {
-  delete_item("/delete_serve_category/" + this.getAttribute("data-pk") + "/");
-  this.parentElement.remove();
+  _this = this;
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', "/delete_serve_category/" + this.getAttribute("data-pk") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      _this.parentElement.remove();
+      toast_success("Удалено!");
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send();
 });
 on('body', 'click', '.remove_tech_category', function() {
   delete_item("/delete_tech_category/" + this.getAttribute("data-pk") + "/");
@@ -287,6 +315,378 @@ on('body', 'click', '.remove_tag', function() {
   this.parentElement.remove();
 });
 
+///////////TRASH //////////////////
+on('body', 'click', '.restore_item', function() {
+  delete_item("/restore_item/" + this.getAttribute("data-pk") + "/");
+  this.parentElement.remove();
+});
+on('body', 'click', '.restore_category', function() {
+  delete_item("/restore_category/" + this.getAttribute("data-pk") + "/");
+  this.parentElement.remove();
+});
+on('body', 'click', '.restore_tag', function() {
+  delete_item("/restore_tag/" + this.getAttribute("data-pk") + "/");
+  this.parentElement.remove();
+});
+on('body', 'click', '.restore_file', function() {
+  delete_item("/restore_file/" + this.getAttribute("data-pk") + "/");
+  this.parentElement.remove();
+});
+on('body', 'click', '.purge_trash', function() {
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/purge_trash/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText.indexOf("{") != 0) {
+      toast_error(link.responseText);
+      return
+    }
+    report = JSON.parse(link.responseText);
+    if (report.skipped_items.length) {
+      toast_info("Корзина очищена. Осталось элементов с заказами: " + report.skipped_items.length);
+    }
+    else {
+      toast_success("Корзина очищена!");
+    }
+  }};
+  link.send();
+});
+
+///////////ВАРИАНТЫ ТОВАРА //////////////////
+function send_variant_data(form, url) {
+  if (!form.querySelector('input[name="title"]').value) {
+    form.querySelector('input[name="title"]').style.setProperty('border', '1px #FF0000 solid', 'important');
+    return
+  }
+  else if (!form.querySelector('input[name="sku"]').value) {
+    form.querySelector('input[name="sku"]').style.setProperty('border', '1px #FF0000 solid', 'important');
+    return
+  }
+  form_data = new FormData(form);
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', url, true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+};
+on('body', 'click', '.create_variant', function() {
+  send_variant_data(this.closest(".variant_form"), "/create_variant/" + this.getAttribute("data-pk") + "/");
+});
+on('body', 'click', '.edit_variant', function() {
+  send_variant_data(this.closest(".variant_form"), "/edit_variant/" + this.getAttribute("data-pk") + "/");
+});
+on('body', 'click', '.remove_variant', function() {
+  _this = this;
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', "/delete_variant/" + this.getAttribute("data-pk") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      toast_success("Удалено!");
+      _this.closest(".variant_form").remove();
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send();
+});
+on('body', 'click', '.accept_order', function() {
+  _this = this;
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', "/accept_order/" + this.getAttribute("data-pk") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      toast_success("Заказ принят!");
+      _this.remove();
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send();
+});
+
+on('body', 'click', '.edit_work_case', function() {
+  form_data = new FormData(this.closest(".work_case_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/edit_work_case/" + this.getAttribute("data-pk") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    ajax_get_reload(window.location.pathname, false);
+  }};
+  link.send(form_data);
+});
+
+on('body', 'click', '.edit_wiki_parent', function() {
+  form_data = new FormData(this.closest(".wiki_parent_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/edit_wiki_parent/" + this.getAttribute("data-pk") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+
+on('body', 'click', '.edit_item_access', function() {
+  form_data = new FormData(this.closest(".item_access_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/edit_item_access/" + this.getAttribute("data-pk") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+
+// правила скидок и промокоды: url формы в data-url кнопки
+on('body', 'click', '.discount_form_btn', function() {
+  form_data = new FormData(this.closest(".discount_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+on('body', 'click', '.discount_disable_btn', function() {
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    ajax_get_reload(window.location.pathname, false);
+  }};
+  link.send();
+});
+on('body', 'click', '.currency_form_btn', function() {
+  form_data = new FormData(this.closest(".currency_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+on('body', 'click', '.user_group_form_btn', function() {
+  form_data = new FormData(this.closest(".user_group_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+on('body', 'click', '.user_group_delete_btn', function() {
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    ajax_get_reload(window.location.pathname, false);
+  }};
+  link.send();
+});
+on('body', 'click', '.timeline_form_btn', function() {
+  form_data = new FormData(this.closest(".timeline_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+on('body', 'click', '.holiday_delete_btn', function() {
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    ajax_get_reload(window.location.pathname, false);
+  }};
+  link.send();
+});
+on('body', 'click', '.serve_relation_form_btn', function() {
+  form_data = new FormData(this.closest(".serve_relation_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+on('body', 'click', '.serve_relation_delete_btn', function() {
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', this.getAttribute("data-url"), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    ajax_get_reload(window.location.pathname, false);
+  }};
+  link.send();
+});
+
+on('body', 'click', '.edit_tag_aliases', function() {
+  form_data = new FormData(this.closest(".tag_aliases_form"));
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/edit_tag_aliases/" + this.getAttribute("data-pk") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+on('body', 'click', '.merge_tag', function() {
+  form_data = new FormData(this.closest(".merge_tag_form"));
+  target = form_data.get("target");
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/merge_tag/" + this.getAttribute("data-pk") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      ajax_get_reload("/tag/" + target + "/", true);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send(form_data);
+});
+
+// теги в формах объектов: подсказки, выбор, новые теги по Enter
+function add_selected_tag(block, field, value, name) {
+  list = block.querySelector(".selected_tags");
+  inputs = list.querySelectorAll("input");
+  for (var i = 0; i < inputs.length; i++) {
+    if (inputs[i].name == field && inputs[i].value == value) {
+      return
+    }
+  };
+  span = document.createElement("span");
+  span.classList.add("selected_tag");
+  span.style.cursor = "pointer";
+  span.title = "Убрать";
+  input = document.createElement("input");
+  input.type = "hidden";
+  input.name = field;
+  input.value = value;
+  span.append(input);
+  span.append(document.createTextNode(" " + name + " \u00d7 "));
+  list.append(span);
+  block.querySelector(".tags_autocomplete_input").value = "";
+  block.querySelector(".tags_autocomplete_list").innerHTML = "";
+};
+on('body', 'input', '.tags_autocomplete_input', function() {
+  _this = this;
+  block = this.closest(".tags_autocomplete");
+  if (!_this.value.trim()) {
+    block.querySelector(".tags_autocomplete_list").innerHTML = "";
+    return
+  }
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', "/tags_autocomplete/?q=" + encodeURIComponent(_this.value.trim()), true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    tags = JSON.parse(link.responseText);
+    list = block.querySelector(".tags_autocomplete_list");
+    list.innerHTML = "";
+    for (var i = 0; i < tags.length; i++) {
+      a = document.createElement("a");
+      a.classList.add("tags_autocomplete_option");
+      a.style.cursor = "pointer";
+      a.setAttribute("data-pk", tags[i].id);
+      a.setAttribute("data-name", tags[i].name);
+      a.textContent = tags[i].name + " (" + tags[i].count + ") ";
+      list.append(a);
+    }
+  }};
+  link.send();
+});
+on('body', 'click', '.tags_autocomplete_option', function() {
+  add_selected_tag(this.closest(".tags_autocomplete"), "tags_list[]", this.getAttribute("data-pk"), this.getAttribute("data-name"));
+});
+on('body', 'keydown', '.tags_autocomplete_input', function(event) {
+  if (event.key != "Enter") {
+    return
+  }
+  event.preventDefault();
+  if (this.value.trim()) {
+    add_selected_tag(this.closest(".tags_autocomplete"), "new_tags[]", this.value.trim(), this.value.trim());
+  }
+});
+on('body', 'click', '.selected_tag', function() {
+  this.remove();
+});
+
 on('body', 'change', '.load_tech_categories_from_level', function() {
   val = this.value;
   next = this.parentElement.nextElementSibling;
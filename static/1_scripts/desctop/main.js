@@ -125,7 +125,7 @@ function get_custom_design() {
 function check_first_load() {
   span = document.body.querySelector(".span");
   loc = window.location.href;
-  if (loc.indexOf('template') > -1) {
+  if (loc.indexOf('?') > -1) {
     url = loc + "&ajax=1"; 
   }
   else {
@@ -143,6 +143,7 @@ function check_first_load() {
           span.innerHTML = elem_.innerHTML;
           get_or_create_cookie_user();
           get_active_button();
+          get_active_currency();
           get_page_view_time(120);
           scrolled(document.body.querySelector(".span"));
           window.history.pushState ({"url":loc}, document.title, loc);
@@ -153,7 +154,7 @@ function check_first_load() {
 
 function ajax_get_reload(url, history_enable) {
   var ajax_link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
-    ajax_link.open( 'GET', url + "?ajax=2", true );
+    ajax_link.open( 'GET', url + (url.indexOf('?') > -1 ? "&ajax=2" : "?ajax=2"), true );
     ajax_link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
     ajax_link.onreadystatechange = function () {
       if ( this.readyState == 4 && this.status == 200 ) {
@@ -189,6 +190,7 @@ function ajax_get_reload(url, history_enable) {
         document.querySelector('link[rel="canonical"]').setAttribute("href", _uri);
 
         window.scrollTo(0,0);
+        update_calculation();
         if (history_enable) {
           window.history.pushState ({"url":url}, $title, url);
         }
@@ -438,6 +438,51 @@ function create_fullscreen(url, type_class, price) {
   link.send();
 };
 
+// оценка сроков и поправки по связям опций - считает тот же калькулятор,
+// что и заказ (/api/calculate/)
+function update_calculation() {
+  block = document.body.querySelector(".total_timeline");
+  if (!block) {
+    return
+  };
+  tech_cats = [];
+  serves = [];
+  banner_blocks = document.body.querySelectorAll(".open_cat");
+  for (var i = 0; i < banner_blocks.length; i++) {
+    tech_cats.push(banner_blocks[i].getAttribute("data-tech"));
+    tab_pk = banner_blocks[i].querySelector(".price_mode .active").getAttribute("data-pk");
+    tab_panes = banner_blocks[i].querySelectorAll(".tab-pane");
+    for (var k = 0; k < tab_panes.length; k++) {
+      if (tab_panes[k].getAttribute("data-pk") == tab_pk) {
+        serves_list = tab_panes[k].querySelectorAll(".hover .get_serve_info");
+        for (var s = 0; s < serves_list.length; s++) {
+          serves.push(serves_list[s].getAttribute("data-pk"));
+        }
+      }
+    }
+  };
+  if (!tech_cats.length) {
+    return
+  };
+  timeline_link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  timeline_link.open( 'GET', "/api/calculate/?tech_cats=" + tech_cats.join(",") + "&serves=" + serves.join(","), true );
+  timeline_link.onreadystatechange = function () {
+  if ( timeline_link.readyState == 4 && timeline_link.status == 200 ) {
+    calculation = JSON.parse(timeline_link.responseText);
+    timeline = calculation.timeline;
+    block.innerHTML = "Срок: " + timeline.work_days + " раб. дн., ориентировочно до " + timeline.deadline.split("-").reverse().join(".");
+    notes_block = document.body.querySelector(".calculate_notes");
+    if (notes_block) {
+      notes = [];
+      for (var i = 0; i < calculation.notes.length; i++) {
+        notes.push(calculation.notes[i].text);
+      }
+      notes_block.innerHTML = notes.join("<br>");
+    }
+  }};
+  timeline_link.send();
+};
+
 function create_order_form(price) {
   try {
     document.body.querySelector(".price_section_block").style.display = "none";
@@ -817,6 +862,11 @@ on('body', 'click', '.select_serve', function(event) {
   }
 });
 
+// после обработчиков выбора опций и вкладок
+on('body', 'click', '.select_serve, .select_child_serve, .price_mode .yy', function() {
+  setTimeout(update_calculation, 0);
+});
+
 function service_tab_action(_this, tab_class) {
   is_price_mode = false;
   if (_this.parentElement.classList.contains("price_mode")) {
@@ -875,6 +925,57 @@ on('body', 'click', '.ajax', function(event) {
   ajax_get_reload(this.getAttribute("href"), true)
 });
 
+// сортировка и фильтры списка: собираем параметры формы и перезагружаем страницу
+on('body', 'click', '.list_filter_btn', function(event) {
+  event.preventDefault();
+  form = this.closest(".list_filter");
+  params = [];
+  sort = form.querySelector(".list_filter_sort").value;
+  if (sort != "created") {
+    params.push("sort=" + sort);
+  };
+  if (form.querySelector(".list_filter_order").value == "asc") {
+    params.push("order=asc");
+  };
+  min = form.querySelector(".list_filter_price_min");
+  if (min && min.value) {
+    params.push("price_min=" + parseInt(min.value));
+  };
+  max = form.querySelector(".list_filter_price_max");
+  if (max && max.value) {
+    params.push("price_max=" + parseInt(max.value));
+  };
+  industry = form.querySelector(".list_filter_industry");
+  if (industry && industry.value) {
+    params.push("industry=" + encodeURIComponent(industry.value));
+  };
+  year = form.querySelector(".list_filter_year");
+  if (year && year.value) {
+    params.push("year=" + parseInt(year.value));
+  };
+  tech = form.querySelector(".list_filter_tech");
+  if (tech && tech.value) {
+    params.push("tech=" + parseInt(tech.value));
+  };
+  tags = [];
+  checked = form.querySelectorAll(".list_filter_tag:checked");
+  for (var i = 0; i < checked.length; i++) {
+    tags.push(encodeURIComponent(checked[i].value));
+  };
+  if (tags.length) {
+    params.push("tags=" + tags.join(","));
+  };
+  url = window.location.pathname;
+  if (params.length) {
+    url += "?" + params.join("&");
+  };
+  ajax_get_reload(url, true)
+});
+on('body', 'click', '.list_filter_reset', function(event) {
+  event.preventDefault();
+  ajax_get_reload(window.location.pathname, true)
+});
+
 
 on('body', 'click', '.s_1', function() {
   service_tab_action(this, ".tab_1")
@@ -933,6 +1034,44 @@ on('body', 'click', '.anon_color_change', function() {
     setCookie("background", new_color, 90);
   }
 });
+// валюта посетителя - в куки currency, шапка рендерится без нее
+function get_active_currency() {
+  labels = document.body.querySelectorAll(".currency_label");
+  if (!labels.length) {
+    return
+  }
+  match = document.cookie.match(/(?:^|; )currency=([^;]*)/);
+  code = match ? match[1] : labels[0].getAttribute("data-default");
+  for (var i = 0; i < labels.length; i++) {
+    labels[i].innerHTML = code;
+  }
+  links = document.body.querySelectorAll(".set_currency");
+  for (var i = 0; i < links.length; i++) {
+    if (links[i].classList.contains("next_currency")) {
+      codes = links[i].getAttribute("data-codes").split(",");
+      links[i].setAttribute("data-code", codes[(codes.indexOf(code) + 1) % codes.length]);
+    }
+    else {
+      links[i].getAttribute("data-code") == code ? links[i].classList.add("active") : links[i].classList.remove("active");
+    }
+  }
+};
+on('body', 'click', '.set_currency', function() {
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', "/set_currency/" + this.getAttribute("data-code") + "/", true );
+  link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    if (link.responseText == "ok") {
+      get_active_currency();
+      ajax_get_reload(window.location.pathname, false);
+    }
+    else {
+      toast_error(link.responseText);
+    }
+  }};
+  link.send();
+});
 on('body', 'click', '.this_fullscreen_hide', function() {
   close_fullscreen()
 });
@@ -1128,6 +1267,8 @@ on('body', 'click', '#create_order_btn', function() {
     return
   }
 
+  _this = this;
+  btn_text = this.innerHTML;
   this.setAttribute("disable", "true");
   this.innerHTML = "Данные отправляются!";
   serves_input = "";
@@ -1149,15 +1290,135 @@ on('body', 'click', '#create_order_btn', function() {
   form_data.append("types", object_type);
   form_data.append("object_id", object_id);
   form_data.append("serve_list", serves_input);
+  variant = document.body.querySelector(".select_variant:checked");
+  if (variant) {
+    form_data.append("variant_id", variant.value);
+  };
 
   link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
   link.open( 'POST', "/create_order/", true );
   link.onreadystatechange = function () {
   if ( link.readyState == 4 && link.status == 200 ) {
+    // калькулятор поправил выбор по связям опций
+    if (link.responseText) {
+      toast_info(link.responseText);
+    }
     get_document_opacity_1();
     ajax_get_reload("/user_orders/", true);
+  }
+  else if ( link.readyState == 4 ) {
+    // например, промокод закончился - заказ не создан
+    toast_error(link.responseText);
+    _this.removeAttribute("disable");
+    _this.innerHTML = btn_text;
+  }};
+  link.send(form_data);
+});
+
+on('body', 'click', '.add_to_cart', function() {
+  block = this.closest(".cart_block");
+  form_data = new FormData();
+  form_data.append("item_id", this.getAttribute("data-pk"));
+  form_data.append("count", block.querySelector(".cart_count").value);
+  variant = document.body.querySelector(".select_variant:checked");
+  if (variant) {
+    form_data.append("variant_id", variant.value);
+  };
+
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/add_to_cart/", true );
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 ) {
+    if ( link.status == 200 ) {
+      summary = JSON.parse(link.responseText);
+      toast_success("Товар в корзине. Всего: " + summary.count + " шт., " + summary.total + " ₽");
+    }
+    else {
+      toast_error(JSON.parse(link.responseText));
+    }
+  }};
+  link.send(form_data);
+});
+
+on('body', 'change', '.change_cart_count', function() {
+  form_data = new FormData();
+  form_data.append("count", this.value);
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/change_cart_count/" + this.getAttribute("data-pk") + "/", true );
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    ajax_get_reload("/cart/", true);
+  }};
+  link.send(form_data);
+});
+
+on('body', 'click', '.remove_from_cart', function() {
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'GET', "/remove_from_cart/" + this.getAttribute("data-pk") + "/", true );
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    ajax_get_reload("/cart/", true);
+  }};
+  link.send();
+});
+
+on('body', 'click', '#checkout_btn', function() {
+  form = this.parentElement;
+  if (!form.querySelector("#id_username").value) {
+    form.querySelector("#id_username").style.setProperty('border', '1px #FF0000 solid', 'important');
+    return
+  }
+  else if (!form.querySelector("#id_email").value) {
+    form.querySelector("#id_email").style.setProperty('border', '1px #FF0000 solid', 'important');
+    return
+  }
+  _this = this;
+  _this.setAttribute("disable", "true");
+  _this.innerHTML = "Данные отправляются!";
+  form_data = new FormData(form);
+
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/checkout/", true );
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 ) {
+    if ( link.status == 200 ) {
+      ajax_get_reload("/user_orders/", true);
+    }
+    else {
+      _this.removeAttribute("disable");
+      _this.innerHTML = "Оформить заказ";
+      toast_error(JSON.parse(link.responseText));
+    }
+  }};
+  link.send(form_data);
+});
+
+function send_help_vote(block, value) {
+  form_data = new FormData();
+  form_data.append("is_helpful", value);
+  comment = block.querySelector("textarea");
+  if (value == "0" && comment.value) {
+    form_data.append("comment", comment.value);
+  };
+  link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
+  link.open( 'POST', "/help_vote/" + block.getAttribute("data-pk") + "/", true );
+  link.onreadystatechange = function () {
+  if ( link.readyState == 4 && link.status == 200 ) {
+    block.innerHTML = "<small>Спасибо за оценку!</small>";
   }};
   link.send(form_data);
+};
+on('body', 'click', '.help_vote_btn', function() {
+  block = this.closest(".help_vote");
+  if (this.getAttribute("data-value") == "1") {
+    send_help_vote(block, "1");
+  }
+  else {
+    block.querySelector(".help_vote_comment").classList.remove("hide");
+  }
+});
+on('body', 'click', '.help_vote_send', function() {
+  send_help_vote(this.closest(".help_vote"), "0");
 });
 
 on('body', 'click', '.remove_order', function() {
@@ -42,7 +42,7 @@ function get_custom_design() {
 function check_first_load() {
   span = document.body.querySelector(".span");
   loc = window.location.href;
-  if (loc.indexOf('template') > -1) {
+  if (loc.indexOf('?') > -1) {
     url = loc + "&ajax=1"; 
   }
   else {
@@ -71,6 +71,7 @@ function check_first_load() {
         get_or_create_cookie_user();
         get_page_view_time(120);
         get_active_button();
+        get_active_currency();
         scrolled(document.body.querySelector(".span"));
 
       }
@@ -79,12 +80,13 @@ function check_first_load() {
   }
   else {
     get_active_button();
+    get_active_currency();
   }
 }
 
 function ajax_get_reload(url, history_enable) {
   var ajax_link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
-    ajax_link.open( 'GET', url + "?ajax=2", true );
+    ajax_link.open( 'GET', url + (url.indexOf('?') > -1 ? "&ajax=2" : "?ajax=2"), true );
     ajax_link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
     ajax_link.onreadystatechange = function () {
       if ( this.readyState == 4 && this.status == 200 ) {
@@ -106,6 +108,7 @@ function ajax_get_reload(url, history_enable) {
         sidebar = elem_.querySelector(".sidebar");
         rtr.innerHTML = elem_.innerHTML;
         window.scrollTo(0,0);
+        update_calculation();
         document.title = rtr.querySelector(".doc_title").getAttribute("data-title");
         if (history_enable) {
           window.history.pushState ({"url":url}, $title, url);
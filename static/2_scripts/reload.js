@@ -74,7 +74,7 @@ function get_background_color() {
 
 function check_first_load() {
   loc = window.location.href;
-  if (loc.indexOf('template') > -1) {
+  if (loc.indexOf('?') > -1) {
     url = loc + "&ajax=1"; 
   }
   else {
@@ -100,7 +100,7 @@ function check_first_load() {
 
   function ajax_get_reload(url, history_enable) {
     var ajax_link = window.XMLHttpRequest ? new XMLHttpRequest() : new ActiveXObject( 'Microsoft.XMLHTTP' );
-      ajax_link.open( 'GET', url + "?ajax=2", true );
+      ajax_link.open( 'GET', url + (url.indexOf('?') > -1 ? "&ajax=2" : "?ajax=2"), true );
       ajax_link.setRequestHeader('X-Requested-With', 'XMLHttpRequest');
       ajax_link.onreadystatechange = function () {
         if ( this.readyState == 4 && this.status == 200 ) {